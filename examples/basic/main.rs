@@ -5,8 +5,9 @@ extern crate color;
 extern crate math;
 extern crate util;
 
+use std::collections::BTreeMap;
+
 const BACKGROUND: color::NormalizedRGBA = color::NormalizedRGBA(0.46,0.62,0.8,1.0);
-const MODEL_DATA: &'static str = include_str!("../../res/unit_cube.obj");
 const VERTEX_SHADER: &'static str = include_str!("../../res/vertex.glsl");
 const FRAGMENT_SHADER: &'static str = include_str!("../../res/fragment.glsl");
 
@@ -21,12 +22,12 @@ pub struct Vertex {
     pub normal: math::Vector3,
 }
 
-impl From<geom::formats::wavefront::Vertex> for Vertex
+impl From<geom::voxel::Vertex<Block>> for Vertex
 {
-    fn from(val: geom::formats::wavefront::Vertex) -> Vertex {
+    fn from(val: geom::voxel::Vertex<Block>) -> Vertex {
         Vertex {
             position: val.position,
-            normal: val.normal.expect("expected a normal"),
+            normal: val.normal,
         }
     }
 }
@@ -46,94 +47,36 @@ pub struct Context
     device: gfx::gl::Device<gfx::gl::backends::glfw::Backend>,
 
     program: gfx::gl::Program,
-    mesh: gfx::gl::mesh::Data,
 
     clock: f32,
 
-    chunk: Chunk,
+    world: geom::voxel::Grid<Block>,
+    chunk_meshes: BTreeMap<geom::voxel::ChunkPos, gfx::gl::mesh::Data>,
 
     camera_pos: math::Vector3,
     rot: math::Vector3,
     timer: util::Timer,
 }
 
-pub struct Chunk
+#[derive(Copy,Clone,PartialEq)]
+pub enum Block
 {
-    blocks: [[[Block; 15]; 15]; 15],
+    Square((f32,f32,f32)),
+    Empty,
 }
 
-impl Chunk
+impl geom::voxel::Block for Block
 {
-    pub fn from_fn<F>(f: F) -> Self
-        where F: Fn(u32,u32,u32) -> Block {
-        let mut blocks = [[[Block::Empty; 15]; 15]; 15];
-
-        for x in 0..15 {
-            for y in 0..15 {
-                for z in 0..15 {
-                    blocks[x as usize][y as usize][z as usize] = f(x,y,z);
-                }
-            }
-        }
-
-        Chunk {
-            blocks: blocks,
-        }
+    fn empty() -> Block { Block::Empty }
 
+    fn is_empty(&self) -> bool {
+        *self == Block::Empty
     }
-
-    pub fn set(&mut self, (x,y,z): (u32,u32,u32), block: Block) {
-        self.blocks[x as usize][y as usize][z as usize] = block;
-    }
-
-    pub fn get(&mut self, (x,y,z): (u32,u32,u32)) -> Block {
-        self.blocks[x as usize][y as usize][z as usize]
-    }
-
-    pub fn render(&self, context: &Context, canvas: &mut gfx::gl::Canvas) {
-        use math::Matrix;
-        for (xi,a) in self.blocks.iter().enumerate() {
-            for (yi,b) in a.iter().enumerate() {
-                for (zi,block) in b.iter().enumerate() {
-
-                    match *block {
-                        Block::Square((_,_,_)) => {
-                            let x = xi as f32 * BLOCK_SIZE;
-                            let y = yi as f32 * BLOCK_SIZE;
-                            let z = zi as f32 * BLOCK_SIZE;
-                            let transform = geom::Transform3::identity()
-                                           .scale(math::Vector3(BLOCK_SIZE,BLOCK_SIZE,BLOCK_SIZE))
-                                           .translate(math::Vector3(x,y,z));
-
-                            context.program.uniform("modelTransform").set(transform);
-
-                            canvas.draw_mesh(&context.mesh, &context.program);
-                        },
-                        Block::Empty => { },
-                    }
-                }
-            }
-        }
-        context.program.uniform("modelTransform").set(geom::Transform3::identity());
-    }
-}
-
-#[derive(Copy,Clone)]
-pub enum Block
-{
-    Square((f32,f32,f32)),
-    Empty,
 }
 
 impl Context
 {
     pub fn new() -> Self {
-        use geom::formats::Format;
-        use std;
-
-        let mesh_cursor = std::io::Cursor::new(MODEL_DATA.as_bytes());
-        let mesh_data: geom::mesh::StaticData<Index,Vertex> = geom::formats::Wavefront::load(mesh_cursor);
-
         let backend = gfx::gl::backends::glfw::Backend::new();
         let mut device = gfx::gl::Device::new(backend);
 
@@ -156,28 +99,43 @@ impl Context
         device.set_culling_mode(gfx::CullingMode::Back);
         device.set_cursor_visible(false);
 
-        let mesh = device.load_mesh_data(&mesh_data);
-
         let light_pos = math::Vector3(0.886,1.0,0.);
         program.uniform("lightPosition").set(light_pos);
 
-        let chunk = Chunk::from_fn(|x,y,z| {
-            if (x+z)%2 == 0 {
-                Block::Square( (1.0/x as f32, 1.0/y as f32, 1.0/z as f32) )
-            } else {
-                Block::Empty
+        let mut world = geom::voxel::Grid::new(BLOCK_SIZE);
+
+        for x in 0..15 {
+            for y in 0..15 {
+                for z in 0..15 {
+                    if (x+z)%2 == 0 {
+                        world.set((x,y,z), Block::Square( (1.0/x as f32, 1.0/y as f32, 1.0/z as f32) ));
+                    }
+                }
             }
-        });
+        }
 
-        Context {
+        let mut context = Context {
             device: device,
             program: program,
-            mesh: mesh,
             clock: 0.0,
-            chunk: chunk,
+            world: world,
+            chunk_meshes: BTreeMap::new(),
             camera_pos: math::Vector3(0.,0.,0.),
             rot: math::Vector3(0.,0.,0.),
             timer: util::Timer::new(),
+        };
+
+        context.update_meshes();
+        context
+    }
+
+    /// Regenerates the meshes of any chunks which have been edited.
+    fn update_meshes(&mut self) {
+        for pos in self.world.take_dirty() {
+            let data: geom::mesh::StaticData<Index,Vertex> = self.world.mesh_chunk(pos);
+            let mesh = self.device.load_mesh_data(&data);
+
+            self.chunk_meshes.insert(pos, mesh);
         }
     }
 
@@ -192,6 +150,7 @@ impl Context
         }
         let delta = self.timer.mark();
         self.step(delta);
+        self.update_meshes();
 
         let mut canvas = self.device.begin();
         self.render(&mut canvas);
@@ -261,18 +220,24 @@ impl Context
 
         canvas.clear();
 
-        let camera_transform = geom::Transform3::identity()
-                               .scale(math::Vector3(0.5,0.5,0.5))
-                               .translate(self.camera_pos)
-                               .rotate(self.rot);
-
-
-
-        let projection = geom::Transform3::perspective(45.0, 0., 1000.0, canvas.viewport().aspect());
-        let transform = projection*camera_transform;
+        // The camera is rotated by its pitch and then its yaw.
+        let (pitch, yaw) = (self.rot.0, self.rot.1);
+        let forward = math::Vector3(pitch.cos() * yaw.sin(), pitch.sin(), -pitch.cos() * yaw.cos());
+
+        let view = geom::Transform3::look_at(self.camera_pos, self.camera_pos + forward,
+                                             math::Vector3(0.0, 1.0, 0.0));
+        let projection = geom::Transform3::perspective_fov(std::f32::consts::FRAC_PI_4,
+                                                          canvas.viewport().aspect(),
+                                                          0.1, 1000.0,
+                                                          geom::DepthRange::NegativeOneToOne);
+        let transform = projection * view;
         self.program.uniform("worldTransform").set(transform);
 
-        self.chunk.render(self, canvas);
+        self.program.uniform("modelTransform").set(geom::Transform3::identity());
+
+        for mesh in self.chunk_meshes.values() {
+            canvas.draw_mesh(mesh, &self.program);
+        }
     }
 }
 
@@ -0,0 +1,261 @@
+//! Compositing and blend modes.
+//!
+//! These work on premultiplied colours, as a rasteriser should store
+//! them. Each operation that fixed function blending can perform has a
+//! `BlendState`, so the same result can be had from the GPU.
+
+use NormalizedRGBA;
+#[cfg(test)]
+use assert_close;
+use num::Decimal;
+
+/// A factor which a colour is multiplied by before the source and
+/// destination are added together.
+#[derive(Copy,Clone,Debug,PartialEq,Eq)]
+pub enum Factor
+{
+    Zero,
+    One,
+    SourceColor,
+    OneMinusSourceColor,
+    DestinationColor,
+    OneMinusDestinationColor,
+    SourceAlpha,
+    OneMinusSourceAlpha,
+    DestinationAlpha,
+    OneMinusDestinationAlpha,
+}
+
+/// Fixed function blending, which calculates
+/// `source * source_factor + destination * destination_factor`.
+#[derive(Copy,Clone,Debug,PartialEq,Eq)]
+pub struct BlendState
+{
+    pub source: Factor,
+    pub destination: Factor,
+}
+
+/// A Porter-Duff compositing operator.
+#[derive(Copy,Clone,Debug,PartialEq,Eq)]
+pub enum Composite
+{
+    Clear,
+    Source,
+    Destination,
+    SourceOver,
+    DestinationOver,
+    SourceIn,
+    DestinationIn,
+    SourceOut,
+    DestinationOut,
+    SourceAtop,
+    DestinationAtop,
+    Xor,
+}
+
+/// A blend mode, which mixes the colours where a source overlaps
+/// the destination.
+#[derive(Copy,Clone,Debug,PartialEq,Eq)]
+pub enum BlendMode
+{
+    /// The source is placed over the destination.
+    Normal,
+    /// Multiplies the colours, which always darkens.
+    Multiply,
+    /// Multiplies the inverted colours, which always lightens.
+    Screen,
+    /// Multiplies dark destinations and screens light ones,
+    /// which increases contrast.
+    Overlay,
+    /// Adds the colours, as light does.
+    Additive,
+}
+
+impl Factor
+{
+    /// Gets the factor for one component, given the source and
+    /// destination values of that component and their alphas.
+    fn evaluate<T: Decimal>(self, source: T, destination: T, source_alpha: T, destination_alpha: T) -> T {
+        match self {
+            Factor::Zero => T::zero(),
+            Factor::One => T::one(),
+            Factor::SourceColor => source,
+            Factor::OneMinusSourceColor => T::one() - source,
+            Factor::DestinationColor => destination,
+            Factor::OneMinusDestinationColor => T::one() - destination,
+            Factor::SourceAlpha => source_alpha,
+            Factor::OneMinusSourceAlpha => T::one() - source_alpha,
+            Factor::DestinationAlpha => destination_alpha,
+            Factor::OneMinusDestinationAlpha => T::one() - destination_alpha,
+        }
+    }
+}
+
+impl BlendState
+{
+    pub fn new(source: Factor, destination: Factor) -> Self {
+        BlendState {
+            source: source,
+            destination: destination,
+        }
+    }
+
+    /// Blends two colours as the GPU would.
+    pub fn apply<T: Decimal>(self, source: NormalizedRGBA<T>, destination: NormalizedRGBA<T>) -> NormalizedRGBA<T> {
+        let (source_alpha, destination_alpha) = (source.3, destination.3);
+        let blend = |s: T, d: T| {
+            s * self.source.evaluate(s, d, source_alpha, destination_alpha) +
+                d * self.destination.evaluate(s, d, source_alpha, destination_alpha)
+        };
+
+        source.zip(destination, blend)
+    }
+}
+
+impl Composite
+{
+    /// Gets the blend state which performs the operator.
+    pub fn blend_state(self) -> BlendState {
+        let (source, destination) = match self {
+            Composite::Clear => (Factor::Zero, Factor::Zero),
+            Composite::Source => (Factor::One, Factor::Zero),
+            Composite::Destination => (Factor::Zero, Factor::One),
+            Composite::SourceOver => (Factor::One, Factor::OneMinusSourceAlpha),
+            Composite::DestinationOver => (Factor::OneMinusDestinationAlpha, Factor::One),
+            Composite::SourceIn => (Factor::DestinationAlpha, Factor::Zero),
+            Composite::DestinationIn => (Factor::Zero, Factor::SourceAlpha),
+            Composite::SourceOut => (Factor::OneMinusDestinationAlpha, Factor::Zero),
+            Composite::DestinationOut => (Factor::Zero, Factor::OneMinusSourceAlpha),
+            Composite::SourceAtop => (Factor::DestinationAlpha, Factor::OneMinusSourceAlpha),
+            Composite::DestinationAtop => (Factor::OneMinusDestinationAlpha, Factor::SourceAlpha),
+            Composite::Xor => (Factor::OneMinusDestinationAlpha, Factor::OneMinusSourceAlpha),
+        };
+
+        BlendState::new(source, destination)
+    }
+}
+
+impl BlendMode
+{
+    /// Gets the closest blend state to the mode, if there is one.
+    ///
+    /// Normal, screen and additive blending are exact. Multiplying is
+    /// only exact over an opaque destination, and overlay can not be
+    /// done with fixed function blending.
+    pub fn blend_state(self) -> Option<BlendState> {
+        match self {
+            BlendMode::Normal => Some(Composite::SourceOver.blend_state()),
+            BlendMode::Multiply => Some(BlendState::new(Factor::DestinationColor, Factor::OneMinusSourceAlpha)),
+            BlendMode::Screen => Some(BlendState::new(Factor::One, Factor::OneMinusSourceColor)),
+            BlendMode::Overlay => None,
+            BlendMode::Additive => Some(BlendState::new(Factor::One, Factor::One)),
+        }
+    }
+
+    /// Mixes a pair of straight colour components.
+    fn mix<T: Decimal>(self, source: T, destination: T) -> T {
+        let two = T::constant(2.0);
+
+        match self {
+            BlendMode::Normal => source,
+            BlendMode::Multiply => source * destination,
+            BlendMode::Screen => source + destination - source * destination,
+            BlendMode::Overlay => {
+                if destination <= T::constant(0.5) {
+                    two * source * destination
+                } else {
+                    T::one() - two * (T::one() - source) * (T::one() - destination)
+                }
+            },
+            BlendMode::Additive => source + destination,
+        }
+    }
+}
+
+/// Composites a premultiplied source colour onto a premultiplied
+/// destination colour with a Porter-Duff operator.
+pub fn composite<T: Decimal>(source: NormalizedRGBA<T>,
+                             destination: NormalizedRGBA<T>,
+                             operator: Composite) -> NormalizedRGBA<T> {
+    operator.blend_state().apply(source, destination)
+}
+
+/// Blends a premultiplied source colour onto a premultiplied
+/// destination colour.
+///
+/// Where both are opaque the colours are mixed by the mode, and
+/// elsewhere they are composited as if the source is placed over
+/// the destination. Additive blending is clamped.
+pub fn blend<T: Decimal>(source: NormalizedRGBA<T>,
+                         destination: NormalizedRGBA<T>,
+                         mode: BlendMode) -> NormalizedRGBA<T> {
+    if mode == BlendMode::Additive {
+        return (source + destination).clamp();
+    }
+
+    let (source_alpha, destination_alpha) = (source.3, destination.3);
+    let (straight_source, straight_destination) = (source.unpremultiply(), destination.unpremultiply());
+    let both = source_alpha * destination_alpha;
+
+    let color = |s: T, d: T, straight_s: T, straight_d: T| {
+        s * (T::one() - destination_alpha) + d * (T::one() - source_alpha) +
+            both * mode.mix(straight_s, straight_d)
+    };
+
+    NormalizedRGBA(color(source.0, destination.0, straight_source.0, straight_destination.0),
+                   color(source.1, destination.1, straight_source.1, straight_destination.1),
+                   color(source.2, destination.2, straight_source.2, straight_destination.2),
+                   source_alpha + destination_alpha - both)
+}
+
+#[test]
+fn test_porter_duff() {
+    let source = NormalizedRGBA(1.0f64, 0.0, 0.0, 0.5).premultiply();
+    let destination = NormalizedRGBA(0.0f64, 0.0, 1.0, 1.0);
+
+    assert_close(composite(source, destination, Composite::SourceOver), NormalizedRGBA(0.5, 0.0, 0.5, 1.0));
+    assert_close(composite(source, destination, Composite::DestinationOver), destination);
+    assert_close(composite(source, destination, Composite::SourceIn), source);
+    assert_close(composite(source, destination, Composite::SourceOut), NormalizedRGBA(0.0, 0.0, 0.0, 0.0));
+    assert_close(composite(source, destination, Composite::DestinationOut), NormalizedRGBA(0.0, 0.0, 0.5, 0.5));
+    assert_close(composite(source, destination, Composite::SourceAtop), NormalizedRGBA(0.5, 0.0, 0.5, 1.0));
+    assert_close(composite(source, destination, Composite::Xor), NormalizedRGBA(0.0, 0.0, 0.5, 0.5));
+    assert_close(composite(source, destination, Composite::Clear), NormalizedRGBA(0.0, 0.0, 0.0, 0.0));
+}
+
+#[test]
+fn test_blend_modes() {
+    let source = NormalizedRGBA(0.5f64, 0.25, 1.0, 1.0);
+    let destination = NormalizedRGBA(0.5f64, 1.0, 0.25, 1.0);
+
+    assert_close(blend(source, destination, BlendMode::Normal), source);
+    assert_close(blend(source, destination, BlendMode::Multiply), NormalizedRGBA(0.25, 0.25, 0.25, 1.0));
+    assert_close(blend(source, destination, BlendMode::Screen), NormalizedRGBA(0.75, 1.0, 1.0, 1.0));
+    assert_close(blend(source, destination, BlendMode::Overlay), NormalizedRGBA(0.5, 1.0, 0.5, 1.0));
+    assert_close(blend(source, destination, BlendMode::Additive), NormalizedRGBA(1.0, 1.0, 1.0, 1.0));
+
+    // A transparent source leaves the destination alone.
+    let clear = NormalizedRGBA(0.0f64, 0.0, 0.0, 0.0);
+    for &mode in [BlendMode::Multiply, BlendMode::Screen, BlendMode::Overlay].iter() {
+        assert_close(blend(clear, destination, mode), destination);
+    }
+}
+
+#[test]
+fn test_blend_state_matches() {
+    let source = NormalizedRGBA(0.2f64, 0.9, 0.4, 0.6).premultiply();
+    let destinations = [NormalizedRGBA(0.7f64, 0.1, 0.5, 0.3).premultiply(),
+                        NormalizedRGBA(0.7f64, 0.1, 0.5, 1.0)];
+
+    for &destination in destinations.iter() {
+        for &mode in [BlendMode::Normal, BlendMode::Screen].iter() {
+            assert_close(mode.blend_state().unwrap().apply(source, destination),
+                         blend(source, destination, mode));
+        }
+    }
+
+    // Multiplying matches over an opaque destination.
+    assert_close(BlendMode::Multiply.blend_state().unwrap().apply(source, destinations[1]),
+                 blend(source, destinations[1], BlendMode::Multiply));
+    assert!(BlendMode::Overlay.blend_state().is_none());
+}
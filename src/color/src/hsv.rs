@@ -0,0 +1,121 @@
+//! Cylindrical representations of sRGB.
+
+use {Color,NormalizedRGBA,wrap_hue};
+use num::{self,Decimal};
+
+/// A colour as hue, saturation, value and alpha.
+///
+/// The hue is in degrees, from 0 to 360, and the other components are
+/// from 0 to 1. This works directly on sRGB encoded components, as
+/// colour pickers do.
+#[derive(Copy,Clone,Debug,PartialEq)]
+pub struct HSVA<T: Decimal = f32>(pub T, pub T, pub T, pub T);
+
+/// A colour as hue, saturation, lightness and alpha.
+///
+/// The hue is in degrees, from 0 to 360, and the other components are
+/// from 0 to 1.
+#[derive(Copy,Clone,Debug,PartialEq)]
+pub struct HSLA<T: Decimal = f32>(pub T, pub T, pub T, pub T);
+
+impl<T: Decimal> Color for HSVA<T> { }
+impl<T: Decimal> Color for HSLA<T> { }
+
+impl<T: Decimal> From<NormalizedRGBA<T>> for HSVA<T>
+{
+    fn from(rgba: NormalizedRGBA<T>) -> HSVA<T> {
+        let (hue, max, min) = hue_max_min(rgba);
+        let saturation = if max.is_zero() { T::zero() } else { (max - min) / max };
+
+        HSVA(hue, saturation, max, rgba.3)
+    }
+}
+
+impl<T: Decimal> From<HSVA<T>> for NormalizedRGBA<T>
+{
+    fn from(HSVA(hue,saturation,value,alpha): HSVA<T>) -> NormalizedRGBA<T> {
+        let chroma = value * saturation;
+        from_hue_chroma(hue, chroma, value - chroma, alpha)
+    }
+}
+
+impl<T: Decimal> From<NormalizedRGBA<T>> for HSLA<T>
+{
+    fn from(rgba: NormalizedRGBA<T>) -> HSLA<T> {
+        let (hue, max, min) = hue_max_min(rgba);
+        let lightness = (max + min) / T::constant(2.0);
+
+        let spread = T::one() - (lightness * T::constant(2.0) - T::one()).abs();
+        let saturation = if spread.is_zero() { T::zero() } else { (max - min) / spread };
+
+        HSLA(hue, saturation, lightness, rgba.3)
+    }
+}
+
+impl<T: Decimal> From<HSLA<T>> for NormalizedRGBA<T>
+{
+    fn from(HSLA(hue,saturation,lightness,alpha): HSLA<T>) -> NormalizedRGBA<T> {
+        let chroma = (T::one() - (lightness * T::constant(2.0) - T::one()).abs()) * saturation;
+        from_hue_chroma(hue, chroma, lightness - chroma / T::constant(2.0), alpha)
+    }
+}
+
+/// Gets the hue, and the largest and smallest components.
+/// Greys have a hue of zero.
+fn hue_max_min<T: Decimal>(NormalizedRGBA(r,g,b,_): NormalizedRGBA<T>) -> (T,T,T) {
+    let max = if r > g { r } else { g };
+    let max = if b > max { b } else { max };
+    let min = if r < g { r } else { g };
+    let min = if b < min { b } else { min };
+    let delta = max - min;
+
+    let sector = if delta.is_zero() {
+        T::zero()
+    } else if max == r {
+        (g - b) / delta
+    } else if max == g {
+        (b - r) / delta + T::constant(2.0)
+    } else {
+        (r - g) / delta + T::constant(4.0)
+    };
+
+    (wrap_hue(sector * T::constant(60.0)), max, min)
+}
+
+/// Builds a colour from its hue and chroma, adding `offset` to
+/// every component.
+fn from_hue_chroma<T: Decimal>(hue: T, chroma: T, offset: T, alpha: T) -> NormalizedRGBA<T> {
+    let sector = wrap_hue(hue) / T::constant(60.0);
+    let second = chroma * (T::one() - (sector % T::constant(2.0) - T::one()).abs());
+    let zero = T::zero();
+
+    let (r,g,b) = match num::cast::<T,i32>(sector.floor()) {
+        0 => (chroma, second, zero),
+        1 => (second, chroma, zero),
+        2 => (zero, chroma, second),
+        3 => (zero, second, chroma),
+        4 => (second, zero, chroma),
+        _ => (chroma, zero, second),
+    };
+
+    NormalizedRGBA(r + offset, g + offset, b + offset, alpha)
+}
+
+#[test]
+fn test_hsv_conversion() {
+    let orange = NormalizedRGBA(1.0f64, 0.5, 0.0, 0.25);
+
+    assert_eq!(HSVA::from(orange), HSVA(30.0, 1.0, 1.0, 0.25));
+    assert_eq!(HSLA::from(orange), HSLA(30.0, 1.0, 0.5, 0.25));
+    assert_eq!(NormalizedRGBA::from(HSVA(30.0, 1.0, 1.0, 0.25)), orange);
+    assert_eq!(NormalizedRGBA::from(HSLA(30.0, 1.0, 0.5, 0.25)), orange);
+
+    let grey = NormalizedRGBA(0.5f64, 0.5, 0.5, 1.0);
+    assert_eq!(HSVA::from(grey), HSVA(0.0, 0.0, 0.5, 1.0));
+    assert_eq!(HSLA::from(grey), HSLA(0.0, 0.0, 0.5, 1.0));
+
+    // Hues wrap around, and magenta is on the far side of zero.
+    let magenta = NormalizedRGBA(1.0f64, 0.0, 1.0, 1.0);
+    assert_eq!(HSVA::from(magenta).0, 300.0);
+    assert_eq!(NormalizedRGBA::from(HSVA(-60.0, 1.0, 1.0, 1.0)), magenta);
+}
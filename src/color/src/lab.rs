@@ -0,0 +1,245 @@
+//! The CIE L*a*b* colour space, and its cylindrical form.
+
+use {Color,NormalizedRGBA,XYZA,wrap_hue};
+use num::Decimal;
+
+/// A colour in CIE L*a*b*, relative to the D65 white point, with alpha.
+///
+/// Lightness is from 0 to 100, and `a` and `b` are roughly -128 to 128.
+/// Equal distances are meant to look like equal differences in colour.
+#[derive(Copy,Clone,Debug,PartialEq)]
+pub struct LabA<T: Decimal = f32>(pub T, pub T, pub T, pub T);
+
+/// A colour in CIE LCh, the cylindrical form of L*a*b*, with alpha.
+///
+/// Lightness is from 0 to 100, chroma is the distance from grey, and
+/// the hue is in degrees from 0 to 360.
+#[derive(Copy,Clone,Debug,PartialEq)]
+pub struct LChA<T: Decimal = f32>(pub T, pub T, pub T, pub T);
+
+impl<T: Decimal> Color for LabA<T> { }
+impl<T: Decimal> Color for LChA<T> { }
+
+impl<T: Decimal> LabA<T>
+{
+    pub fn lerp(self, other: Self, t: T) -> Self {
+        let lerp = |a: T, b: T| a + (b - a) * t;
+        LabA(lerp(self.0, other.0), lerp(self.1, other.1), lerp(self.2, other.2), lerp(self.3, other.3))
+    }
+
+    /// The CIE76 colour difference, which is the straight line distance.
+    pub fn delta_e_76(self, other: Self) -> T {
+        let (dl, da, db) = (self.0 - other.0, self.1 - other.1, self.2 - other.2);
+        (dl*dl + da*da + db*db).sqrt()
+    }
+
+    /// The CIEDE2000 colour difference, which corrects the
+    /// distances of Lab where they disagree with perception.
+    pub fn delta_e_2000(self, other: Self) -> T {
+        let c = |x: f64| T::constant(x);
+        let (LabA(l1,a1,b1,_), LabA(l2,a2,b2,_)) = (self, other);
+
+        // How close the chroma is to 25^7 decides how much to boost a*.
+        let pow7 = |x: T| x.powi(7);
+        let chroma_weight = |chroma: T| (pow7(chroma) / (pow7(chroma) + pow7(c(25.0)))).sqrt();
+
+        let mean_chroma = ((a1*a1 + b1*b1).sqrt() + (a2*a2 + b2*b2).sqrt()) / c(2.0);
+        let g = (T::one() - chroma_weight(mean_chroma)) / c(2.0);
+
+        let (a1, a2) = (a1 * (T::one() + g), a2 * (T::one() + g));
+        let (c1, c2) = ((a1*a1 + b1*b1).sqrt(), (a2*a2 + b2*b2).sqrt());
+        let hue = |b: T, a: T| if a.is_zero() && b.is_zero() { T::zero() } else { wrap_hue(b.atan2(a).to_degrees()) };
+        let (h1, h2) = (hue(b1, a1), hue(b2, a2));
+
+        let neither_grey = !(c1 * c2).is_zero();
+        let hue_difference = h2 - h1;
+        let hue_difference = if !neither_grey {
+            T::zero()
+        } else if hue_difference > c(180.0) {
+            hue_difference - c(360.0)
+        } else if hue_difference < c(-180.0) {
+            hue_difference + c(360.0)
+        } else {
+            hue_difference
+        };
+
+        let delta_l = l2 - l1;
+        let delta_c = c2 - c1;
+        let delta_h = c(2.0) * (c1 * c2).sqrt() * (hue_difference.to_radians() / c(2.0)).sin();
+
+        let mean_l = (l1 + l2) / c(2.0);
+        let mean_c = (c1 + c2) / c(2.0);
+        let mean_h = if !neither_grey {
+            h1 + h2
+        } else if (h1 - h2).abs() <= c(180.0) {
+            (h1 + h2) / c(2.0)
+        } else if h1 + h2 < c(360.0) {
+            (h1 + h2 + c(360.0)) / c(2.0)
+        } else {
+            (h1 + h2 - c(360.0)) / c(2.0)
+        };
+
+        let cos = |degrees: T| degrees.to_radians().cos();
+        let t = T::one() - c(0.17) * cos(mean_h - c(30.0)) + c(0.24) * cos(mean_h * c(2.0))
+              + c(0.32) * cos(mean_h * c(3.0) + c(6.0)) - c(0.20) * cos(mean_h * c(4.0) - c(63.0));
+
+        let rotation = (mean_h - c(275.0)) / c(25.0);
+        let rotation = c(30.0) * (T::zero() - rotation * rotation).exp();
+        let r_t = T::zero() - c(2.0) * chroma_weight(mean_c) * (rotation * c(2.0)).to_radians().sin();
+
+        let l50 = (mean_l - c(50.0)) * (mean_l - c(50.0));
+        let s_l = T::one() + c(0.015) * l50 / (c(20.0) + l50).sqrt();
+        let s_c = T::one() + c(0.045) * mean_c;
+        let s_h = T::one() + c(0.015) * mean_c * t;
+
+        let (l, c, h) = (delta_l / s_l, delta_c / s_c, delta_h / s_h);
+        (l*l + c*c + h*h + r_t * c * h).sqrt()
+    }
+}
+
+impl<T: Decimal> LChA<T>
+{
+    /// Interpolates, taking the shortest way around the hue circle.
+    pub fn lerp(self, other: Self, t: T) -> Self {
+        let lerp = |a: T, b: T| a + (b - a) * t;
+        let half = T::constant(180.0);
+
+        let hue_difference = other.2 - self.2;
+        let hue_difference = if hue_difference > half {
+            hue_difference - half - half
+        } else if hue_difference < T::zero() - half {
+            hue_difference + half + half
+        } else {
+            hue_difference
+        };
+
+        LChA(lerp(self.0, other.0), lerp(self.1, other.1),
+             wrap_hue(self.2 + hue_difference * t), lerp(self.3, other.3))
+    }
+}
+
+impl<T: Decimal> From<XYZA<T>> for LabA<T>
+{
+    fn from(XYZA(x,y,z,alpha): XYZA<T>) -> LabA<T> {
+        let XYZA(xn,yn,zn,_) = XYZA::<T>::white();
+        let delta = T::constant(6.0 / 29.0);
+
+        // A cube root, with a linear segment near black.
+        let f = |t: T| {
+            if t > delta * delta * delta {
+                t.cbrt()
+            } else {
+                t / (T::constant(3.0) * delta * delta) + T::constant(4.0 / 29.0)
+            }
+        };
+        let (fx, fy, fz) = (f(x / xn), f(y / yn), f(z / zn));
+
+        LabA(T::constant(116.0) * fy - T::constant(16.0),
+             T::constant(500.0) * (fx - fy),
+             T::constant(200.0) * (fy - fz),
+             alpha)
+    }
+}
+
+impl<T: Decimal> From<LabA<T>> for XYZA<T>
+{
+    fn from(LabA(l,a,b,alpha): LabA<T>) -> XYZA<T> {
+        let XYZA(xn,yn,zn,_) = XYZA::<T>::white();
+        let delta = T::constant(6.0 / 29.0);
+
+        let f_inverse = |t: T| {
+            if t > delta {
+                t * t * t
+            } else {
+                T::constant(3.0) * delta * delta * (t - T::constant(4.0 / 29.0))
+            }
+        };
+
+        let fy = (l + T::constant(16.0)) / T::constant(116.0);
+        let fx = fy + a / T::constant(500.0);
+        let fz = fy - b / T::constant(200.0);
+
+        XYZA(xn * f_inverse(fx), yn * f_inverse(fy), zn * f_inverse(fz), alpha)
+    }
+}
+
+impl<T: Decimal> From<LabA<T>> for LChA<T>
+{
+    fn from(LabA(l,a,b,alpha): LabA<T>) -> LChA<T> {
+        let hue = if a.is_zero() && b.is_zero() { T::zero() } else { wrap_hue(b.atan2(a).to_degrees()) };
+
+        LChA(l, (a*a + b*b).sqrt(), hue, alpha)
+    }
+}
+
+impl<T: Decimal> From<LChA<T>> for LabA<T>
+{
+    fn from(LChA(l,chroma,hue,alpha): LChA<T>) -> LabA<T> {
+        let (sin, cos) = hue.to_radians().sincos();
+
+        LabA(l, chroma * cos, chroma * sin, alpha)
+    }
+}
+
+impl<T: Decimal> From<NormalizedRGBA<T>> for LabA<T>
+{
+    fn from(rgba: NormalizedRGBA<T>) -> LabA<T> {
+        XYZA::from(rgba).into()
+    }
+}
+
+impl<T: Decimal> From<LabA<T>> for NormalizedRGBA<T>
+{
+    fn from(lab: LabA<T>) -> NormalizedRGBA<T> {
+        XYZA::from(lab).into()
+    }
+}
+
+impl<T: Decimal> From<NormalizedRGBA<T>> for LChA<T>
+{
+    fn from(rgba: NormalizedRGBA<T>) -> LChA<T> {
+        LabA::from(rgba).into()
+    }
+}
+
+impl<T: Decimal> From<LChA<T>> for NormalizedRGBA<T>
+{
+    fn from(lch: LChA<T>) -> NormalizedRGBA<T> {
+        LabA::from(lch).into()
+    }
+}
+
+#[test]
+fn test_lab_conversion() {
+    let LabA(l,a,b,_) = LabA::from(NormalizedRGBA(1.0f64, 0.0, 0.0, 1.0));
+    assert!((l - 53.2408).abs() < 1.0e-3 && (a - 80.0925).abs() < 1.0e-3 && (b - 67.2032).abs() < 1.0e-3);
+
+    let LabA(l,a,b,_) = LabA::from(NormalizedRGBA(1.0f64, 1.0, 1.0, 1.0));
+    assert!((l - 100.0).abs() < 1.0e-4 && a.abs() < 1.0e-3 && b.abs() < 1.0e-3);
+
+    let color = NormalizedRGBA(0.1f64, 0.7, 0.3, 1.0);
+    let NormalizedRGBA(r,g,b,_) = NormalizedRGBA::from(LChA::from(color));
+    assert!((r - 0.1).abs() < 1.0e-6 && (g - 0.7).abs() < 1.0e-6 && (b - 0.3).abs() < 1.0e-6);
+
+    // Hues take the short way across zero.
+    let middle = LChA(50.0f64, 30.0, 350.0, 1.0).lerp(LChA(50.0, 30.0, 30.0, 1.0), 0.5);
+    assert!((middle.2 - 10.0).abs() < 1.0e-9);
+}
+
+#[test]
+fn test_delta_e_2000() {
+    // Pairs from Sharma, Wu and Dalal's test data.
+    let pairs = [((50.0, 2.6772, -79.7751), (50.0, 0.0, -82.7485), 2.0425),
+                 ((50.0, -1.3802, -84.2814), (50.0, 0.0, -82.7485), 1.0),
+                 ((50.0, 2.5, 0.0), (50.0, 0.0, -2.5), 4.3065),
+                 ((50.0, 2.5, 0.0), (73.0, 25.0, -18.0), 27.1492),
+                 ((60.2574, -34.0099, 36.2677), (60.4626, -34.1751, 39.4387), 1.2644),
+                 ((2.0776, 0.0795, -1.1350), (0.9033, -0.0636, -0.5514), 0.9082)];
+
+    for &((l1,a1,b1), (l2,a2,b2), expected) in pairs.iter() {
+        let (first, second) = (LabA(l1, a1, b1, 1.0f64), LabA(l2, a2, b2, 1.0));
+
+        assert!((first.delta_e_2000(second) - expected).abs() < 1.0e-4);
+        assert!((second.delta_e_2000(first) - expected).abs() < 1.0e-4);
+    }
+}
@@ -1,10 +1,22 @@
 
 extern crate num;
 
+pub use self::hsv::{HSVA,HSLA};
+pub use self::xyz::XYZA;
+pub use self::lab::{LabA,LChA};
+pub use self::blend::{Composite,BlendMode,BlendState,Factor};
+
+use std::ops::{Add,Sub,Mul,Div};
+
+pub mod blend;
+pub mod hsv;
+pub mod xyz;
+pub mod lab;
+
 pub trait Color : Copy + Clone { }
 
 /// An RGBA value.
-#[derive(Copy,Clone)]
+#[derive(Copy,Clone,Debug,PartialEq)]
 pub struct RGBA<T: num::Integer = u8>(pub T, pub T, pub T, pub T);
 
 impl<T: num::Integer> RGBA<T>
@@ -20,11 +32,24 @@ impl<T: num::Integer> RGBA<T>
 
         RGBA(nr,ng,nb,na)
     }
+
+    /// Converts to fractions of the largest value of `T`.
+    pub fn to_normalized<D: num::Decimal>(self) -> NormalizedRGBA<D> {
+        let max: D = num::cast(num::max::<T>());
+        let RGBA(r,g,b,a) = self;
+        let normalize = |c: T| num::cast::<T,D>(c) / max;
+
+        NormalizedRGBA(normalize(r), normalize(g), normalize(b), normalize(a))
+    }
 }
 
 impl<T: num::Integer> Color for RGBA<T> { }
 
-#[derive(Copy,Clone)]
+/// An RGBA value with components from 0 to 1.
+///
+/// Unless stated otherwise, the colour components are sRGB encoded,
+/// as they would be stored in an image.
+#[derive(Copy,Clone,Debug,PartialEq)]
 pub struct NormalizedRGBA<T: num::Decimal = f32>(pub T, pub T, pub T, pub T);
 
 impl<T: num::Decimal> NormalizedRGBA<T>
@@ -40,8 +65,229 @@ impl<T: num::Decimal> NormalizedRGBA<T>
 
         NormalizedRGBA(nr,ng,nb,na)
     }
-   
+
+    /// Linearly interpolates every component.
+    pub fn lerp(self, other: Self, t: T) -> Self {
+        self + (other - self) * t
+    }
+
+    /// Clamps every component to the range 0 to 1.
+    pub fn clamp(self) -> Self {
+        self.map(|c| if c < T::zero() { T::zero() } else if c > T::one() { T::one() } else { c })
+    }
+
+    /// Multiplies the colour components by alpha.
+    ///
+    /// Premultiplied colours can be filtered and composited without
+    /// transparent pixels bleeding their colour into their neighbours.
+    pub fn premultiply(self) -> Self {
+        let NormalizedRGBA(r,g,b,a) = self;
+        NormalizedRGBA(r*a, g*a, b*a, a)
+    }
+
+    /// Divides premultiplied colour components by alpha.
+    /// Fully transparent colours become transparent black.
+    pub fn unpremultiply(self) -> Self {
+        let NormalizedRGBA(r,g,b,a) = self;
+
+        if a == T::zero() {
+            NormalizedRGBA(T::zero(), T::zero(), T::zero(), T::zero())
+        } else {
+            NormalizedRGBA(r/a, g/a, b/a, a)
+        }
+    }
+
+    /// Combines each pair of components.
+    fn zip<F>(self, other: Self, f: F) -> Self
+        where F: Fn(T,T) -> T {
+        let (NormalizedRGBA(r1,g1,b1,a1), NormalizedRGBA(r2,g2,b2,a2)) = (self, other);
+        NormalizedRGBA(f(r1,r2), f(g1,g2), f(b1,b2), f(a1,a2))
+    }
+
+    /// Converts to integers, scaling 1 to the largest value of `I`.
+    /// Components are clamped to the range 0 to 1.
+    pub fn to_rgba<I: num::Integer>(self) -> RGBA<I> {
+        let max: T = num::cast(num::max::<I>());
+        let quantize = |c: T| {
+            let c = if c < T::zero() { T::zero() } else if c > T::one() { T::one() } else { c };
+            num::cast::<T,I>((c * max).round())
+        };
+        let NormalizedRGBA(r,g,b,a) = self;
+
+        RGBA(quantize(r), quantize(g), quantize(b), quantize(a))
+    }
+
+    /// Decodes sRGB components into linear light, which is what
+    /// lighting and blending should be performed in.
+    /// Alpha is already linear, so it is unchanged.
+    pub fn to_linear(self) -> Self {
+        let decode = |c: T| {
+            if c <= T::constant(0.04045) {
+                c / T::constant(12.92)
+            } else {
+                ((c + T::constant(0.055)) / T::constant(1.055)).powf(T::constant(2.4))
+            }
+        };
+        let NormalizedRGBA(r,g,b,a) = self;
+
+        NormalizedRGBA(decode(r), decode(g), decode(b), a)
+    }
+
+    /// Encodes linear components as sRGB.
+    pub fn to_srgb(self) -> Self {
+        let encode = |c: T| {
+            if c <= T::constant(0.0031308) {
+                c * T::constant(12.92)
+            } else {
+                T::constant(1.055) * c.powf(T::constant(1.0 / 2.4)) - T::constant(0.055)
+            }
+        };
+        let NormalizedRGBA(r,g,b,a) = self;
+
+        NormalizedRGBA(encode(r), encode(g), encode(b), a)
+    }
+
+    /// Interpolates in CIE Lab, so that the steps between colours
+    /// look even.
+    pub fn lerp_lab(self, other: Self, t: T) -> Self {
+        LabA::from(self).lerp(LabA::from(other), t).into()
+    }
+
+    /// Interpolates in CIE LCh, taking the shortest way around
+    /// the hue circle. This keeps colours saturated, where Lab passes
+    /// through grey between opposite hues.
+    pub fn lerp_lch(self, other: Self, t: T) -> Self {
+        LChA::from(self).lerp(LChA::from(other), t).into()
+    }
+
+    /// Calculates the perceived difference between two colours, using
+    /// CIEDE2000. A difference of about 1 is just noticeable.
+    pub fn delta_e(self, other: Self) -> T {
+        LabA::from(self).delta_e_2000(LabA::from(other))
+    }
+}
+
+impl<T: num::Decimal> Add for NormalizedRGBA<T>
+{
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self { self.zip(rhs, |a,b| a + b) }
+}
+
+impl<T: num::Decimal> Sub for NormalizedRGBA<T>
+{
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self { self.zip(rhs, |a,b| a - b) }
+}
+
+impl<T: num::Decimal> Mul for NormalizedRGBA<T>
+{
+    type Output = Self;
+
+    /// Multiplies each component, which modulates one colour by another.
+    fn mul(self, rhs: Self) -> Self { self.zip(rhs, |a,b| a * b) }
+}
+
+impl<T: num::Decimal> Mul<T> for NormalizedRGBA<T>
+{
+    type Output = Self;
+
+    fn mul(self, rhs: T) -> Self { self.map(|c| c * rhs) }
+}
+
+impl<T: num::Decimal> Div<T> for NormalizedRGBA<T>
+{
+    type Output = Self;
+
+    fn div(self, rhs: T) -> Self { self.map(|c| c / rhs) }
+}
+
+impl<I: num::Integer, D: num::Decimal> From<RGBA<I>> for NormalizedRGBA<D>
+{
+    fn from(rgba: RGBA<I>) -> NormalizedRGBA<D> {
+        rgba.to_normalized()
+    }
+}
+
+impl<I: num::Integer, D: num::Decimal> From<NormalizedRGBA<D>> for RGBA<I>
+{
+    fn from(rgba: NormalizedRGBA<D>) -> RGBA<I> {
+        rgba.to_rgba()
+    }
 }
 
 impl<T: num::Decimal> Color for NormalizedRGBA<T> { }
 
+/// Wraps a hue in degrees into the range 0 to 360.
+fn wrap_hue<T: num::Decimal>(hue: T) -> T {
+    let full = T::constant(360.0);
+    let hue = hue % full;
+
+    if hue < T::zero() { hue + full } else { hue }
+}
+
+#[cfg(test)]
+fn assert_close(a: NormalizedRGBA<f64>, b: NormalizedRGBA<f64>) {
+    let (NormalizedRGBA(r1,g1,b1,a1), NormalizedRGBA(r2,g2,b2,a2)) = (a, b);
+    let error = (r1-r2).abs() + (g1-g2).abs() + (b1-b2).abs() + (a1-a2).abs();
+    assert!(error < 1.0e-5, "{:?} != {:?}", a, b);
+}
+
+#[test]
+fn test_rgba_normalization() {
+    let rgba = RGBA(255u8, 0, 51, 128);
+    let normalized: NormalizedRGBA<f64> = rgba.into();
+
+    assert_close(normalized, NormalizedRGBA(1.0, 0.0, 0.2, 128.0 / 255.0));
+    assert_eq!(normalized.to_rgba::<u8>(), rgba);
+    assert_eq!(NormalizedRGBA(1.5, -0.5, 0.5, 1.0).to_rgba::<u8>(), RGBA(255, 0, 128, 255));
+    assert_eq!(NormalizedRGBA(1.0f64, 0.0, 0.0, 1.0).to_rgba::<u16>(), RGBA(65535, 0, 0, 65535));
+}
+
+#[test]
+fn test_color_arithmetic() {
+    let (a, b) = (NormalizedRGBA(0.5f64, 0.25, 1.0, 0.5), NormalizedRGBA(0.25, 0.75, 0.0, 1.0));
+
+    assert_eq!(a + b, NormalizedRGBA(0.75, 1.0, 1.0, 1.5));
+    assert_eq!(a * b, NormalizedRGBA(0.125, 0.1875, 0.0, 0.5));
+    assert_eq!(a.lerp(b, 0.5), NormalizedRGBA(0.375, 0.5, 0.5, 0.75));
+    assert_eq!((a + b).clamp(), NormalizedRGBA(0.75, 1.0, 1.0, 1.0));
+    assert_eq!((a - b).clamp(), NormalizedRGBA(0.25, 0.0, 1.0, 0.0));
+
+    assert_eq!(a.premultiply(), NormalizedRGBA(0.25, 0.125, 0.5, 0.5));
+    assert_eq!(a.premultiply().unpremultiply(), a);
+    assert_eq!(NormalizedRGBA(0.5f64, 0.5, 0.5, 0.0).unpremultiply(), NormalizedRGBA(0.0, 0.0, 0.0, 0.0));
+}
+
+#[test]
+fn test_srgb_transfer() {
+    let srgb = NormalizedRGBA(0.5f64, 0.02, 1.0, 0.5);
+    let linear = srgb.to_linear();
+
+    assert!((linear.0 - 0.21404114).abs() < 1.0e-8);
+    assert!((linear.1 - 0.02 / 12.92).abs() < 1.0e-12);
+    assert_eq!(linear.3, 0.5);
+    assert_close(linear.to_srgb(), srgb);
+}
+
+#[test]
+fn test_perceptual_interpolation() {
+    let (red, blue) = (NormalizedRGBA(1.0f64, 0.0, 0.0, 1.0), NormalizedRGBA(0.0, 0.0, 1.0, 0.0));
+
+    assert_close(red.lerp_lab(blue, 0.0), red);
+    assert_close(red.lerp_lab(blue, 1.0), blue);
+    assert_close(red.lerp_lch(blue, 1.0), blue);
+    assert!((red.lerp_lab(blue, 0.5).3 - 0.5).abs() < 1.0e-12);
+
+    // The middle of a Lab interpolation is equally far from each end.
+    let middle = LabA::from(red.lerp_lab(blue, 0.5));
+    assert!((middle.delta_e_76(red.into()) - middle.delta_e_76(blue.into())).abs() < 1.0e-4);
+
+    // LCh keeps the chroma which Lab loses through the middle.
+    let chroma = |c: NormalizedRGBA<f64>| LChA::from(c).1;
+    assert!(chroma(red.lerp_lch(blue, 0.5)) > chroma(red.lerp_lab(blue, 0.5)));
+
+    assert!(red.delta_e(red).abs() < 1.0e-9);
+    assert!(red.delta_e(blue) > 10.0);
+}
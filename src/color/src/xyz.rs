@@ -0,0 +1,70 @@
+//! The CIE 1931 XYZ colour space.
+
+use {Color,NormalizedRGBA};
+use num::Decimal;
+
+/// A colour in CIE XYZ, relative to the D65 white point, with alpha.
+///
+/// `Y` is the luminance, from 0 to 1 for colours within sRGB.
+#[derive(Copy,Clone,Debug,PartialEq)]
+pub struct XYZA<T: Decimal = f32>(pub T, pub T, pub T, pub T);
+
+impl<T: Decimal> Color for XYZA<T> { }
+
+impl<T: Decimal> XYZA<T>
+{
+    /// The D65 white point, which sRGB white maps to.
+    pub fn white() -> Self {
+        XYZA(T::constant(0.95047), T::one(), T::constant(1.08883), T::one())
+    }
+
+    /// Converts linear sRGB components to XYZ.
+    pub fn from_linear(NormalizedRGBA(r,g,b,a): NormalizedRGBA<T>) -> Self {
+        let row = |x: f64, y: f64, z: f64| T::constant(x)*r + T::constant(y)*g + T::constant(z)*b;
+
+        XYZA(row(0.4124564, 0.3575761, 0.1804375),
+             row(0.2126729, 0.7151522, 0.0721750),
+             row(0.0193339, 0.1191920, 0.9503041),
+             a)
+    }
+
+    /// Converts to linear sRGB components, which may be out of range
+    /// for colours outside of the sRGB gamut.
+    pub fn to_linear(self) -> NormalizedRGBA<T> {
+        let XYZA(x,y,z,a) = self;
+        let row = |r: f64, g: f64, b: f64| T::constant(r)*x + T::constant(g)*y + T::constant(b)*z;
+
+        NormalizedRGBA(row( 3.2404542, -1.5371385, -0.4985314),
+                       row(-0.9692660,  1.8760108,  0.0415560),
+                       row( 0.0556434, -0.2040259,  1.0572252),
+                       a)
+    }
+}
+
+impl<T: Decimal> From<NormalizedRGBA<T>> for XYZA<T>
+{
+    fn from(rgba: NormalizedRGBA<T>) -> XYZA<T> {
+        XYZA::from_linear(rgba.to_linear())
+    }
+}
+
+impl<T: Decimal> From<XYZA<T>> for NormalizedRGBA<T>
+{
+    fn from(xyz: XYZA<T>) -> NormalizedRGBA<T> {
+        xyz.to_linear().to_srgb()
+    }
+}
+
+#[test]
+fn test_xyz_conversion() {
+    let white = XYZA::from(NormalizedRGBA(1.0f64, 1.0, 1.0, 1.0));
+    let XYZA(x,y,z,_) = white;
+    let XYZA(wx,wy,wz,_) = XYZA::<f64>::white();
+
+    assert!((x - wx).abs() < 1.0e-4 && (y - wy).abs() < 1.0e-6 && (z - wz).abs() < 1.0e-4);
+
+    let color = NormalizedRGBA(0.2f64, 0.6, 0.9, 0.5);
+    let NormalizedRGBA(r,g,b,a) = NormalizedRGBA::from(XYZA::from(color));
+    assert!((r - 0.2).abs() < 1.0e-6 && (g - 0.6).abs() < 1.0e-6 && (b - 0.9).abs() < 1.0e-6);
+    assert_eq!(a, 0.5);
+}
@@ -1,6 +1,7 @@
 
-use num::{self,Num};
+use num::{self,Num,Interval};
 use math::Vector3;
+use Transform3;
 
 /// An axis-aligned bounding box.
 #[derive(Copy,Clone)]
@@ -51,6 +52,17 @@ impl<T: Num> Aabb<T>
         (dx <= hx) && (dy <= hy) && (dz <= hz)
     }
 
+    /// Checks if the box overlaps another box.
+    /// Boxes which only touch are considered to overlap.
+    pub fn intersects(&self, other: &Aabb<T>) -> bool {
+        let (amin,amax) = (self.min(), self.max());
+        let (bmin,bmax) = (other.min(), other.max());
+
+        (amin.x() <= bmax.x()) && (bmin.x() <= amax.x()) &&
+        (amin.y() <= bmax.y()) && (bmin.y() <= amax.y()) &&
+        (amin.z() <= bmax.z()) && (bmin.z() <= amax.z())
+    }
+
     pub fn contains_any<I>(&self, mut points: I) -> bool
         where I: Iterator<Item=Vector3<T>> {
         points.any(|p| self.contains(p))
@@ -101,11 +113,94 @@ impl<T: Num> Aabb<T>
         ].into_iter().cloned().collect()
     }
 
+    /// Creates the smallest box containing a box of intervals.
+    pub fn from_intervals(bounds: Vector3<Interval<T>>) -> Self
+        where T: num::Float {
+        let center = bounds.map(|i| i.midpoint());
+
+        // Round the extents up, so that the box contains both bounds.
+        let extent = |i: Interval<T>, c: T| {
+            let (below, above) = (Interval::point(c) - Interval::point(i.lower()),
+                                  Interval::point(i.upper()) - Interval::point(c));
+            if below.upper() > above.upper() { below.upper() } else { above.upper() }
+        };
+
+        let half_extents = Vector3(extent(bounds.x(), center.x()),
+                                   extent(bounds.y(), center.y()),
+                                   extent(bounds.z(), center.z()));
+        Aabb::new(center, half_extents)
+    }
+
+    /// Gets the box as an interval along each axis.
+    pub fn to_intervals(&self) -> Vector3<Interval<T>>
+        where T: num::Float {
+        let (Vector3(cx,cy,cz), Vector3(hx,hy,hz)) = (self.center, self.half_extents);
+        let axis = |c: T, h: T| {
+            let (c, h) = (Interval::point(c), Interval::point(h));
+            Interval::new((c - h).lower(), (c + h).upper())
+        };
+
+        Vector3(axis(cx,hx), axis(cy,hy), axis(cz,hz))
+    }
+
+    /// Gets a box which is guaranteed to contain this box after it has
+    /// been transformed, despite rounding errors.
+    pub fn transformed(&self, transform: &Transform3<T>) -> Self
+        where T: num::Float {
+        Aabb::from_intervals(transform.to_interval().transform_point(self.to_intervals()))
+    }
+
     pub fn center(&self) -> Vector3<T> { self.center }
     pub fn half_extents(&self) -> Vector3<T> { self.half_extents }
+    /// Gets the corner with the smallest coordinates.
+    pub fn min(&self) -> Vector3<T> { self.center - self.half_extents }
+    /// Gets the corner with the largest coordinates.
+    pub fn max(&self) -> Vector3<T> { self.center + self.half_extents }
     pub fn quarter_extents(&self) -> Vector3<T> {
         let two = T::one()+T::one();
         self.half_extents.map(|a| a/two)
     }
 }
 
+#[test]
+fn test_aabb_transformed() {
+    let aabb = Aabb::new(Vector3(1.0f64, 2.0, 3.0), Vector3(1.0, 0.5, 0.25));
+    let rotation = ::math::Quaternion::from_axis_angle(Vector3(1.0, 1.0, 0.0), 0.4);
+    let transform = Transform3::from_trs(Vector3(0.1, -0.3, 0.7), rotation, Vector3(2.0, 1.0, 3.0));
+    let bounds = aabb.transformed(&transform);
+
+    let (min, max) = (aabb.min(), aabb.max());
+    let pick = |i: usize, bit: usize, low: f64, high: f64| if i & bit == 0 { low } else { high };
+    let corner = |i: usize| {
+        transform.transform_point(Vector3(pick(i, 1, min.x(), max.x()),
+                                          pick(i, 2, min.y(), max.y()),
+                                          pick(i, 4, min.z(), max.z())))
+    };
+
+    // Every transformed corner must lie inside the bounds, which are only
+    // wider than the exact box by rounding.
+    assert!((0..8).all(|i| bounds.contains(corner(i))));
+
+    let exact = Aabb::containing((0..8).map(corner));
+    assert!((bounds.half_extents() - exact.half_extents()).length_squared() < 1.0e-20);
+}
+
+#[test]
+fn test_fixed_aabb() {
+    use num::{Decimal,I16F16};
+    use math::Vector;
+
+    let fixed = |x: f64| I16F16::from_f64(x);
+    let points = vec![Vector3(fixed(-1.0), fixed(0.5), fixed(2.0)),
+                      Vector3(fixed(3.0), fixed(-0.5), fixed(1.0))];
+    let aabb = Aabb::containing(points.into_iter());
+
+    assert_eq!(aabb.center().xyz(), (fixed(1.0), fixed(0.0), fixed(1.5)));
+    assert_eq!(aabb.half_extents().xyz(), (fixed(2.0), fixed(0.5), fixed(0.5)));
+    assert!(aabb.contains(Vector3(fixed(2.75), fixed(0.25), fixed(1.25))));
+
+    let far = Aabb::new(Vector3(fixed(5.5), fixed(0.0), fixed(0.0)),
+                        Vector3(fixed(0.25), fixed(1.0), fixed(1.0)));
+    assert!(!aabb.intersects(&far));
+    assert_eq!((aabb.max() - aabb.min()).length(), fixed(18.0).sqrt());
+}
@@ -0,0 +1,286 @@
+//! Broad-phase collision detection using sweep and prune.
+//!
+//! The endpoints of every box along the x axis are kept in a sorted list.
+//! Objects usually move only a little between steps, so the list is nearly
+//! sorted already and an insertion sort brings it back into order in
+//! close to linear time. A single sweep over the list then finds every
+//! pair of boxes which overlap.
+
+use Aabb;
+use num::Num;
+use std::collections::BTreeSet;
+use std::collections::btree_set;
+
+/// A handle to an object in the broad phase.
+#[derive(Copy,Clone,Debug,PartialEq,Eq,PartialOrd,Ord,Hash)]
+pub struct Proxy(pub usize);
+
+/// A change in the overlap state of a pair of objects.
+///
+/// The first proxy of a pair is always the smaller one.
+#[derive(Copy,Clone,Debug,PartialEq,Eq)]
+pub enum Event
+{
+    /// The objects started overlapping this step.
+    Begin(Proxy,Proxy),
+    /// The objects were overlapping last step and still are.
+    Persist(Proxy,Proxy),
+    /// The objects stopped overlapping this step, or one of them
+    /// was removed.
+    End(Proxy,Proxy),
+}
+
+/// The start or end of a box along the sweep axis.
+#[derive(Copy,Clone)]
+struct Endpoint<T: Num>
+{
+    value: T,
+    proxy: usize,
+    is_min: bool,
+}
+
+impl<T: Num> Endpoint<T>
+{
+    /// Checks if the endpoint should be sorted before another.
+    ///
+    /// At equal values, starting points come first so that touching
+    /// boxes are reported as overlapping. Remaining ties are broken by
+    /// the proxy, which keeps the order deterministic.
+    fn precedes(&self, other: &Endpoint<T>) -> bool {
+        if self.value != other.value {
+            self.value < other.value
+        } else if self.is_min != other.is_min {
+            self.is_min
+        } else {
+            self.proxy < other.proxy
+        }
+    }
+}
+
+/// A sweep and prune broad phase.
+pub struct SweepAndPrune<T: Num>
+{
+    /// The box of each proxy, or `None` if the slot is free.
+    boxes: Vec<Option<Aabb<T>>>,
+    /// Slots which can be reused.
+    free: Vec<usize>,
+    /// The endpoints along the sweep axis.
+    endpoints: Vec<Endpoint<T>>,
+    /// The pairs which overlapped at the last update.
+    pairs: BTreeSet<(Proxy,Proxy)>,
+    /// Events for removed objects which have not been reported yet.
+    pending: Vec<Event>,
+}
+
+impl<T: Num> SweepAndPrune<T>
+{
+    pub fn new() -> Self {
+        SweepAndPrune {
+            boxes: Vec::new(),
+            free: Vec::new(),
+            endpoints: Vec::new(),
+            pairs: BTreeSet::new(),
+            pending: Vec::new(),
+        }
+    }
+
+    /// Adds an object.
+    pub fn insert(&mut self, aabb: Aabb<T>) -> Proxy {
+        let index = match self.free.pop() {
+            Some(index) => { self.boxes[index] = Some(aabb); index },
+            None => { self.boxes.push(Some(aabb)); self.boxes.len() - 1 },
+        };
+
+        self.endpoints.push(Endpoint { value: aabb.min().x(), proxy: index, is_min: true });
+        self.endpoints.push(Endpoint { value: aabb.max().x(), proxy: index, is_min: false });
+
+        Proxy(index)
+    }
+
+    /// Removes an object.
+    ///
+    /// Any pairs the object was part of are reported as ended at the
+    /// next update.
+    /// Panics if the proxy has already been removed.
+    pub fn remove(&mut self, proxy: Proxy) {
+        let Proxy(index) = proxy;
+        assert!(self.boxes[index].is_some(), "the proxy has already been removed");
+
+        self.boxes[index] = None;
+        self.free.push(index);
+        self.endpoints.retain(|e| e.proxy != index);
+
+        let ended: Vec<_> = self.pairs.iter().cloned().filter(|&(a,b)| {
+            a == proxy || b == proxy
+        }).collect();
+
+        for pair in ended {
+            self.pairs.remove(&pair);
+            self.pending.push(Event::End(pair.0, pair.1));
+        }
+    }
+
+    /// Moves an object.
+    /// Panics if the proxy has been removed.
+    pub fn set(&mut self, proxy: Proxy, aabb: Aabb<T>) {
+        let Proxy(index) = proxy;
+        assert!(self.boxes[index].is_some(), "the proxy has been removed");
+
+        self.boxes[index] = Some(aabb);
+    }
+
+    /// Gets the box of an object.
+    /// Panics if the proxy has been removed.
+    pub fn get(&self, proxy: Proxy) -> Aabb<T> {
+        self.boxes[proxy.0].expect("the proxy has been removed")
+    }
+
+    /// Finds the overlapping pairs, and reports how they have
+    /// changed since the last update.
+    ///
+    /// Pairs which ended because an object was removed are reported
+    /// first, in the order they were removed. The rest of the events
+    /// follow, ordered by pair.
+    pub fn update(&mut self) -> Vec<Event> {
+        self.refresh_endpoints();
+        self.sort_endpoints();
+
+        let pairs = self.sweep();
+        let mut events = Vec::new();
+
+        for &(a,b) in pairs.iter() {
+            if self.pairs.contains(&(a,b)) {
+                events.push(Event::Persist(a,b));
+            } else {
+                events.push(Event::Begin(a,b));
+            }
+        }
+
+        for &(a,b) in self.pairs.difference(&pairs) {
+            events.push(Event::End(a,b));
+        }
+
+        events.sort_by(|x,y| event_pair(x).cmp(&event_pair(y)));
+        self.pairs = pairs;
+
+        let mut removed: Vec<Event> = self.pending.drain(..).collect();
+        removed.extend(events);
+        removed
+    }
+
+    /// Gets the pairs which were overlapping at the last update.
+    pub fn pairs<'a>(&'a self) -> btree_set::Iter<'a,(Proxy,Proxy)> {
+        self.pairs.iter()
+    }
+
+    /// Copies the latest boxes into the endpoint list.
+    fn refresh_endpoints(&mut self) {
+        for endpoint in self.endpoints.iter_mut() {
+            let aabb = self.boxes[endpoint.proxy].unwrap();
+
+            endpoint.value = if endpoint.is_min {
+                aabb.min().x()
+            } else {
+                aabb.max().x()
+            };
+        }
+    }
+
+    /// Insertion sorts the endpoints, which is fast when objects
+    /// have only moved slightly.
+    fn sort_endpoints(&mut self) {
+        for i in 1..self.endpoints.len() {
+            let mut j = i;
+
+            while j > 0 && self.endpoints[j].precedes(&self.endpoints[j-1]) {
+                self.endpoints.swap(j, j-1);
+                j -= 1;
+            }
+        }
+    }
+
+    /// Sweeps along the axis, finding the overlapping pairs.
+    fn sweep(&self) -> BTreeSet<(Proxy,Proxy)> {
+        let mut pairs = BTreeSet::new();
+        let mut active: Vec<usize> = Vec::new();
+
+        for endpoint in self.endpoints.iter() {
+            if endpoint.is_min {
+                let aabb = self.boxes[endpoint.proxy].unwrap();
+
+                for &other in active.iter() {
+                    if aabb.intersects(&self.boxes[other].unwrap()) {
+                        let (a,b) = if other < endpoint.proxy {
+                            (other, endpoint.proxy)
+                        } else {
+                            (endpoint.proxy, other)
+                        };
+
+                        pairs.insert((Proxy(a), Proxy(b)));
+                    }
+                }
+
+                active.push(endpoint.proxy);
+            } else {
+                active.retain(|&p| p != endpoint.proxy);
+            }
+        }
+
+        pairs
+    }
+}
+
+impl<T: Num> Default for SweepAndPrune<T>
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn event_pair(event: &Event) -> (Proxy,Proxy) {
+    match *event {
+        Event::Begin(a,b) => (a,b),
+        Event::Persist(a,b) => (a,b),
+        Event::End(a,b) => (a,b),
+    }
+}
+
+#[test]
+fn test_sweep_and_prune_events() {
+    use math::Vector3;
+
+    let unit = Vector3(0.5, 0.5, 0.5);
+    let mut broad = SweepAndPrune::new();
+
+    let a = broad.insert(Aabb::new(Vector3(0.0, 0.0, 0.0), unit));
+    let b = broad.insert(Aabb::new(Vector3(0.75, 0.0, 0.0), unit));
+    let c = broad.insert(Aabb::new(Vector3(0.75, 5.0, 0.0), unit));
+
+    assert_eq!(broad.update(), vec![Event::Begin(a,b)]);
+    assert_eq!(broad.update(), vec![Event::Persist(a,b)]);
+
+    broad.set(b, Aabb::new(Vector3(3.0, 0.0, 0.0), unit));
+    broad.set(c, Aabb::new(Vector3(0.5, 0.5, 0.0), unit));
+    assert_eq!(broad.update(), vec![Event::End(a,b), Event::Begin(a,c)]);
+
+    broad.remove(c);
+    assert_eq!(broad.update(), vec![Event::End(a,c)]);
+    assert_eq!(broad.update(), vec![]);
+}
+
+#[test]
+fn test_removal_events_first() {
+    use math::Vector3;
+
+    let unit = Vector3(0.5, 0.5, 0.5);
+    let mut broad = SweepAndPrune::new();
+
+    let a = broad.insert(Aabb::new(Vector3(0.0, 0.0, 0.0), unit));
+    let b = broad.insert(Aabb::new(Vector3(0.75, 0.0, 0.0), unit));
+    let c = broad.insert(Aabb::new(Vector3(1.5, 0.0, 0.0), unit));
+    assert_eq!(broad.update(), vec![Event::Begin(a,b), Event::Begin(b,c)]);
+
+    // The removed pair sorts after the persisting one, but comes first.
+    broad.remove(c);
+    assert_eq!(broad.update(), vec![Event::End(b,c), Event::Persist(a,b)]);
+}
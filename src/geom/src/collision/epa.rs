@@ -0,0 +1,337 @@
+//! The expanding polytope algorithm.
+
+use super::{Support,SupportPoint,Proximity,proximity,tolerance};
+use math::Vector3;
+use num::{Decimal,Signed};
+
+/// The maximum number of times the polytope is expanded.
+const MAXIMUM_ITERATIONS: usize = 64;
+
+/// Contact information for two overlapping shapes.
+#[derive(Copy,Clone)]
+pub struct Contact<T: Decimal>
+{
+    /// The unit contact normal, pointing from the first shape towards
+    /// the second.
+    pub normal: Vector3<T>,
+    /// The distance the shapes overlap by along the normal.
+    pub depth: T,
+    /// The point on the first shape which is deepest inside the second.
+    pub point_a: Vector3<T>,
+    /// The point on the second shape which is deepest inside the first.
+    pub point_b: Vector3<T>,
+}
+
+/// A triangular face on the polytope.
+#[derive(Copy,Clone)]
+struct Face<T: Decimal>
+{
+    indices: [usize; 3],
+    normal: Vector3<T>,
+    distance: T,
+}
+
+impl<T: Decimal + Signed> Face<T>
+{
+    fn new(vertices: &[SupportPoint<T>], indices: [usize; 3]) -> Self {
+        let a = vertices[indices[0]].point;
+        let b = vertices[indices[1]].point;
+        let c = vertices[indices[2]].point;
+
+        let normal = (b - a).cross(c - a);
+        let length_squared = normal.length_squared();
+
+        if length_squared.is_zero() {
+            // A sliver face. It cannot be seen from any point, and
+            // will never be picked as the closest face.
+            Face {
+                indices: indices,
+                normal: normal,
+                distance: T::max(),
+            }
+        } else {
+            let normal = normal * length_squared.rsqrt();
+
+            Face {
+                indices: indices,
+                normal: normal,
+                distance: normal.dot(a),
+            }
+        }
+    }
+
+    /// Checks if the face can be seen from a point.
+    fn is_visible_from(&self, vertices: &[SupportPoint<T>], point: Vector3<T>) -> bool {
+        self.normal.dot(point - vertices[self.indices[0]].point) > T::zero()
+    }
+}
+
+/// Finds the penetration depth and contact normal of two shapes.
+/// Returns `None` if the shapes do not overlap, and a contact with zero
+/// depth if they only touch.
+pub fn penetration<T,A,B>(a: &A, b: &B) -> Option<Contact<T>>
+    where T: Decimal + Signed, A: Support<T>, B: Support<T> {
+    match proximity(a, b) {
+        Proximity::Intersecting(simplex) => expand(a, b, simplex),
+        Proximity::Separated(..) => None,
+    }
+}
+
+/// Expands a simplex enclosing the origin until the closest face of the
+/// Minkowski difference is found.
+///
+/// If the Minkowski difference is flat, which can only happen when both
+/// shapes are flat, the shapes only touch and the contact has zero depth.
+pub fn expand<T,A,B>(a: &A, b: &B, simplex: Vec<SupportPoint<T>>) -> Option<Contact<T>>
+    where T: Decimal + Signed, A: Support<T>, B: Support<T> {
+    let tolerance: T = tolerance();
+
+    let mut vertices = match build_tetrahedron(a, b, simplex) {
+        Ok(vertices) => vertices,
+        Err(flat) => return Some(touching(&flat)),
+    };
+
+    // Wind all of the faces outwards.
+    let centroid = vertices.iter().fold(Vector3::with_components(T::zero()), |acc,v| {
+        acc + v.point
+    }) * T::constant(0.25);
+
+    let mut faces: Vec<Face<T>> = [[0,1,2], [0,3,1], [0,2,3], [1,3,2]].iter().map(|&indices| {
+        let face = Face::new(&vertices, indices);
+
+        if face.normal.dot(vertices[indices[0]].point - centroid) < T::zero() {
+            Face::new(&vertices, [indices[0], indices[2], indices[1]])
+        } else {
+            face
+        }
+    }).collect();
+
+    for _ in 0..MAXIMUM_ITERATIONS {
+        let closest = closest_face(&faces);
+        let w = SupportPoint::new(a, b, closest.normal);
+
+        // The polytope can't be expanded any further towards the face.
+        if w.point.dot(closest.normal) - closest.distance <= tolerance {
+            return Some(contact(&vertices, closest));
+        }
+
+        // Remove every face that the new point can see, keeping track of
+        // the edges on the boundary of the hole this leaves.
+        let mut horizon: Vec<(usize,usize)> = Vec::new();
+
+        faces.retain(|face| {
+            if !face.is_visible_from(&vertices, w.point) {
+                return true;
+            }
+
+            let [i,j,k] = face.indices;
+            for &(from,to) in [(i,j), (j,k), (k,i)].iter() {
+                match horizon.iter().position(|&edge| edge == (to,from)) {
+                    Some(pos) => { horizon.remove(pos); },
+                    None => horizon.push((from,to)),
+                }
+            }
+
+            false
+        });
+
+        let new_index = vertices.len();
+        vertices.push(w);
+
+        for &(from,to) in horizon.iter() {
+            faces.push(Face::new(&vertices, [from, to, new_index]));
+        }
+    }
+
+    Some(contact(&vertices, closest_face(&faces)))
+}
+
+/// Grows a simplex into a tetrahedron which encloses the origin.
+/// Fails with the largest simplex found if the Minkowski difference is flat.
+fn build_tetrahedron<T,A,B>(a: &A, b: &B, mut simplex: Vec<SupportPoint<T>>)
+    -> Result<Vec<SupportPoint<T>>,Vec<SupportPoint<T>>>
+    where T: Decimal + Signed, A: Support<T>, B: Support<T> {
+    let tolerance: T = tolerance();
+    let (zero, one) = (T::zero(), T::one());
+
+    let directions = [
+        Vector3(one, zero, zero), Vector3(-one, zero, zero),
+        Vector3(zero, one, zero), Vector3(zero, -one, zero),
+        Vector3(zero, zero, one), Vector3(zero, zero, -one),
+    ];
+
+    while simplex.len() < 4 {
+        // For a triangle, searching along its normal is the only
+        // way to gain volume.
+        let search: Vec<Vector3<T>> = if simplex.len() == 3 {
+            let normal = (simplex[1].point - simplex[0].point)
+                         .cross(simplex[2].point - simplex[0].point);
+            vec![normal, -normal]
+        } else {
+            directions.iter().cloned().collect()
+        };
+
+        let found = search.into_iter().map(|d| SupportPoint::new(a, b, d)).find(|w| {
+            extends(&simplex, w.point, tolerance)
+        });
+
+        match found {
+            Some(w) => simplex.push(w),
+            None => return Err(simplex),
+        }
+    }
+
+    Ok(simplex)
+}
+
+/// Checks if adding a point to a simplex would increase its dimension.
+fn extends<T>(simplex: &[SupportPoint<T>], point: Vector3<T>, tolerance: T) -> bool
+    where T: Decimal + Signed {
+    let origin = simplex[0].point;
+    let offset = point - origin;
+
+    match simplex.len() {
+        1 => offset.length_squared() > tolerance*tolerance,
+        2 => {
+            let edge = simplex[1].point - origin;
+            edge.cross(offset).length_squared() > tolerance*tolerance
+        },
+        3 => {
+            let normal = (simplex[1].point - origin).cross(simplex[2].point - origin);
+            offset.dot(normal).abs() > tolerance*tolerance
+        },
+        _ => false,
+    }
+}
+
+/// Gets the face closest to the origin.
+/// The earliest face wins ties.
+fn closest_face<T: Decimal>(faces: &[Face<T>]) -> Face<T> {
+    let mut best = faces[0];
+
+    for face in faces.iter().skip(1) {
+        if face.distance < best.distance {
+            best = *face;
+        }
+    }
+
+    best
+}
+
+/// Builds the contact information from the closest face.
+fn contact<T>(vertices: &[SupportPoint<T>], face: Face<T>) -> Contact<T>
+    where T: Decimal + Signed {
+    let a = vertices[face.indices[0]];
+    let b = vertices[face.indices[1]];
+    let c = vertices[face.indices[2]];
+
+    // Find the barycentric coordinates of the origin projected
+    // onto the face.
+    let p = face.normal * face.distance;
+    let (v0, v1, v2) = (b.point - a.point, c.point - a.point, p - a.point);
+
+    let d00 = v0.dot(v0);
+    let d01 = v0.dot(v1);
+    let d11 = v1.dot(v1);
+    let d20 = v2.dot(v0);
+    let d21 = v2.dot(v1);
+    let denominator = d00*d11 - d01*d01;
+
+    let (u, v, w) = if denominator.is_zero() {
+        (T::one(), T::zero(), T::zero())
+    } else {
+        let v = (d11*d20 - d01*d21) / denominator;
+        let w = (d00*d21 - d01*d20) / denominator;
+        (T::one() - v - w, v, w)
+    };
+
+    Contact {
+        normal: face.normal,
+        depth: face.distance,
+        point_a: a.a*u + b.a*v + c.a*w,
+        point_b: a.b*u + b.b*v + c.b*w,
+    }
+}
+
+/// Builds the contact information for shapes whose Minkowski difference
+/// is flat. Moving either shape off the plane of the difference separates
+/// them, so they only touch.
+fn touching<T>(simplex: &[SupportPoint<T>]) -> Contact<T>
+    where T: Decimal + Signed {
+    let (zero, one) = (T::zero(), T::one());
+    let first = simplex[0];
+
+    let (weights, normal) = match simplex.len() {
+        1 => (vec![one], Vector3(one, zero, zero)),
+        2 => {
+            let edge = simplex[1].point - first.point;
+            let t = -first.point.dot(edge) / edge.dot(edge);
+
+            // Any direction perpendicular to the edge will do.
+            let normal = edge.cross(Vector3(one, zero, zero));
+            let normal = if normal.length_squared() > edge.length_squared() * T::constant(0.5) {
+                normal
+            } else {
+                edge.cross(Vector3(zero, one, zero))
+            };
+
+            (vec![one - t, t], normal * normal.length_squared().rsqrt())
+        },
+        _ => {
+            let face = Face::new(simplex, [0, 1, 2]);
+            let contact = contact(simplex, Face { distance: zero, ..face });
+            return Contact { depth: zero, ..contact };
+        },
+    };
+
+    let (point_a, point_b) = simplex.iter().zip(weights).fold(
+        (Vector3::with_components(zero), Vector3::with_components(zero)),
+        |(pa, pb), (p, weight)| (pa + p.a*weight, pb + p.b*weight));
+
+    Contact {
+        normal: normal,
+        depth: zero,
+        point_a: point_a,
+        point_b: point_b,
+    }
+}
+
+#[test]
+fn test_sphere_penetration() {
+    use super::Sphere;
+
+    let a = Sphere::new(Vector3(0.0f64, 0.0, 0.0), 1.0);
+    let b = Sphere::new(Vector3(1.5f64, 0.0, 0.0), 1.0);
+
+    let contact = penetration(&a, &b).unwrap();
+    assert!((contact.depth - 0.5).abs() < 1.0e-2);
+    assert!((contact.normal.x() - 1.0).abs() < 1.0e-2);
+}
+
+#[test]
+fn test_box_penetration() {
+    use super::Cuboid;
+
+    let a = Cuboid::new(Vector3(0.0f64, 0.0, 0.0), Vector3(1.0, 1.0, 1.0));
+    let b = Cuboid::new(Vector3(0.0f64, 1.75, 0.0), Vector3(1.0, 1.0, 1.0));
+
+    let contact = penetration(&a, &b).unwrap();
+    assert!((contact.depth - 0.25).abs() < 1.0e-6);
+    assert!((contact.normal.y() - 1.0).abs() < 1.0e-6);
+    assert!((contact.point_a.y() - 1.0).abs() < 1.0e-6);
+    assert!((contact.point_b.y() - 0.75).abs() < 1.0e-6);
+}
+
+#[test]
+fn test_flat_touching() {
+    use super::Cuboid;
+
+    // Two squares in the same plane, sharing an edge.
+    let a = Cuboid::new(Vector3(0.0f64, 0.0, 0.0), Vector3(1.0, 1.0, 0.0));
+    let b = Cuboid::new(Vector3(2.0f64, 0.0, 0.0), Vector3(1.0, 1.0, 0.0));
+
+    let contact = penetration(&a, &b).unwrap();
+    assert_eq!(contact.depth, 0.0);
+    assert!((contact.normal.z().abs() - 1.0).abs() < 1.0e-6);
+    assert!((contact.point_a - contact.point_b).length_squared() < 1.0e-12);
+}
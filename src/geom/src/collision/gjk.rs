@@ -0,0 +1,328 @@
+//! The Gilbert-Johnson-Keerthi algorithm.
+
+use super::{Support,tolerance};
+use math::Vector3;
+use num::{Decimal,Signed};
+
+/// The maximum number of iterations before the search gives up.
+const MAXIMUM_ITERATIONS: usize = 64;
+
+/// A point on the Minkowski difference `A - B` of two shapes.
+#[derive(Copy,Clone)]
+pub struct SupportPoint<T: Decimal>
+{
+    /// The point on the Minkowski difference.
+    pub point: Vector3<T>,
+    /// The support point of the first shape.
+    pub a: Vector3<T>,
+    /// The support point of the second shape.
+    pub b: Vector3<T>,
+}
+
+impl<T: Decimal + Signed> SupportPoint<T>
+{
+    /// Gets the point of the Minkowski difference furthest along a direction.
+    pub fn new<A,B>(a: &A, b: &B, direction: Vector3<T>) -> Self
+        where A: Support<T>, B: Support<T> {
+        let pa = a.support(direction);
+        let pb = b.support(-direction);
+
+        SupportPoint {
+            point: pa - pb,
+            a: pa,
+            b: pb,
+        }
+    }
+}
+
+/// The closest points between two separated shapes.
+#[derive(Copy,Clone)]
+pub struct Separation<T: Decimal>
+{
+    /// The distance between the shapes.
+    pub distance: T,
+    /// The point on the first shape closest to the second.
+    pub point_a: Vector3<T>,
+    /// The point on the second shape closest to the first.
+    pub point_b: Vector3<T>,
+}
+
+/// How two shapes are positioned relative to each other.
+pub enum Proximity<T: Decimal>
+{
+    /// The shapes overlap or touch.
+    ///
+    /// Contains the final simplex, which encloses the origin.
+    Intersecting(Vec<SupportPoint<T>>),
+    /// The shapes are separated.
+    Separated(Separation<T>),
+}
+
+/// Finds how two shapes are positioned relative to each other.
+pub fn proximity<T,A,B>(a: &A, b: &B) -> Proximity<T>
+    where T: Decimal + Signed, A: Support<T>, B: Support<T> {
+    let tolerance: T = tolerance();
+
+    let initial_direction = Vector3(T::one(), T::zero(), T::zero());
+    let mut simplex = vec![SupportPoint::new(a, b, initial_direction)];
+    let mut closest = vec![(simplex[0], T::one())];
+
+    for _ in 0..MAXIMUM_ITERATIONS {
+        closest = closest_to_origin(&simplex);
+        simplex = closest.iter().map(|&(p,_)| p).collect();
+
+        let v = weighted_sum(&closest, |p| p.point);
+        let vv = v.dot(v);
+
+        // The origin lies inside the simplex.
+        if simplex.len() == 4 || vv <= tolerance*tolerance {
+            return Proximity::Intersecting(simplex);
+        }
+
+        let w = SupportPoint::new(a, b, -v);
+
+        // Check if the new point is any closer to the origin. If it
+        // isn't, we have found the closest point.
+        let no_progress = vv - v.dot(w.point) <= vv * tolerance;
+        let duplicate = simplex.iter().any(|p| {
+            (p.point - w.point).length_squared() <= tolerance*tolerance
+        });
+
+        if no_progress || duplicate {
+            break;
+        }
+
+        simplex.push(w);
+    }
+
+    Proximity::Separated(Separation {
+        distance: weighted_sum(&closest, |p| p.point).length_squared().sqrt(),
+        point_a: weighted_sum(&closest, |p| p.a),
+        point_b: weighted_sum(&closest, |p| p.b),
+    })
+}
+
+/// Checks if two shapes overlap.
+pub fn intersects<T,A,B>(a: &A, b: &B) -> bool
+    where T: Decimal + Signed, A: Support<T>, B: Support<T> {
+    match proximity(a, b) {
+        Proximity::Intersecting(..) => true,
+        Proximity::Separated(..) => false,
+    }
+}
+
+/// Finds the distance and closest points between two shapes.
+/// Returns `None` if the shapes overlap.
+pub fn distance<T,A,B>(a: &A, b: &B) -> Option<Separation<T>>
+    where T: Decimal + Signed, A: Support<T>, B: Support<T> {
+    match proximity(a, b) {
+        Proximity::Intersecting(..) => None,
+        Proximity::Separated(separation) => Some(separation),
+    }
+}
+
+/// Sums the barycentric combination of some property of the points.
+fn weighted_sum<T,F>(points: &[(SupportPoint<T>,T)], f: F) -> Vector3<T>
+    where T: Decimal, F: Fn(SupportPoint<T>) -> Vector3<T> {
+    points.iter().fold(Vector3::with_components(T::zero()), |acc, &(p,weight)| {
+        acc + f(p) * weight
+    })
+}
+
+/// Finds the point on a simplex closest to the origin.
+///
+/// Returns the smallest sub-simplex containing that point, with the
+/// barycentric weight of each of its vertices.
+fn closest_to_origin<T>(simplex: &[SupportPoint<T>]) -> Vec<(SupportPoint<T>,T)>
+    where T: Decimal + Signed {
+    match simplex.len() {
+        1 => vec![(simplex[0], T::one())],
+        2 => closest_on_segment(simplex[0], simplex[1]),
+        3 => closest_on_triangle(simplex[0], simplex[1], simplex[2]),
+        4 => closest_on_tetrahedron(simplex[0], simplex[1], simplex[2], simplex[3]),
+        _ => unreachable!(),
+    }
+}
+
+fn closest_on_segment<T>(a: SupportPoint<T>,
+                         b: SupportPoint<T>) -> Vec<(SupportPoint<T>,T)>
+    where T: Decimal + Signed {
+    let ab = b.point - a.point;
+    let length_squared = ab.length_squared();
+    let t = -a.point.dot(ab);
+
+    if t <= T::zero() || length_squared.is_zero() {
+        vec![(a, T::one())]
+    } else if t >= length_squared {
+        vec![(b, T::one())]
+    } else {
+        let t = t / length_squared;
+        vec![(a, T::one() - t), (b, t)]
+    }
+}
+
+fn closest_on_triangle<T>(a: SupportPoint<T>,
+                          b: SupportPoint<T>,
+                          c: SupportPoint<T>) -> Vec<(SupportPoint<T>,T)>
+    where T: Decimal + Signed {
+    let zero = T::zero();
+    let ab = b.point - a.point;
+    let ac = c.point - a.point;
+
+    // Vertex region outside `a`.
+    let d1 = -ab.dot(a.point);
+    let d2 = -ac.dot(a.point);
+    if d1 <= zero && d2 <= zero {
+        return vec![(a, T::one())];
+    }
+
+    // Vertex region outside `b`.
+    let d3 = -ab.dot(b.point);
+    let d4 = -ac.dot(b.point);
+    if d3 >= zero && d4 <= d3 {
+        return vec![(b, T::one())];
+    }
+
+    // Edge region of `ab`.
+    let vc = d1*d4 - d3*d2;
+    if vc <= zero && d1 >= zero && d3 <= zero {
+        let v = d1 / (d1 - d3);
+        return vec![(a, T::one() - v), (b, v)];
+    }
+
+    // Vertex region outside `c`.
+    let d5 = -ab.dot(c.point);
+    let d6 = -ac.dot(c.point);
+    if d6 >= zero && d5 <= d6 {
+        return vec![(c, T::one())];
+    }
+
+    // Edge region of `ac`.
+    let vb = d5*d2 - d1*d6;
+    if vb <= zero && d2 >= zero && d6 <= zero {
+        let w = d2 / (d2 - d6);
+        return vec![(a, T::one() - w), (c, w)];
+    }
+
+    // Edge region of `bc`.
+    let va = d3*d6 - d5*d4;
+    if va <= zero && (d4 - d3) >= zero && (d5 - d6) >= zero {
+        let w = (d4 - d3) / ((d4 - d3) + (d5 - d6));
+        return vec![(b, T::one() - w), (c, w)];
+    }
+
+    let denominator = va + vb + vc;
+
+    // The triangle is degenerate, so the closest point lies on an edge.
+    if denominator.is_zero() {
+        let edges = [closest_on_segment(a, b),
+                     closest_on_segment(b, c),
+                     closest_on_segment(a, c)];
+
+        return closest_of(edges.iter().cloned());
+    }
+
+    let v = vb / denominator;
+    let w = vc / denominator;
+    vec![(a, T::one() - v - w), (b, v), (c, w)]
+}
+
+fn closest_on_tetrahedron<T>(a: SupportPoint<T>,
+                             b: SupportPoint<T>,
+                             c: SupportPoint<T>,
+                             d: SupportPoint<T>) -> Vec<(SupportPoint<T>,T)>
+    where T: Decimal + Signed {
+    // Each face, along with the vertex opposite it.
+    let faces = [(a,b,c,d), (a,c,d,b), (a,d,b,c), (b,d,c,a)];
+
+    let candidates: Vec<_> = faces.iter().filter(|&&(p,q,r,opposite)| {
+        origin_outside_of_plane(p, q, r, opposite)
+    }).map(|&(p,q,r,_)| closest_on_triangle(p, q, r)).collect();
+
+    if !candidates.is_empty() {
+        return closest_of(candidates.into_iter());
+    }
+
+    // The origin is inside the tetrahedron, so calculate its weights
+    // from the signed volumes of the sub-tetrahedra.
+    let origin = Vector3::with_components(T::zero());
+    let volume = signed_volume(a.point, b.point, c.point, d.point);
+
+    vec![
+        (a, signed_volume(origin, b.point, c.point, d.point) / volume),
+        (b, signed_volume(a.point, origin, c.point, d.point) / volume),
+        (c, signed_volume(a.point, b.point, origin, d.point) / volume),
+        (d, signed_volume(a.point, b.point, c.point, origin) / volume),
+    ]
+}
+
+/// Checks whether the origin lies on the opposite side of the plane
+/// `abc` to the point `opposite`.
+///
+/// If the tetrahedron is flat, the origin is considered to be outside.
+fn origin_outside_of_plane<T>(a: SupportPoint<T>,
+                              b: SupportPoint<T>,
+                              c: SupportPoint<T>,
+                              opposite: SupportPoint<T>) -> bool
+    where T: Decimal + Signed {
+    let normal = (b.point - a.point).cross(c.point - a.point);
+    let sign_origin = -a.point.dot(normal);
+    let sign_opposite = (opposite.point - a.point).dot(normal);
+
+    sign_opposite.is_zero() || sign_origin * sign_opposite < T::zero()
+}
+
+fn signed_volume<T: Decimal>(a: Vector3<T>,
+                             b: Vector3<T>,
+                             c: Vector3<T>,
+                             d: Vector3<T>) -> T {
+    (b - a).dot((c - a).cross(d - a))
+}
+
+/// Picks the candidate sub-simplex whose point is closest to the origin.
+/// The earliest candidate wins ties.
+fn closest_of<T,I>(candidates: I) -> Vec<(SupportPoint<T>,T)>
+    where T: Decimal, I: Iterator<Item=Vec<(SupportPoint<T>,T)>> {
+    let mut best: Option<(Vec<(SupportPoint<T>,T)>,T)> = None;
+
+    for candidate in candidates {
+        let distance = weighted_sum(&candidate, |p| p.point).length_squared();
+
+        let better = match best {
+            Some((_, best_distance)) => distance < best_distance,
+            None => true,
+        };
+
+        if better {
+            best = Some((candidate, distance));
+        }
+    }
+
+    best.unwrap().0
+}
+
+#[test]
+fn test_sphere_distance() {
+    use super::Sphere;
+
+    let a = Sphere::new(Vector3(0.0f64, 0.0, 0.0), 1.0);
+    let b = Sphere::new(Vector3(4.0f64, 0.0, 0.0), 1.0);
+
+    let separation = distance(&a, &b).unwrap();
+    assert!((separation.distance - 2.0).abs() < 1.0e-6);
+    assert!((separation.point_a.x() - 1.0).abs() < 1.0e-6);
+    assert!((separation.point_b.x() - 3.0).abs() < 1.0e-6);
+}
+
+#[test]
+fn test_box_intersection() {
+    use super::Cuboid;
+
+    let a = Cuboid::new(Vector3(0.0f64, 0.0, 0.0), Vector3(1.0, 1.0, 1.0));
+    let b = Cuboid::new(Vector3(1.5f64, 1.5, 0.0), Vector3(1.0, 1.0, 1.0));
+    let c = Cuboid::new(Vector3(2.5f64, 0.0, 0.0), Vector3(0.25, 0.25, 0.25));
+
+    assert!(intersects(&a, &b));
+    assert!(!intersects(&a, &c));
+    assert!((distance(&a, &c).unwrap().distance - 1.25).abs() < 1.0e-6);
+}
@@ -0,0 +1,29 @@
+//! Collision detection.
+//!
+//! The broad phase (see `broad`) cheaply finds which pairs of objects might
+//! be touching, and the narrow phase then tests those pairs exactly.
+//!
+//! For the narrow phase, shapes are described purely by their support
+//! function (see `Support`), which GJK uses for overlap and distance
+//! queries, and EPA uses to find the penetration depth of overlapping shapes.
+//!
+//! Both algorithms only ever pick points in a fixed order and run for a
+//! bounded number of iterations, so the same inputs always give the same
+//! results.
+
+pub use self::shape::{Support,Sphere,Cuboid,Capsule,ConvexHull};
+pub use self::gjk::{Proximity,Separation,SupportPoint,proximity,intersects,distance};
+pub use self::epa::{Contact,penetration};
+pub use self::broad::{SweepAndPrune,Proxy,Event};
+
+pub mod shape;
+pub mod gjk;
+pub mod epa;
+pub mod broad;
+
+use num::Decimal;
+
+/// The tolerance used when checking for convergence.
+fn tolerance<T: Decimal>() -> T {
+    T::constant(1.0e-6)
+}
@@ -0,0 +1,248 @@
+use {Vertex,Triangle,Aabb,hull,mesh};
+use math::{Vector3,Matrix,Matrix3};
+use num::{Num,Decimal,Signed};
+
+/// A convex shape which can be queried for its furthest point in a direction.
+pub trait Support<T: Num>
+{
+    /// Gets the point on the shape which is furthest along `direction`.
+    ///
+    /// The direction does not need to be normalized. If several points are
+    /// equally far along the direction, the same one must always be returned.
+    fn support(&self, direction: Vector3<T>) -> Vector3<T>;
+}
+
+/// A sphere.
+#[derive(Copy,Clone)]
+pub struct Sphere<T: Num>
+{
+    center: Vector3<T>,
+    radius: T,
+}
+
+impl<T: Num> Sphere<T>
+{
+    pub fn new(center: Vector3<T>, radius: T) -> Self {
+        Sphere {
+            center: center,
+            radius: radius,
+        }
+    }
+
+    pub fn center(&self) -> Vector3<T> { self.center }
+    pub fn radius(&self) -> T { self.radius }
+}
+
+impl<T: Decimal> Support<T> for Sphere<T>
+{
+    fn support(&self, direction: Vector3<T>) -> Vector3<T> {
+        self.center + with_length(direction, self.radius)
+    }
+}
+
+/// A box, which may be rotated about its center.
+#[derive(Copy,Clone)]
+pub struct Cuboid<T: Num>
+{
+    center: Vector3<T>,
+    half_extents: Vector3<T>,
+    /// The rotation from the local space of the box into world space.
+    orientation: Matrix3<T>,
+}
+
+impl<T: Num> Cuboid<T>
+{
+    /// Creates an axis-aligned box.
+    pub fn new(center: Vector3<T>,
+               half_extents: Vector3<T>) -> Self {
+        Cuboid::with_orientation(center, half_extents, Matrix::identity())
+    }
+
+    /// Creates a box with a rotation matrix.
+    pub fn with_orientation(center: Vector3<T>,
+                            half_extents: Vector3<T>,
+                            orientation: Matrix3<T>) -> Self {
+        Cuboid {
+            center: center,
+            half_extents: half_extents,
+            orientation: orientation,
+        }
+    }
+
+    pub fn center(&self) -> Vector3<T> { self.center }
+    pub fn half_extents(&self) -> Vector3<T> { self.half_extents }
+    pub fn orientation(&self) -> Matrix3<T> { self.orientation }
+}
+
+impl<T: Num> Support<T> for Cuboid<T>
+{
+    fn support(&self, direction: Vector3<T>) -> Vector3<T> {
+        let m = self.orientation;
+
+        // Multiplying a row vector by the matrix rotates the direction
+        // into the local space of the box.
+        let local = direction * m;
+        let Vector3(x,y,z) = box_corner(local, self.half_extents);
+
+        let wx = m[(0,0)]*x + m[(0,1)]*y + m[(0,2)]*z;
+        let wy = m[(1,0)]*x + m[(1,1)]*y + m[(1,2)]*z;
+        let wz = m[(2,0)]*x + m[(2,1)]*y + m[(2,2)]*z;
+
+        self.center + Vector3(wx, wy, wz)
+    }
+}
+
+impl<T: Num> From<Aabb<T>> for Cuboid<T>
+{
+    fn from(aabb: Aabb<T>) -> Cuboid<T> {
+        Cuboid::new(aabb.center(), aabb.half_extents())
+    }
+}
+
+impl<T: Num> Support<T> for Aabb<T>
+{
+    fn support(&self, direction: Vector3<T>) -> Vector3<T> {
+        self.center() + box_corner(direction, self.half_extents())
+    }
+}
+
+/// A capsule.
+///
+/// This is the set of points within `radius` of the line segment
+/// from `start` to `end`.
+#[derive(Copy,Clone)]
+pub struct Capsule<T: Num>
+{
+    start: Vector3<T>,
+    end: Vector3<T>,
+    radius: T,
+}
+
+impl<T: Num> Capsule<T>
+{
+    pub fn new(start: Vector3<T>,
+               end: Vector3<T>,
+               radius: T) -> Self {
+        Capsule {
+            start: start,
+            end: end,
+            radius: radius,
+        }
+    }
+
+    pub fn start(&self) -> Vector3<T> { self.start }
+    pub fn end(&self) -> Vector3<T> { self.end }
+    pub fn radius(&self) -> T { self.radius }
+}
+
+impl<T: Decimal> Support<T> for Capsule<T>
+{
+    fn support(&self, direction: Vector3<T>) -> Vector3<T> {
+        let axis = self.end - self.start;
+
+        let endpoint = if direction.dot(axis) > T::zero() {
+            self.end
+        } else {
+            self.start
+        };
+
+        endpoint + with_length(direction, self.radius)
+    }
+}
+
+/// The convex hull of a set of vertices.
+#[derive(Clone)]
+pub struct ConvexHull<V: Vertex>
+{
+    vertices: Vec<V>,
+}
+
+impl<V: Vertex> ConvexHull<V>
+{
+    /// Creates the convex hull of a set of vertices.
+    /// Panics if there are no vertices.
+    pub fn new<I>(vertices: I) -> Self
+        where I: Iterator<Item=V> {
+        let vertices: Vec<V> = vertices.collect();
+        assert!(!vertices.is_empty(), "a convex hull must have at least one vertex");
+
+        ConvexHull {
+            vertices: vertices,
+        }
+    }
+
+    pub fn vertices<'a>(&'a self) -> ::std::slice::Iter<'a,V> {
+        self.vertices.iter()
+    }
+}
+
+impl<T: Decimal + Signed> ConvexHull<Vector3<T>>
+{
+    /// Creates the convex hull of a point cloud, keeping only the
+    /// points which lie on the hull.
+    /// Panics if there are no points.
+    pub fn from_points<I>(points: I) -> Self
+        where I: Iterator<Item=Vector3<T>> {
+        let hull: mesh::Buffer<usize,Vector3<T>> = hull::quickhull(points);
+
+        ConvexHull::new(hull.vertices.into_iter())
+    }
+}
+
+impl<V: Vertex> Support<V::T> for ConvexHull<V>
+{
+    fn support(&self, direction: Vector3<V::T>) -> Vector3<V::T> {
+        furthest(self.vertices.iter().map(|v| v.coords()), direction)
+    }
+}
+
+impl<V: Vertex> Support<V::T> for Triangle<V>
+{
+    fn support(&self, direction: Vector3<V::T>) -> Vector3<V::T> {
+        furthest(self.points().map(|v| v.coords()), direction)
+    }
+}
+
+/// Gets the point which is furthest along a direction.
+/// The earliest point wins ties.
+fn furthest<T,I>(mut points: I, direction: Vector3<T>) -> Vector3<T>
+    where T: Num, I: Iterator<Item=Vector3<T>> {
+    let mut best = points.next().unwrap();
+    let mut best_distance = best.dot(direction);
+
+    for point in points {
+        let distance = point.dot(direction);
+
+        if distance > best_distance {
+            best = point;
+            best_distance = distance;
+        }
+    }
+
+    best
+}
+
+/// Gets the corner of a box centered at the origin which is furthest
+/// along a direction.
+fn box_corner<T: Num>(direction: Vector3<T>,
+                      half_extents: Vector3<T>) -> Vector3<T> {
+    let Vector3(dx,dy,dz) = direction;
+    let Vector3(hx,hy,hz) = half_extents;
+    let zero = T::zero();
+
+    let pick = |d: T, h: T| if d < zero { zero - h } else { h };
+
+    Vector3(pick(dx,hx), pick(dy,hy), pick(dz,hz))
+}
+
+/// Scales a direction to a specific length.
+/// The zero vector is mapped onto the x axis.
+fn with_length<T: Decimal>(direction: Vector3<T>, length: T) -> Vector3<T> {
+    let length_squared = direction.length_squared();
+
+    if length_squared.is_zero() {
+        Vector3(length, T::zero(), T::zero())
+    } else {
+        direction * (length * length_squared.rsqrt())
+    }
+}
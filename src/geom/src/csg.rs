@@ -0,0 +1,416 @@
+//! Constructive solid geometry.
+//!
+//! Meshes are converted into BSP trees of polygons, which are clipped
+//! against each other to find the parts of each mesh which should be kept.
+//! This is a port of the algorithm used by Evan Wallace's csg.js.
+//!
+//! Every mesh must be closed, and have its triangles wound
+//! counter-clockwise when seen from the outside.
+
+use mesh;
+use math::Vector3;
+use num::{self,Integer,Decimal,Signed};
+use std;
+
+/// A vertex which can be split by CSG operations.
+pub trait Vertex : ::Vertex
+{
+    /// Linearly interpolates all attributes between two vertices.
+    fn lerp(self, other: Self, t: Self::T) -> Self;
+
+    /// Flips any attributes which depend on which way the surface faces,
+    /// such as normals.
+    fn flip(self) -> Self { self }
+}
+
+impl<T> Vertex for Vector3<T>
+    where T: Decimal
+{
+    fn lerp(self, other: Self, t: T) -> Self {
+        self + (other - self) * t
+    }
+}
+
+/// Computes the union of two solids.
+pub fn union<I,V>(a: &mesh::Buffer<I,V>, b: &mesh::Buffer<I,V>) -> mesh::Buffer<I,V>
+    where I: Integer, V: Vertex, V::T: Decimal + Signed {
+    let mut a = Node::new(polygons(a));
+    let mut b = Node::new(polygons(b));
+
+    a.clip_to(&b);
+    b.clip_to(&a);
+    b.invert();
+    b.clip_to(&a);
+    b.invert();
+    a.build(b.all_polygons());
+
+    triangulate(a.all_polygons())
+}
+
+/// Computes the intersection of two solids.
+pub fn intersection<I,V>(a: &mesh::Buffer<I,V>, b: &mesh::Buffer<I,V>) -> mesh::Buffer<I,V>
+    where I: Integer, V: Vertex, V::T: Decimal + Signed {
+    let mut a = Node::new(polygons(a));
+    let mut b = Node::new(polygons(b));
+
+    a.invert();
+    b.clip_to(&a);
+    b.invert();
+    a.clip_to(&b);
+    b.clip_to(&a);
+    a.build(b.all_polygons());
+    a.invert();
+
+    triangulate(a.all_polygons())
+}
+
+/// Subtracts the second solid from the first.
+pub fn difference<I,V>(a: &mesh::Buffer<I,V>, b: &mesh::Buffer<I,V>) -> mesh::Buffer<I,V>
+    where I: Integer, V: Vertex, V::T: Decimal + Signed {
+    let mut a = Node::new(polygons(a));
+    let mut b = Node::new(polygons(b));
+
+    a.invert();
+    a.clip_to(&b);
+    b.clip_to(&a);
+    b.invert();
+    b.clip_to(&a);
+    b.invert();
+    a.build(b.all_polygons());
+    a.invert();
+
+    triangulate(a.all_polygons())
+}
+
+/// A plane, containing the points `p` where `normal.dot(p) == w`.
+#[derive(Copy,Clone)]
+struct Plane<T: Decimal>
+{
+    normal: Vector3<T>,
+    w: T,
+}
+
+/// A convex polygon.
+#[derive(Clone)]
+struct Polygon<V: Vertex>
+    where V::T: Decimal
+{
+    vertices: Vec<V>,
+    plane: Plane<V::T>,
+}
+
+/// The result of splitting polygons by a plane.
+struct Parts<V: Vertex>
+    where V::T: Decimal
+{
+    coplanar_front: Vec<Polygon<V>>,
+    coplanar_back: Vec<Polygon<V>>,
+    front: Vec<Polygon<V>>,
+    back: Vec<Polygon<V>>,
+}
+
+/// A node in a BSP tree.
+struct Node<V: Vertex>
+    where V::T: Decimal
+{
+    plane: Option<Plane<V::T>>,
+    front: Option<Box<Node<V>>>,
+    back: Option<Box<Node<V>>>,
+    polygons: Vec<Polygon<V>>,
+}
+
+/// The distance within which points are considered to lie on a plane.
+fn epsilon<T: Decimal>() -> T {
+    T::constant(1.0e-5)
+}
+
+impl<T: Decimal + Signed> Plane<T>
+{
+    /// Gets the plane through three points.
+    /// Returns `None` if the points are collinear.
+    fn from_points(a: Vector3<T>, b: Vector3<T>, c: Vector3<T>) -> Option<Self> {
+        let normal = (b - a).cross(c - a);
+        let length_squared = normal.length_squared();
+
+        if length_squared.is_zero() {
+            None
+        } else {
+            let normal = normal * length_squared.rsqrt();
+            Some(Plane { normal: normal, w: normal.dot(a) })
+        }
+    }
+
+    fn flip(self) -> Self {
+        Plane { normal: -self.normal, w: -self.w }
+    }
+
+    /// Splits a polygon by the plane.
+    fn split<V>(&self, polygon: Polygon<V>, parts: &mut Parts<V>)
+        where V: Vertex<T=T> {
+        const COPLANAR: u8 = 0;
+        const FRONT: u8 = 1;
+        const BACK: u8 = 2;
+        const SPANNING: u8 = 3;
+
+        let epsilon: T = epsilon();
+
+        let types: Vec<u8> = polygon.vertices.iter().map(|v| {
+            let t = self.normal.dot(v.coords()) - self.w;
+
+            if t < -epsilon { BACK } else if t > epsilon { FRONT } else { COPLANAR }
+        }).collect();
+
+        match types.iter().fold(COPLANAR, |acc, &t| acc | t) {
+            COPLANAR => {
+                if self.normal.dot(polygon.plane.normal) > T::zero() {
+                    parts.coplanar_front.push(polygon);
+                } else {
+                    parts.coplanar_back.push(polygon);
+                }
+            },
+            FRONT => parts.front.push(polygon),
+            BACK => parts.back.push(polygon),
+            _ => {
+                let mut front = Vec::new();
+                let mut back = Vec::new();
+                let count = polygon.vertices.len();
+
+                for i in 0..count {
+                    let j = (i + 1) % count;
+                    let (ti, tj) = (types[i], types[j]);
+                    let (vi, vj) = (polygon.vertices[i], polygon.vertices[j]);
+
+                    if ti != BACK { front.push(vi); }
+                    if ti != FRONT { back.push(vi); }
+
+                    if (ti | tj) == SPANNING {
+                        let (pi, pj) = (vi.coords(), vj.coords());
+                        let t = (self.w - self.normal.dot(pi)) / self.normal.dot(pj - pi);
+                        let v = vi.lerp(vj, t);
+
+                        front.push(v);
+                        back.push(v);
+                    }
+                }
+
+                if front.len() >= 3 {
+                    parts.front.push(Polygon { vertices: front, plane: polygon.plane });
+                }
+                if back.len() >= 3 {
+                    parts.back.push(Polygon { vertices: back, plane: polygon.plane });
+                }
+            },
+        }
+    }
+}
+
+impl<V> Polygon<V>
+    where V: Vertex, V::T: Decimal + Signed
+{
+    fn flip(self) -> Self {
+        Polygon {
+            vertices: self.vertices.into_iter().rev().map(|v| v.flip()).collect(),
+            plane: self.plane.flip(),
+        }
+    }
+}
+
+impl<V> Parts<V>
+    where V: Vertex, V::T: Decimal
+{
+    fn new() -> Self {
+        Parts {
+            coplanar_front: Vec::new(),
+            coplanar_back: Vec::new(),
+            front: Vec::new(),
+            back: Vec::new(),
+        }
+    }
+}
+
+impl<V> Node<V>
+    where V: Vertex, V::T: Decimal + Signed
+{
+    fn new(polygons: Vec<Polygon<V>>) -> Self {
+        let mut node = Node {
+            plane: None,
+            front: None,
+            back: None,
+            polygons: Vec::new(),
+        };
+
+        node.build(polygons);
+        node
+    }
+
+    /// Converts solid space to empty space and empty space to solid space.
+    fn invert(&mut self) {
+        let polygons = std::mem::replace(&mut self.polygons, Vec::new());
+        self.polygons = polygons.into_iter().map(|p| p.flip()).collect();
+        self.plane = self.plane.map(|p| p.flip());
+
+        if let Some(ref mut front) = self.front { front.invert(); }
+        if let Some(ref mut back) = self.back { back.invert(); }
+
+        std::mem::swap(&mut self.front, &mut self.back);
+    }
+
+    /// Removes the parts of the polygons which are inside this tree.
+    fn clip_polygons(&self, polygons: Vec<Polygon<V>>) -> Vec<Polygon<V>> {
+        let plane = match self.plane {
+            Some(plane) => plane,
+            None => return polygons,
+        };
+
+        let mut parts = Parts::new();
+        for polygon in polygons {
+            plane.split(polygon, &mut parts);
+        }
+
+        let mut front = parts.front;
+        front.extend(parts.coplanar_front);
+        let mut back = parts.back;
+        back.extend(parts.coplanar_back);
+
+        let mut front = match self.front {
+            Some(ref node) => node.clip_polygons(front),
+            None => front,
+        };
+
+        let back = match self.back {
+            Some(ref node) => node.clip_polygons(back),
+            None => Vec::new(),
+        };
+
+        front.extend(back);
+        front
+    }
+
+    /// Removes the parts of this tree which are inside another tree.
+    fn clip_to(&mut self, other: &Node<V>) {
+        let polygons = std::mem::replace(&mut self.polygons, Vec::new());
+        self.polygons = other.clip_polygons(polygons);
+
+        if let Some(ref mut front) = self.front { front.clip_to(other); }
+        if let Some(ref mut back) = self.back { back.clip_to(other); }
+    }
+
+    /// Gets every polygon in the tree.
+    fn all_polygons(&self) -> Vec<Polygon<V>> {
+        let mut polygons = self.polygons.clone();
+
+        if let Some(ref front) = self.front { polygons.extend(front.all_polygons()); }
+        if let Some(ref back) = self.back { polygons.extend(back.all_polygons()); }
+
+        polygons
+    }
+
+    /// Adds polygons to the tree.
+    fn build(&mut self, polygons: Vec<Polygon<V>>) {
+        if polygons.is_empty() {
+            return;
+        }
+
+        let plane = match self.plane {
+            Some(plane) => plane,
+            None => polygons[0].plane,
+        };
+        self.plane = Some(plane);
+
+        let mut parts = Parts::new();
+        for polygon in polygons {
+            plane.split(polygon, &mut parts);
+        }
+
+        self.polygons.extend(parts.coplanar_front);
+        self.polygons.extend(parts.coplanar_back);
+
+        if !parts.front.is_empty() {
+            match self.front {
+                Some(ref mut node) => node.build(parts.front),
+                None => self.front = Some(Box::new(Node::new(parts.front))),
+            }
+        }
+
+        if !parts.back.is_empty() {
+            match self.back {
+                Some(ref mut node) => node.build(parts.back),
+                None => self.back = Some(Box::new(Node::new(parts.back))),
+            }
+        }
+    }
+}
+
+/// Converts the triangles of a buffer into polygons.
+/// Degenerate triangles are skipped.
+fn polygons<I,V>(buffer: &mesh::Buffer<I,V>) -> Vec<Polygon<V>>
+    where I: Integer, V: Vertex, V::T: Decimal + Signed {
+    buffer.indices.chunks(3).filter(|t| t.len() == 3).filter_map(|triangle| {
+        let vertices: Vec<V> = triangle.iter().map(|&i| {
+            buffer.vertices[num::cast::<I,usize>(i)]
+        }).collect();
+
+        Plane::from_points(vertices[0].coords(), vertices[1].coords(), vertices[2].coords())
+              .map(|plane| Polygon { vertices: vertices, plane: plane })
+    }).collect()
+}
+
+/// Converts polygons into a triangle buffer.
+fn triangulate<I,V>(polygons: Vec<Polygon<V>>) -> mesh::Buffer<I,V>
+    where I: Integer, V: Vertex, V::T: Decimal {
+    let mut buffer = mesh::Buffer::empty();
+
+    for polygon in polygons {
+        let first = buffer.vertices.len();
+
+        for i in 1..(polygon.vertices.len() - 1) {
+            buffer.indices.push(num::cast(first));
+            buffer.indices.push(num::cast(first + i));
+            buffer.indices.push(num::cast(first + i + 1));
+        }
+
+        buffer.vertices.extend(polygon.vertices);
+    }
+
+    buffer
+}
+
+#[cfg(test)]
+fn cube(center: Vector3<f64>) -> mesh::Buffer<u32,Vector3<f64>> {
+    let vertices: Vec<_> = (0..8).map(|i| {
+        let corner = Vector3((i & 1) as f64, ((i >> 1) & 1) as f64, ((i >> 2) & 1) as f64);
+        center + corner * 2.0 - Vector3(1.0, 1.0, 1.0)
+    }).collect();
+
+    let indices = vec![
+        0,2,1, 1,2,3, // -z
+        4,5,6, 5,7,6, // +z
+        0,1,4, 1,5,4, // -y
+        2,6,3, 3,6,7, // +y
+        0,4,2, 2,4,6, // -x
+        1,3,5, 3,7,5, // +x
+    ];
+
+    mesh::Buffer::new(indices, vertices)
+}
+
+#[cfg(test)]
+fn volume(buffer: &mesh::Buffer<u32,Vector3<f64>>) -> f64 {
+    buffer.indices.chunks(3).fold(0.0, |acc, t| {
+        let (a,b,c) = (buffer.vertices[t[0] as usize],
+                       buffer.vertices[t[1] as usize],
+                       buffer.vertices[t[2] as usize]);
+        acc + a.dot(b.cross(c)) / 6.0
+    })
+}
+
+#[test]
+fn test_csg_volumes() {
+    let a = cube(Vector3(0.0, 0.0, 0.0));
+    let b = cube(Vector3(1.0, 0.0, 0.0));
+
+    assert!((volume(&a) - 8.0).abs() < 1.0e-9);
+    assert!((volume(&union(&a, &b)) - 12.0).abs() < 1.0e-9);
+    assert!((volume(&intersection(&a, &b)) - 4.0).abs() < 1.0e-9);
+    assert!((volume(&difference(&a, &b)) - 4.0).abs() < 1.0e-9);
+}
@@ -2,7 +2,7 @@
 pub use self::wavefront::Wavefront;
 
 use mesh;
-use std::io;
+use std::{error,fmt,io};
 
 pub mod wavefront;
 pub mod tds;
@@ -11,16 +11,48 @@ pub mod tds;
 pub trait Format<I, V>
 {
     /// Loads the geometry into a mesh builder.
-    fn load_with_builder<R>(read: R, builder: &mut mesh::StaticBuilder<I,V>)
+    fn load_with_builder<R>(read: R, builder: &mut mesh::StaticBuilder<I,V>) -> Result<(),Error>
         where R: io::Read;
 
     /// Loads the geometry and returns it as a mesh.
-    fn load<R>(read: R) -> mesh::StaticData<I,V>
+    fn load<R>(read: R) -> Result<mesh::StaticData<I,V>,Error>
         where R: io::Read {
         let mut builder = mesh::StaticBuilder::new();
 
-        Self::load_with_builder(read, &mut builder);
+        Self::load_with_builder(read, &mut builder)?;
 
-        builder.into()
+        Ok(builder.into())
     }
 }
+
+/// An error which occurred while loading geometry.
+#[derive(Debug)]
+pub enum Error
+{
+    /// The geometry could not be read.
+    Io(io::Error),
+    /// The mesh has more distinct vertices than its index type
+    /// can address.
+    TooManyVertices(usize),
+}
+
+impl From<io::Error> for Error
+{
+    fn from(error: io::Error) -> Error {
+        Error::Io(error)
+    }
+}
+
+impl fmt::Display for Error
+{
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::Io(ref error) => write!(fmt, "{}", error),
+            Error::TooManyVertices(count) => {
+                write!(fmt, "the mesh has {} distinct vertices, which is too many for its index type", count)
+            },
+        }
+    }
+}
+
+impl error::Error for Error { }
@@ -1,6 +1,7 @@
 
 use math::{Scalar,Vector3};
 use {mesh,util,Format};
+use formats::Error;
 use num;
 
 use std::io;
@@ -39,6 +40,27 @@ impl Vertex
     }
 }
 
+impl ::csg::Vertex for Vertex
+{
+    fn lerp(self, other: Vertex, t: Scalar) -> Vertex {
+        let normal = match (self.normal, other.normal) {
+            (Some(a), Some(b)) => Some(a.lerp(b, t)),
+            _ => None,
+        };
+
+        let uv = match (self.uv, other.uv) {
+            (Some((u1,v1)), Some((u2,v2))) => Some((u1 + (u2-u1)*t, v1 + (v2-v1)*t)),
+            _ => None,
+        };
+
+        Vertex::new(self.position.lerp(other.position, t), normal, uv)
+    }
+
+    fn flip(self) -> Vertex {
+        Vertex::new(self.position, self.normal.map(|n| -n), self.uv)
+    }
+}
+
 // Gets the position of a vertex
 impl From<Vertex> for Vector3 {
     fn from(vert: Vertex) -> Vector3 {
@@ -50,9 +72,10 @@ pub struct Wavefront;
 
 impl<I,V> Format<I,V> for Wavefront
     where I: num::Integer,
-          V: From<Vertex>
+          V: From<Vertex>,
+          usize: num::TryCast<I>
 {
-    fn load_with_builder<R>(read: R, builder: &mut mesh::StaticBuilder<I,V>)
+    fn load_with_builder<R>(read: R, builder: &mut mesh::StaticBuilder<I,V>) -> Result<(),Error>
         where R: io::Read {
         use std::io::BufRead;
 
@@ -68,12 +91,9 @@ impl<I,V> Format<I,V> for Wavefront
         // We later sort and deduplicate.
         let mut distinct_vertices: Vec<(i32,i32,i32)> = Vec::new();
 
-        let statements = reader.lines()
-                               .map(|a| self::load::parse_line(&a.unwrap()));
-
         // store relevant mesh data into their respective arrays
-        for stmt in statements {
-            match stmt {
+        for line in reader.lines() {
+            match self::load::parse_line(&line?) {
                 Statement::Vertex(x,y,z,w) => {
                     points.push((x,y,z,w))
                 },
@@ -122,6 +142,13 @@ impl<I,V> Format<I,V> for Wavefront
         drop(normals);
         drop(uvs);
 
+        // Every vertex must be addressable by the index type.
+        if let Some(last) = vertex_index_map.len().checked_sub(1) {
+            if num::checked_cast::<usize,I>(last).is_none() {
+                return Err(Error::TooManyVertices(vertex_index_map.len()));
+            }
+        }
+
         let faces: Vec<Vec<I>> = face_indices.into_iter()
                                                 .map(|vec| {
             vec.into_iter().map(|(v,vn,vt)| {
@@ -148,6 +175,7 @@ impl<I,V> Format<I,V> for Wavefront
             }
         }
 
+        Ok(())
     }
 }
 
@@ -259,3 +287,36 @@ pub mod load
         Statement::SmoothShading(enabled)
     }
 }
+
+#[test]
+fn test_load_triangle() {
+    use Format;
+
+    let source = "v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1// 2// 3//\n";
+    let mesh: mesh::StaticData<u16,Vector3> = Wavefront::load(source.as_bytes()).unwrap();
+    let buffer = mesh.buffers().next().unwrap();
+
+    assert_eq!(buffer.vertices.len(), 3);
+    assert_eq!(buffer.indices, vec![0, 1, 2]);
+}
+
+#[test]
+fn test_index_overflow() {
+    use Format;
+
+    // Each face has its own vertices, so there are more than a u16 can index.
+    let faces = 21846;
+    let mut source = String::new();
+    for i in 0..faces * 3 {
+        source.push_str(&format!("v {} 0 0\n", i));
+    }
+    for i in 0..faces {
+        source.push_str(&format!("f {}// {}// {}//\n", i*3 + 1, i*3 + 2, i*3 + 3));
+    }
+
+    let result: Result<mesh::StaticData<u16,Vector3>,Error> = Wavefront::load(source.as_bytes());
+    match result {
+        Err(Error::TooManyVertices(count)) => assert_eq!(count, faces * 3),
+        _ => panic!("expected the index type to overflow"),
+    }
+}
@@ -0,0 +1,343 @@
+//! Convex hull computation.
+
+use mesh;
+use math::Vector3;
+use num::{self,Integer,Decimal,Signed};
+use std::cmp::Ordering;
+
+/// A triangle on the hull under construction.
+struct Face<T: Decimal>
+{
+    indices: [usize; 3],
+    normal: Vector3<T>,
+    offset: T,
+    /// The points which lie outside of this face.
+    outside: Vec<usize>,
+    alive: bool,
+}
+
+impl<T: Decimal + Signed> Face<T>
+{
+    fn new(points: &[Vector3<T>], indices: [usize; 3]) -> Self {
+        let a = points[indices[0]];
+        let normal = (points[indices[1]] - a).cross(points[indices[2]] - a);
+        let length_squared = normal.length_squared();
+
+        let normal = if length_squared.is_zero() {
+            normal
+        } else {
+            normal * length_squared.rsqrt()
+        };
+
+        Face {
+            indices: indices,
+            normal: normal,
+            offset: normal.dot(a),
+            outside: Vec::new(),
+            alive: true,
+        }
+    }
+
+    /// Gets the signed distance from the plane of the face to a point.
+    fn distance(&self, point: Vector3<T>) -> T {
+        self.normal.dot(point) - self.offset
+    }
+}
+
+/// Computes the convex hull of a set of points using quickhull.
+///
+/// The hull is returned as an indexed triangle mesh containing only the
+/// points on the hull. Triangles are wound counter-clockwise when seen
+/// from outside the hull.
+///
+/// If all of the points lie on a plane, both sides of the flat hull are
+/// included. If they all lie on a line, the mesh has the two end points
+/// and no triangles.
+pub fn quickhull<I,T,P>(points: P) -> mesh::Buffer<I,Vector3<T>>
+    where I: Integer, usize: num::TryCast<I>, T: Decimal + Signed, P: Iterator<Item=Vector3<T>> {
+    let points: Vec<Vector3<T>> = points.collect();
+
+    if points.is_empty() {
+        return mesh::Buffer::empty();
+    }
+
+    let epsilon = tolerance(&points);
+
+    // Find the points furthest apart along each axis, and pick the
+    // pair furthest from each other.
+    let extremes: Vec<usize> = (0..3).flat_map(|axis| {
+        let component = |i: &usize| points[*i].components().nth(axis).unwrap();
+        let min = (0..points.len()).min_by(|a,b| compare(component(a), component(b))).unwrap();
+        let max = (0..points.len()).max_by(|a,b| compare(component(a), component(b))).unwrap();
+        vec![min, max]
+    }).collect();
+
+    let (a, b) = {
+        let mut best = (extremes[0], extremes[1]);
+        for &i in extremes.iter() {
+            for &j in extremes.iter() {
+                let distance = (points[i] - points[j]).length_squared();
+                if distance > (points[best.0] - points[best.1]).length_squared() {
+                    best = (i, j);
+                }
+            }
+        }
+        best
+    };
+
+    if (points[a] - points[b]).length_squared() <= epsilon*epsilon {
+        return build_buffer(&points, &[a], &[]);
+    }
+
+    // The point furthest from the line `ab`.
+    let ab = points[b] - points[a];
+    let c = furthest(&points, |p| ab.cross(p - points[a]).length_squared());
+
+    if ab.cross(points[c] - points[a]).length_squared() <= epsilon*epsilon*ab.length_squared() {
+        return build_buffer(&points, &[a, b], &[]);
+    }
+
+    // The point furthest from the plane `abc`.
+    let base = Face::new(&points, [a, b, c]);
+    let d = furthest(&points, |p| base.distance(p).abs());
+
+    if base.distance(points[d]).abs() <= epsilon {
+        return planar_hull(&points, base.normal, a, b);
+    }
+
+    // Build the initial tetrahedron, wound so that every face
+    // points away from the fourth vertex.
+    let mut faces: Vec<Face<T>> = Vec::new();
+
+    for &(indices, opposite) in [([a,b,c],d), ([a,d,b],c), ([a,c,d],b), ([b,d,c],a)].iter() {
+        let face = Face::new(&points, indices);
+
+        if face.distance(points[opposite]) > T::zero() {
+            faces.push(Face::new(&points, [indices[0], indices[2], indices[1]]));
+        } else {
+            faces.push(face);
+        }
+    }
+
+    let unassigned: Vec<usize> = (0..points.len()).collect();
+    assign_outside(&points, &mut faces, 0, unassigned, epsilon);
+
+    // Repeatedly push out the face with the furthest point outside of it.
+    while let Some(face_index) = faces.iter().position(|f| f.alive && !f.outside.is_empty()) {
+        let eye = {
+            let face = &faces[face_index];
+            let mut best = face.outside[0];
+            for &i in face.outside.iter() {
+                if face.distance(points[i]) > face.distance(points[best]) {
+                    best = i;
+                }
+            }
+            best
+        };
+
+        // Remove every face the eye point can see, finding the edges
+        // on the boundary of the hole this leaves.
+        let mut horizon: Vec<(usize,usize)> = Vec::new();
+        let mut orphans: Vec<usize> = Vec::new();
+
+        for face in faces.iter_mut().filter(|f| f.alive) {
+            if face.distance(points[eye]) <= epsilon {
+                continue;
+            }
+
+            face.alive = false;
+            orphans.extend(face.outside.drain(..).filter(|&i| i != eye));
+
+            let [i,j,k] = face.indices;
+            for &(from,to) in [(i,j), (j,k), (k,i)].iter() {
+                match horizon.iter().position(|&edge| edge == (to,from)) {
+                    Some(pos) => { horizon.remove(pos); },
+                    None => horizon.push((from,to)),
+                }
+            }
+        }
+
+        let first_new = faces.len();
+        for &(from,to) in horizon.iter() {
+            faces.push(Face::new(&points, [from, to, eye]));
+        }
+
+        assign_outside(&points, &mut faces, first_new, orphans, epsilon);
+    }
+
+    let triangles: Vec<[usize; 3]> = faces.iter().filter(|f| f.alive).map(|f| f.indices).collect();
+    let used: Vec<usize> = {
+        let mut used: Vec<usize> = triangles.iter().flat_map(|t| t.iter().cloned()).collect();
+        used.sort();
+        used.dedup();
+        used
+    };
+
+    build_buffer(&points, &used, &triangles)
+}
+
+/// Gives each point to the first face (starting at `first_face`) it
+/// lies outside of. Points inside every face are dropped.
+fn assign_outside<T>(points: &[Vector3<T>],
+                     faces: &mut [Face<T>],
+                     first_face: usize,
+                     candidates: Vec<usize>,
+                     epsilon: T)
+    where T: Decimal + Signed {
+    for i in candidates {
+        let point = points[i];
+
+        if let Some(face) = faces[first_face..].iter_mut().find(|f| f.distance(point) > epsilon) {
+            face.outside.push(i);
+        }
+    }
+}
+
+/// Computes the hull of points which all lie on a plane.
+///
+/// The points are projected onto the plane, and their 2D hull
+/// is found using Andrew's monotone chain.
+fn planar_hull<I,T>(points: &[Vector3<T>],
+                    normal: Vector3<T>,
+                    a: usize,
+                    b: usize) -> mesh::Buffer<I,Vector3<T>>
+    where I: Integer, usize: num::TryCast<I>, T: Decimal + Signed {
+    let u = points[b] - points[a];
+    let u = u * u.length_squared().rsqrt();
+    let v = normal.cross(u);
+
+    let project = |i: usize| {
+        let p = points[i] - points[a];
+        (p.dot(u), p.dot(v))
+    };
+
+    let mut order: Vec<usize> = (0..points.len()).collect();
+    order.sort_by(|&i,&j| {
+        let (pi,pj) = (project(i), project(j));
+        compare(pi.0, pj.0).then(compare(pi.1, pj.1))
+    });
+
+    // Twice the signed area of the triangle `opq`.
+    let turn = |o: usize, p: usize, q: usize| {
+        let (o,p,q) = (project(o), project(p), project(q));
+        (p.0 - o.0)*(q.1 - o.1) - (p.1 - o.1)*(q.0 - o.0)
+    };
+
+    let mut hull: Vec<usize> = Vec::new();
+
+    for pass in 0..2 {
+        let start = hull.len();
+        let sweep: Vec<usize> = if pass == 0 { order.clone() } else { order.iter().rev().cloned().collect() };
+
+        for i in sweep {
+            while hull.len() >= start + 2 && turn(hull[hull.len()-2], hull[hull.len()-1], i) <= T::zero() {
+                hull.pop();
+            }
+            hull.push(i);
+        }
+
+        // The last point is the first point of the next chain.
+        hull.pop();
+    }
+
+    let mut triangles = Vec::new();
+    for i in 1..(hull.len() - 1) {
+        triangles.push([hull[0], hull[i], hull[i+1]]);
+        triangles.push([hull[0], hull[i+1], hull[i]]);
+    }
+
+    build_buffer(points, &hull, &triangles)
+}
+
+/// Builds a buffer out of a subset of the points.
+fn build_buffer<I,T>(points: &[Vector3<T>],
+                     used: &[usize],
+                     triangles: &[[usize; 3]]) -> mesh::Buffer<I,Vector3<T>>
+    where I: Integer, usize: num::TryCast<I>, T: Decimal {
+    // Map each point to its position in the buffer.
+    let mut remap = vec![0; points.len()];
+    for (position, &i) in used.iter().enumerate() {
+        remap[i] = position;
+    }
+
+    let vertices = used.iter().map(|&i| points[i]).collect();
+    let indices = mesh::cast_indices(triangles.iter().flat_map(|t| t.iter().map(|&i| remap[i])));
+
+    mesh::Buffer::new(indices, vertices)
+}
+
+/// Gets the point with the largest value of a function.
+/// The earliest point wins ties.
+fn furthest<T,F>(points: &[Vector3<T>], f: F) -> usize
+    where T: Decimal, F: Fn(Vector3<T>) -> T {
+    let mut best = 0;
+    for i in 1..points.len() {
+        if f(points[i]) > f(points[best]) {
+            best = i;
+        }
+    }
+    best
+}
+
+/// Gets the distance below which points are considered coplanar,
+/// based on the scale of the input and the precision of `T`.
+fn tolerance<T: Decimal + Signed>(points: &[Vector3<T>]) -> T {
+    let scale = points.iter().fold(T::zero(), |acc, p| {
+        let Vector3(x,y,z) = p.as_positive();
+        let largest = if x > y { x } else { y };
+        let largest = if largest > z { largest } else { z };
+        if largest > acc { largest } else { acc }
+    });
+
+    T::constant(3.0) * (scale + T::one()) * T::epsilon()
+}
+
+fn compare<T: PartialOrd>(a: T, b: T) -> Ordering {
+    a.partial_cmp(&b).unwrap_or(Ordering::Equal)
+}
+
+#[test]
+fn test_cube_hull() {
+    let mut points = Vec::new();
+    for &x in [-1.0f64, 1.0].iter() {
+        for &y in [-1.0f64, 1.0].iter() {
+            for &z in [-1.0f64, 1.0].iter() {
+                points.push(Vector3(x,y,z));
+            }
+        }
+    }
+    // Interior and face points should not appear on the hull.
+    points.push(Vector3(0.0, 0.0, 0.0));
+    points.push(Vector3(0.25, 0.5, 1.0));
+
+    let hull: mesh::Buffer<u16,_> = quickhull(points.into_iter());
+    assert_eq!(hull.vertices.len(), 8);
+    assert_eq!(hull.indices.len(), 12 * 3);
+
+    // Every triangle should face away from the center.
+    for triangle in hull.indices.chunks(3) {
+        let a = hull.vertices[triangle[0] as usize];
+        let b = hull.vertices[triangle[1] as usize];
+        let c = hull.vertices[triangle[2] as usize];
+
+        assert!((b - a).cross(c - a).dot(a) > 0.0);
+    }
+}
+
+#[test]
+fn test_degenerate_hulls() {
+    let line = (0..5).map(|i| Vector3(i as f32, i as f32, 0.0));
+    let hull: mesh::Buffer<u16,_> = quickhull(line);
+    assert_eq!(hull.vertices.len(), 2);
+    assert!(hull.indices.is_empty());
+
+    let square = vec![Vector3(0.0f32, 0.0, 0.0), Vector3(1.0, 0.0, 0.0),
+                      Vector3(1.0, 1.0, 0.0), Vector3(0.0, 1.0, 0.0),
+                      Vector3(0.5, 0.5, 0.0)];
+    let hull: mesh::Buffer<u16,_> = quickhull(square.into_iter());
+    assert_eq!(hull.vertices.len(), 4);
+    assert_eq!(hull.indices.len(), 4 * 3);
+
+    let hull: mesh::Buffer<u16,Vector3<f32>> = quickhull(Vec::new().into_iter());
+    assert!(hull.vertices.is_empty());
+}
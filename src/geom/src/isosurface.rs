@@ -0,0 +1,389 @@
+//! Isosurface extraction from scalar fields.
+//!
+//! Surfaces are extracted using surface nets, a simple form of dual
+//! contouring. Every cell that the surface passes through gets one vertex,
+//! placed at the average of the points where the surface crosses the edges
+//! of the cell. Every edge the surface crosses becomes a quad joining the
+//! vertices of the four cells around it.
+//!
+//! Points where the field is greater than the iso level are considered
+//! to be inside the surface.
+//!
+//! Fields are sampled on a global lattice, so a surface can be extracted
+//! chunk by chunk. Vertices along the border of neighbouring chunks are
+//! placed identically in both chunks, and every edge belongs to exactly one
+//! chunk, so the chunks join up without cracks or overlaps.
+
+use mesh;
+use math::{Scalar,Vector3};
+use num::{self,Integer};
+
+/// A vertex on an extracted surface.
+#[derive(Copy,Clone)]
+pub struct Vertex
+{
+    pub position: Vector3,
+    /// The unit normal, pointing out of the surface.
+    pub normal: Vector3,
+}
+
+impl ::Vertex for Vertex
+{
+    type T = Scalar;
+
+    fn coords(self) -> Vector3<Scalar> {
+        self.position
+    }
+}
+
+/// A box of cells on the sampling lattice.
+#[derive(Copy,Clone,Debug)]
+pub struct Region
+{
+    /// The lattice coordinates of the first corner.
+    pub offset: (i32,i32,i32),
+    /// The number of cells along each axis.
+    pub cells: (usize,usize,usize),
+    /// The distance between lattice points.
+    pub cell_size: Scalar,
+}
+
+impl Region
+{
+    pub fn new(offset: (i32,i32,i32),
+               cells: (usize,usize,usize),
+               cell_size: Scalar) -> Self {
+        Region {
+            offset: offset,
+            cells: cells,
+            cell_size: cell_size,
+        }
+    }
+
+    /// Gets a cubic chunk of cells.
+    ///
+    /// Neighbouring chunks tile the lattice without overlapping.
+    pub fn chunk((cx,cy,cz): (i32,i32,i32),
+                 size: usize,
+                 cell_size: Scalar) -> Self {
+        let s = size as i32;
+        Region::new((cx*s, cy*s, cz*s), (size,size,size), cell_size)
+    }
+
+    /// Gets the position of a lattice point.
+    fn position(&self, x: i32, y: i32, z: i32) -> Vector3 {
+        let (ox,oy,oz) = self.offset;
+
+        Vector3((ox + x) as Scalar * self.cell_size,
+                (oy + y) as Scalar * self.cell_size,
+                (oz + z) as Scalar * self.cell_size)
+    }
+}
+
+/// A grid of precomputed density values.
+pub struct DensityGrid
+{
+    values: Vec<Scalar>,
+    dimensions: (usize,usize,usize),
+    cell_size: Scalar,
+}
+
+impl DensityGrid
+{
+    /// Creates a grid from values ordered by x, then y, then z.
+    /// Panics if the number of values doesn't match the dimensions.
+    pub fn new(values: Vec<Scalar>,
+               dimensions: (usize,usize,usize),
+               cell_size: Scalar) -> Self {
+        let (w,h,d) = dimensions;
+        assert!(values.len() == w*h*d, "the number of values must match the dimensions");
+        assert!(w > 0 && h > 0 && d > 0, "the grid must not be empty");
+
+        DensityGrid {
+            values: values,
+            dimensions: dimensions,
+            cell_size: cell_size,
+        }
+    }
+
+    /// Creates a grid from a function taking lattice coordinates.
+    pub fn from_fn<F>(dimensions: (usize,usize,usize),
+                      cell_size: Scalar,
+                      f: F) -> Self
+        where F: Fn(usize,usize,usize) -> Scalar {
+        let (w,h,d) = dimensions;
+        let mut values = Vec::with_capacity(w*h*d);
+
+        for z in 0..d {
+            for y in 0..h {
+                for x in 0..w {
+                    values.push(f(x,y,z));
+                }
+            }
+        }
+
+        DensityGrid::new(values, dimensions, cell_size)
+    }
+
+    /// Gets the value at a lattice point.
+    /// Panics on out of range.
+    pub fn get(&self, x: usize, y: usize, z: usize) -> Scalar {
+        let (w,h,_) = self.dimensions;
+        self.values[x + y*w + z*w*h]
+    }
+
+    /// Samples the grid at any point using trilinear interpolation.
+    ///
+    /// The first lattice point is at the origin. Points outside the grid
+    /// take the value of the closest point on its boundary.
+    pub fn sample(&self, point: Vector3) -> Scalar {
+        let (w,h,d) = self.dimensions;
+        let Vector3(px,py,pz) = point / self.cell_size;
+
+        // Splits a coordinate into the lower lattice point and the
+        // fraction of the way to the next.
+        let split = |p: Scalar, size: usize| {
+            let max = (size - 1) as Scalar;
+            let p = if p < 0.0 { 0.0 } else if p > max { max } else { p };
+            let i = p.floor() as usize;
+            let i = if i + 1 >= size { if size > 1 { size - 2 } else { 0 } } else { i };
+            let next = if size > 1 { i + 1 } else { i };
+            (i, next, p - i as Scalar)
+        };
+
+        let (x0,x1,tx) = split(px, w);
+        let (y0,y1,ty) = split(py, h);
+        let (z0,z1,tz) = split(pz, d);
+
+        let lerp = |a: Scalar, b: Scalar, t: Scalar| a + (b-a)*t;
+
+        let c00 = lerp(self.get(x0,y0,z0), self.get(x1,y0,z0), tx);
+        let c10 = lerp(self.get(x0,y1,z0), self.get(x1,y1,z0), tx);
+        let c01 = lerp(self.get(x0,y0,z1), self.get(x1,y0,z1), tx);
+        let c11 = lerp(self.get(x0,y1,z1), self.get(x1,y1,z1), tx);
+
+        lerp(lerp(c00, c10, ty), lerp(c01, c11, ty), tz)
+    }
+}
+
+/// The corners of a cell.
+const CORNERS: [(i32,i32,i32); 8] = [
+    (0,0,0), (1,0,0), (0,1,0), (1,1,0),
+    (0,0,1), (1,0,1), (0,1,1), (1,1,1),
+];
+
+/// The edges of a cell, as pairs of corners.
+const EDGES: [(usize,usize); 12] = [
+    (0,1), (2,3), (4,5), (6,7),
+    (0,2), (1,3), (4,6), (5,7),
+    (0,4), (1,5), (2,6), (3,7),
+];
+
+/// Extracts the surface of a field over a region.
+///
+/// The field is sampled one cell beyond the region on every side, so that
+/// the surface joins up with neighbouring regions.
+pub fn extract<I,V,F>(field: F,
+                      iso_level: Scalar,
+                      region: Region) -> mesh::StaticData<I,V>
+    where I: Integer, usize: num::TryCast<I>, V: From<Vertex>, F: Fn(Vector3) -> Scalar {
+    let (nx,ny,nz) = region.cells;
+    let (nx,ny,nz) = (nx as i32, ny as i32, nz as i32);
+
+    // Samples cover lattice points -1..n+1 along each axis.
+    let (sx,sy,sz) = (nx+3, ny+3, nz+3);
+    let sample_index = |x: i32, y: i32, z: i32| {
+        ((x+1) + (y+1)*sx + (z+1)*sx*sy) as usize
+    };
+
+    let mut samples = Vec::with_capacity((sx*sy*sz) as usize);
+    for z in -1..(nz+2) {
+        for y in -1..(ny+2) {
+            for x in -1..(nx+2) {
+                samples.push(field(region.position(x,y,z)));
+            }
+        }
+    }
+
+    let inside = |x: i32, y: i32, z: i32| samples[sample_index(x,y,z)] > iso_level;
+
+    // Place a vertex in every cell from -1..n that the surface crosses.
+    let (cx,cy) = (nx+1, ny+1);
+    let cell_index = |x: i32, y: i32, z: i32| ((x+1) + (y+1)*cx + (z+1)*cx*cy) as usize;
+
+    let mut cell_vertices: Vec<Option<usize>> = vec![None; ((nx+1)*(ny+1)*(nz+1)) as usize];
+    let mut vertices = Vec::new();
+
+    // Half a cell gives a smooth gradient without reaching far
+    // across the surface.
+    let h = region.cell_size * 0.5;
+
+    for z in -1..nz {
+        for y in -1..ny {
+            for x in -1..nx {
+                let mut sum = Vector3(0.0, 0.0, 0.0);
+                let mut crossings = 0;
+
+                for &(a,b) in EDGES.iter() {
+                    let (ax,ay,az) = CORNERS[a];
+                    let (bx,by,bz) = CORNERS[b];
+                    let (pa,pb) = ((x+ax, y+ay, z+az), (x+bx, y+by, z+bz));
+
+                    if inside(pa.0,pa.1,pa.2) == inside(pb.0,pb.1,pb.2) {
+                        continue;
+                    }
+
+                    let va = samples[sample_index(pa.0,pa.1,pa.2)];
+                    let vb = samples[sample_index(pb.0,pb.1,pb.2)];
+                    let t = (iso_level - va) / (vb - va);
+
+                    let position_a = region.position(pa.0,pa.1,pa.2);
+                    let position_b = region.position(pb.0,pb.1,pb.2);
+
+                    sum = sum + position_a + (position_b - position_a) * t;
+                    crossings += 1;
+                }
+
+                if crossings == 0 {
+                    continue;
+                }
+
+                let position = sum / crossings as Scalar;
+                let gradient = Vector3(
+                    field(position + Vector3(h, 0.0, 0.0)) - field(position - Vector3(h, 0.0, 0.0)),
+                    field(position + Vector3(0.0, h, 0.0)) - field(position - Vector3(0.0, h, 0.0)),
+                    field(position + Vector3(0.0, 0.0, h)) - field(position - Vector3(0.0, 0.0, h)),
+                );
+
+                // The field increases going into the surface.
+                let length_squared = gradient.length_squared();
+                let normal = if length_squared > 0.0 {
+                    gradient * -(1.0 / length_squared.sqrt())
+                } else {
+                    Vector3(0.0, 0.0, 0.0)
+                };
+
+                cell_vertices[cell_index(x,y,z)] = Some(vertices.len());
+                vertices.push(Vertex { position: position, normal: normal });
+            }
+        }
+    }
+
+    // Join up the cells around every edge the surface crosses. The region
+    // owns the edges which start at lattice points in 0..n.
+    let mut indices: Vec<usize> = Vec::new();
+
+    for z in 0..nz {
+        for y in 0..ny {
+            for x in 0..nx {
+                let p = [x, y, z];
+
+                for axis in 0..3 {
+                    let (u, v) = ((axis + 1) % 3, (axis + 2) % 3);
+
+                    let mut q = p;
+                    q[axis] += 1;
+
+                    let start_inside = inside(p[0], p[1], p[2]);
+                    if start_inside == inside(q[0], q[1], q[2]) {
+                        continue;
+                    }
+
+                    // The cells around the edge, counter-clockwise
+                    // around the axis.
+                    let cell = |du: i32, dv: i32| {
+                        let mut c = p;
+                        c[u] -= du;
+                        c[v] -= dv;
+                        cell_vertices[cell_index(c[0], c[1], c[2])].unwrap()
+                    };
+
+                    let quad = [cell(1,1), cell(0,1), cell(0,0), cell(1,0)];
+
+                    // Wind the quad to face out of the surface.
+                    if start_inside {
+                        indices.extend([quad[0], quad[1], quad[2], quad[0], quad[2], quad[3]].iter().cloned());
+                    } else {
+                        indices.extend([quad[0], quad[2], quad[1], quad[0], quad[3], quad[2]].iter().cloned());
+                    }
+                }
+            }
+        }
+    }
+
+    // Only keep the vertices which are used by this region.
+    let mut remap: Vec<Option<usize>> = vec![None; vertices.len()];
+    let mut used = Vec::new();
+
+    for index in indices.iter_mut() {
+        if remap[*index].is_none() {
+            remap[*index] = Some(used.len());
+            used.push(vertices[*index]);
+        }
+        *index = remap[*index].unwrap();
+    }
+
+    let mut builder = mesh::StaticBuilder::new();
+    builder.feed_vertices(used.into_iter().map(V::from));
+    builder.feed_indices(mesh::cast_indices(indices).into_iter());
+    builder.into()
+}
+
+#[cfg(test)]
+fn sphere(p: Vector3) -> Scalar {
+    2.5 - p.length_squared().sqrt()
+}
+
+#[test]
+fn test_sphere_surface() {
+    let region = Region::new((-4,-4,-4), (8,8,8), 1.0);
+    let mesh: mesh::StaticData<u32,Vertex> = extract(sphere, 0.0, region);
+    let buffer = &mesh.data[0];
+
+    assert!(!buffer.indices.is_empty());
+
+    for vertex in buffer.vertices.iter() {
+        let distance = vertex.position.length_squared().sqrt();
+        assert!((distance - 2.5).abs() < 0.5);
+        assert!(vertex.normal.dot(vertex.position) > 0.0);
+    }
+
+    for triangle in buffer.indices.chunks(3) {
+        let a = buffer.vertices[triangle[0] as usize].position;
+        let b = buffer.vertices[triangle[1] as usize].position;
+        let c = buffer.vertices[triangle[2] as usize].position;
+
+        assert!((b - a).cross(c - a).dot(a + b + c) > 0.0);
+    }
+}
+
+#[test]
+fn test_chunks_are_watertight() {
+    use std::collections::HashMap;
+
+    // Split the sphere between two chunks, and check that every edge
+    // is shared by exactly two triangles.
+    let mut edges: HashMap<((u32,u32,u32),(u32,u32,u32)),usize> = HashMap::new();
+
+    for &chunk in [(-1,-1,-1), (0,-1,-1), (-1,0,-1), (0,0,-1),
+                   (-1,-1,0), (0,-1,0), (-1,0,0), (0,0,0)].iter() {
+        let mesh: mesh::StaticData<u32,Vertex> = extract(sphere, 0.0, Region::chunk(chunk, 4, 1.0));
+        let buffer = &mesh.data[0];
+
+        let key = |i: u32| {
+            let Vector3(x,y,z) = buffer.vertices[i as usize].position;
+            (x.to_bits(), y.to_bits(), z.to_bits())
+        };
+
+        for triangle in buffer.indices.chunks(3) {
+            for &(a,b) in [(0,1), (1,2), (2,0)].iter() {
+                let (ka,kb) = (key(triangle[a]), key(triangle[b]));
+                let edge = if ka < kb { (ka,kb) } else { (kb,ka) };
+                *edges.entry(edge).or_insert(0) += 1;
+            }
+        }
+    }
+
+    assert!(!edges.is_empty());
+    assert!(edges.values().all(|&count| count == 2));
+}
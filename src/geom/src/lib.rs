@@ -8,13 +8,20 @@ pub use self::formats::Format;
 pub use self::triangle::Triangle;
 pub use self::aabb::Aabb;
 pub use self::octree::Octree;
-pub use self::transform::Transform3;
+pub use self::transform::{Transform3,DepthRange};
 
 pub mod mesh;
 pub mod triangle;
 pub mod aabb;
 pub mod octree;
 pub mod transform;
+pub mod collision;
+pub mod hull;
+pub mod csg;
+pub mod voxel;
+pub mod isosurface;
+pub mod terrain;
+pub mod tube;
 
 pub mod formats;
 pub mod util;
@@ -2,10 +2,11 @@
 pub use self::stat::{StaticData,StaticBuilder};
 pub use self::anim::AnimatedData;
 
+use num;
+
 pub mod stat;
 pub mod anim;
 
-
 const DEFAULT_BUFFER_SIZE: usize = 4096;
 
 /// A mesh buffer.
@@ -36,3 +37,16 @@ impl<I,V> Default for Buffer<I,V>
     }
 }
 
+/// Converts vertex positions into the index type of a mesh.
+///
+/// Panics if an index does not fit the index type, which happens when
+/// the mesh has too many vertices. This is better than letting the
+/// indices wrap around and silently connect the wrong vertices.
+pub fn cast_indices<I,It>(indices: It) -> Vec<I>
+    where It: IntoIterator<Item=usize>, usize: num::TryCast<I> {
+    indices.into_iter().map(|i| {
+        num::checked_cast(i).unwrap_or_else(|| {
+            panic!("vertex {} can not be addressed by the mesh's index type", i)
+        })
+    }).collect()
+}
@@ -0,0 +1,388 @@
+//! Heightmap terrain.
+//!
+//! The terrain is split into square chunks, which can each be meshed at
+//! a different level of detail. At LOD `n`, a chunk uses every `2^n`th
+//! sample of the heightmap.
+//!
+//! Where a chunk borders a coarser chunk, the vertices along the shared
+//! edge which the coarser chunk doesn't have are moved onto the coarser
+//! chunk's edge, so that no cracks appear between them.
+
+use mesh;
+use math::{Scalar,Vector3};
+use num::{self,Integer};
+
+/// A grid of heights.
+///
+/// Heights are stored in rows along the x axis.
+#[derive(Clone)]
+pub struct Heightmap
+{
+    heights: Vec<Scalar>,
+    width: usize,
+    depth: usize,
+}
+
+impl Heightmap
+{
+    /// Creates a heightmap.
+    /// Panics if the number of heights doesn't match the dimensions.
+    pub fn new(heights: Vec<Scalar>, (width,depth): (usize,usize)) -> Self {
+        assert!(heights.len() == width*depth, "the number of heights must match the dimensions");
+        assert!(width >= 2 && depth >= 2, "the heightmap must be at least 2x2");
+
+        Heightmap {
+            heights: heights,
+            width: width,
+            depth: depth,
+        }
+    }
+
+    /// Creates a heightmap from a function taking sample coordinates.
+    pub fn from_fn<F>((width,depth): (usize,usize), f: F) -> Self
+        where F: Fn(usize,usize) -> Scalar {
+        let mut heights = Vec::with_capacity(width*depth);
+
+        for z in 0..depth {
+            for x in 0..width {
+                heights.push(f(x,z));
+            }
+        }
+
+        Heightmap::new(heights, (width,depth))
+    }
+
+    /// Creates a heightmap from an 8-bit greyscale image.
+    /// Heights are mapped to `0..1`.
+    pub fn from_luma8(pixels: &[u8], dimensions: (usize,usize)) -> Self {
+        let heights = pixels.iter().map(|&p| p as Scalar / 255.0).collect();
+        Heightmap::new(heights, dimensions)
+    }
+
+    /// Creates a heightmap from a 16-bit greyscale image.
+    /// Heights are mapped to `0..1`.
+    pub fn from_luma16(pixels: &[u16], dimensions: (usize,usize)) -> Self {
+        let heights = pixels.iter().map(|&p| p as Scalar / 65535.0).collect();
+        Heightmap::new(heights, dimensions)
+    }
+
+    /// Gets the height at a sample.
+    /// Panics on out of range.
+    pub fn get(&self, x: usize, z: usize) -> Scalar {
+        assert!(x < self.width && z < self.depth, "out of bounds sample");
+        self.heights[x + z*self.width]
+    }
+
+    /// Gets the number of samples along the x and z axes.
+    pub fn dimensions(&self) -> (usize,usize) {
+        (self.width, self.depth)
+    }
+}
+
+/// A vertex on the terrain.
+#[derive(Copy,Clone)]
+pub struct Vertex
+{
+    pub position: Vector3,
+    pub normal: Vector3,
+    /// The texture coordinates, ranging from `0..1` across the
+    /// entire terrain.
+    pub uv: (Scalar,Scalar),
+}
+
+impl ::Vertex for Vertex
+{
+    type T = Scalar;
+
+    fn coords(self) -> Vector3<Scalar> {
+        self.position
+    }
+}
+
+/// The LODs of the chunks next to a chunk.
+#[derive(Copy,Clone,Debug,PartialEq,Eq)]
+pub struct NeighbourLods
+{
+    pub neg_x: usize,
+    pub pos_x: usize,
+    pub neg_z: usize,
+    pub pos_z: usize,
+}
+
+impl NeighbourLods
+{
+    /// Every neighbour has the same LOD.
+    pub fn uniform(lod: usize) -> Self {
+        NeighbourLods {
+            neg_x: lod,
+            pos_x: lod,
+            neg_z: lod,
+            pos_z: lod,
+        }
+    }
+}
+
+/// A chunked heightmap terrain.
+pub struct Terrain
+{
+    heightmap: Heightmap,
+    /// The distance between samples.
+    spacing: Scalar,
+    /// The scale applied to every height.
+    height_scale: Scalar,
+    /// The number of cells along each edge of a chunk.
+    chunk_size: usize,
+}
+
+impl Terrain
+{
+    /// Creates a terrain.
+    ///
+    /// The chunk size is the number of cells along each edge of a chunk,
+    /// and must be a power of two which evenly divides the number of
+    /// cells in the heightmap.
+    pub fn new(heightmap: Heightmap,
+               spacing: Scalar,
+               height_scale: Scalar,
+               chunk_size: usize) -> Self {
+        let (width,depth) = heightmap.dimensions();
+
+        assert!(chunk_size.is_power_of_two(), "the chunk size must be a power of two");
+        assert!((width-1) % chunk_size == 0 && (depth-1) % chunk_size == 0,
+                "the chunk size must divide the heightmap evenly");
+
+        Terrain {
+            heightmap: heightmap,
+            spacing: spacing,
+            height_scale: height_scale,
+            chunk_size: chunk_size,
+        }
+    }
+
+    pub fn heightmap(&self) -> &Heightmap { &self.heightmap }
+
+    /// Gets the number of chunks along the x and z axes.
+    pub fn chunk_count(&self) -> (usize,usize) {
+        let (width,depth) = self.heightmap.dimensions();
+        ((width-1) / self.chunk_size, (depth-1) / self.chunk_size)
+    }
+
+    /// Gets the coarsest LOD, which has a single quad per chunk.
+    pub fn max_lod(&self) -> usize {
+        self.chunk_size.trailing_zeros() as usize
+    }
+
+    /// Gets the world position of the center of a chunk.
+    pub fn chunk_center(&self, (cx,cz): (usize,usize)) -> Vector3 {
+        let half = self.chunk_size as Scalar * 0.5;
+        let x = (cx * self.chunk_size) as Scalar + half;
+        let z = (cz * self.chunk_size) as Scalar + half;
+
+        let world = Vector3(x * self.spacing, 0.0, z * self.spacing);
+        Vector3(world.x(), self.height_at(world.x(), world.z()), world.z())
+    }
+
+    /// Gets the height of the terrain at a world position.
+    ///
+    /// This matches the surface of the LOD 0 mesh exactly. Positions
+    /// outside the terrain are clamped to its edge.
+    pub fn height_at(&self, x: Scalar, z: Scalar) -> Scalar {
+        let (ix, iz, fx, fz) = self.locate(x, z);
+        let h = |dx: usize, dz: usize| self.sample_height(ix + dx, iz + dz);
+
+        // Each cell is split along the diagonal from its first
+        // corner to its last.
+        if fz >= fx {
+            h(0,0) + fx*(h(1,1) - h(0,1)) + fz*(h(0,1) - h(0,0))
+        } else {
+            h(0,0) + fx*(h(1,0) - h(0,0)) + fz*(h(1,1) - h(1,0))
+        }
+    }
+
+    /// Gets the unit normal of the terrain at a world position.
+    ///
+    /// The normals of the surrounding samples are interpolated, which
+    /// gives smooth lighting across cells.
+    pub fn normal_at(&self, x: Scalar, z: Scalar) -> Vector3 {
+        let (ix, iz, fx, fz) = self.locate(x, z);
+        let n = |dx: usize, dz: usize| self.sample_normal(ix + dx, iz + dz);
+
+        let near = n(0,0) * (1.0 - fx) + n(1,0) * fx;
+        let far = n(0,1) * (1.0 - fx) + n(1,1) * fx;
+
+        normalize(near * (1.0 - fz) + far * fz)
+    }
+
+    /// Generates the mesh of a chunk at a level of detail.
+    ///
+    /// Edges shared with coarser neighbours are stitched to match them.
+    /// Panics if the chunk or any LOD is out of range.
+    pub fn mesh_chunk<I,V>(&self,
+                           (cx,cz): (usize,usize),
+                           lod: usize,
+                           neighbours: NeighbourLods) -> mesh::StaticData<I,V>
+        where I: Integer, usize: num::TryCast<I>, V: From<Vertex> {
+        let (chunks_x, chunks_z) = self.chunk_count();
+        assert!(cx < chunks_x && cz < chunks_z, "out of bounds chunk");
+        assert!(lod <= self.max_lod(), "out of range LOD");
+
+        let step = 1 << lod;
+        let count = self.chunk_size / step + 1;
+        let (x0, z0) = (cx * self.chunk_size, cz * self.chunk_size);
+
+        let mut vertices = Vec::with_capacity(count*count);
+
+        for j in 0..count {
+            for i in 0..count {
+                let (sx, sz) = (x0 + i*step, z0 + j*step);
+
+                // Find the step of the coarsest neighbour along this
+                // vertex's edge, if it is on one.
+                let mut seam_step = step;
+                let mut along_x = true;
+
+                if j == 0 && neighbours.neg_z > lod { seam_step = 1 << neighbours.neg_z; }
+                if j == count-1 && neighbours.pos_z > lod { seam_step = 1 << neighbours.pos_z; }
+                if i == 0 && neighbours.neg_x > lod { seam_step = 1 << neighbours.neg_x; along_x = false; }
+                if i == count-1 && neighbours.pos_x > lod { seam_step = 1 << neighbours.pos_x; along_x = false; }
+
+                let (height, normal) = self.stitched_sample(sx, sz, seam_step, along_x);
+                vertices.push(self.vertex(sx, sz, height, normal));
+            }
+        }
+
+        let mut indices = Vec::with_capacity((count-1)*(count-1)*6);
+
+        for j in 0..(count-1) {
+            for i in 0..(count-1) {
+                let a = i + j*count;
+                let (b, c, d) = (a + 1, a + count, a + count + 1);
+
+                indices.extend([a, c, d, a, d, b].iter().cloned());
+            }
+        }
+
+        let mut builder = mesh::StaticBuilder::new();
+        builder.feed_vertices(vertices.into_iter().map(V::from));
+        builder.feed_indices(mesh::cast_indices(indices).into_iter());
+        builder.into()
+    }
+
+    /// Gets the height and normal of a sample, moved onto the edge of a
+    /// coarser grid with the given step.
+    fn stitched_sample(&self, sx: usize, sz: usize, seam_step: usize, along_x: bool) -> (Scalar,Vector3) {
+        let along = if along_x { sx } else { sz };
+        let offset = along % seam_step;
+
+        if offset == 0 {
+            return (self.sample_height(sx, sz), self.sample_normal(sx, sz));
+        }
+
+        let (start, end) = if along_x {
+            ((sx - offset, sz), (sx - offset + seam_step, sz))
+        } else {
+            ((sx, sz - offset), (sx, sz - offset + seam_step))
+        };
+
+        let t = offset as Scalar / seam_step as Scalar;
+        let (h0, h1) = (self.sample_height(start.0, start.1), self.sample_height(end.0, end.1));
+        let (n0, n1) = (self.sample_normal(start.0, start.1), self.sample_normal(end.0, end.1));
+
+        (h0 + (h1 - h0)*t, normalize(n0 + (n1 - n0)*t))
+    }
+
+    fn vertex(&self, sx: usize, sz: usize, height: Scalar, normal: Vector3) -> Vertex {
+        let (width,depth) = self.heightmap.dimensions();
+
+        Vertex {
+            position: Vector3(sx as Scalar * self.spacing, height, sz as Scalar * self.spacing),
+            normal: normal,
+            uv: (sx as Scalar / (width-1) as Scalar, sz as Scalar / (depth-1) as Scalar),
+        }
+    }
+
+    /// Finds the cell containing a world position, and the position
+    /// inside the cell.
+    fn locate(&self, x: Scalar, z: Scalar) -> (usize,usize,Scalar,Scalar) {
+        let (width,depth) = self.heightmap.dimensions();
+
+        let split = |p: Scalar, size: usize| {
+            let max = (size - 1) as Scalar;
+            let p = p / self.spacing;
+            let p = if p < 0.0 { 0.0 } else if p > max { max } else { p };
+            let i = p.floor() as usize;
+            let i = if i >= size - 1 { size - 2 } else { i };
+            (i, p - i as Scalar)
+        };
+
+        let (ix, fx) = split(x, width);
+        let (iz, fz) = split(z, depth);
+        (ix, iz, fx, fz)
+    }
+
+    fn sample_height(&self, sx: usize, sz: usize) -> Scalar {
+        self.heightmap.get(sx, sz) * self.height_scale
+    }
+
+    /// Gets the normal at a sample from the central difference of
+    /// its neighbours.
+    fn sample_normal(&self, sx: usize, sz: usize) -> Vector3 {
+        let (width,depth) = self.heightmap.dimensions();
+
+        let (x0, x1) = (if sx > 0 { sx - 1 } else { sx }, if sx + 1 < width { sx + 1 } else { sx });
+        let (z0, z1) = (if sz > 0 { sz - 1 } else { sz }, if sz + 1 < depth { sz + 1 } else { sz });
+
+        let dx = (self.sample_height(x1, sz) - self.sample_height(x0, sz)) / ((x1 - x0) as Scalar * self.spacing);
+        let dz = (self.sample_height(sx, z1) - self.sample_height(sx, z0)) / ((z1 - z0) as Scalar * self.spacing);
+
+        normalize(Vector3(-dx, 1.0, -dz))
+    }
+}
+
+fn normalize(v: Vector3) -> Vector3 {
+    v / v.length_squared().sqrt()
+}
+
+#[cfg(test)]
+fn test_terrain() -> Terrain {
+    let heightmap = Heightmap::from_fn((9,9), |x,z| (x*x) as Scalar * 0.1 + (z*x) as Scalar * 0.05);
+    Terrain::new(heightmap, 2.0, 3.0, 4)
+}
+
+#[test]
+fn test_height_queries() {
+    let terrain = test_terrain();
+
+    assert_eq!(terrain.height_at(4.0, 6.0), terrain.heightmap().get(2, 3) * 3.0);
+
+    // A point halfway along a cell diagonal.
+    let expected = (terrain.heightmap().get(1, 1) + terrain.heightmap().get(2, 2)) * 0.5 * 3.0;
+    assert!((terrain.height_at(3.0, 3.0) - expected).abs() < 1.0e-5);
+
+    let normal = terrain.normal_at(5.0, 5.0);
+    assert!((normal.length_squared() - 1.0).abs() < 1.0e-5);
+    assert!(normal.y() > 0.0 && normal.x() < 0.0);
+}
+
+#[test]
+fn test_lod_seams() {
+    let terrain = test_terrain();
+
+    // The fine chunk borders the coarse chunk along its positive x edge.
+    let fine: mesh::StaticData<u16,Vertex> = terrain.mesh_chunk((0,0), 0, NeighbourLods {
+        pos_x: 2, ..NeighbourLods::uniform(0)
+    });
+    let coarse: mesh::StaticData<u16,Vertex> = terrain.mesh_chunk((1,0), 2, NeighbourLods::uniform(0));
+
+    assert_eq!(fine.data[0].vertices.len(), 25);
+    assert_eq!(coarse.data[0].vertices.len(), 4);
+
+    let (c0, c1) = (coarse.data[0].vertices[0].position, coarse.data[0].vertices[2].position);
+
+    for vertex in fine.data[0].vertices.iter().filter(|v| v.position.x() == 8.0) {
+        let t = (vertex.position.z() - c0.z()) / (c1.z() - c0.z());
+        let expected = c0.y() + (c1.y() - c0.y()) * t;
+
+        assert!((vertex.position.y() - expected).abs() < 1.0e-5);
+    }
+}
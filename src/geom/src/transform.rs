@@ -10,6 +10,17 @@ pub struct Transform3<T: Num = Scalar>
     matrix: Matrix4<T>,
 }
 
+/// The range that depths are mapped to in clip space.
+#[derive(Copy,Clone,Debug,PartialEq,Eq)]
+pub enum DepthRange
+{
+    /// Depths map to `-1..1`, as OpenGL expects by default.
+    NegativeOneToOne,
+    /// Depths map to `0..1`, as Direct3D, Vulkan and Metal expect, and as
+    /// OpenGL does with `glClipControl`.
+    ZeroToOne,
+}
+
 /// A 3D rotation matrix.
 #[derive(Copy,Clone,Debug)]
 pub struct Rotation3<T: Num+Decimal>
@@ -35,6 +46,11 @@ impl<T: Num+Decimal> Rotation3<T>
         }
     }
 
+    /// Converts the rotation to a quaternion.
+    pub fn to_quaternion(&self) -> math::Quaternion<T> {
+        math::Quaternion::from_rotation_matrix(self.matrix)
+    }
+
     pub fn from_vector(vec: Vector3<T>) -> Self
         where T: num::Signed {
 
@@ -86,25 +102,145 @@ impl<T: Num+Decimal> Transform3<T>
         Transform3::from_matrix(Matrix::identity())
     }
 
-    /// Creates a perspective transformation.
-    /// `fov` - the field of view in radians.
-    pub fn perspective(fov: T, near: T, far: T, aspect: T) -> Self
-        where T: Decimal + num::Signed {
+    /// Creates a perspective projection for OpenGL's default `-1..1`
+    /// depth range.
+    /// `fov` - the vertical field of view in radians.
+    /// `near` - the distance to the near plane, which must be positive.
+    pub fn perspective(fov: T, near: T, far: T, aspect: T) -> Self {
+        Transform3::perspective_fov(fov, aspect, near, far, DepthRange::NegativeOneToOne)
+    }
+
+    /// Creates a view transformation for a camera at `eye` looking
+    /// towards `target`.
+    ///
+    /// The camera looks down its negative Z axis, with `up` roughly
+    /// along its positive Y axis.
+    pub fn look_at(eye: Vector3<T>, target: Vector3<T>, up: Vector3<T>) -> Self
+        where T: num::Signed {
+        let normalize = |v: Vector3<T>| v * v.length_squared().rsqrt();
+
+        let forward = normalize(target - eye);
+        let side = normalize(forward.cross(up));
+        let up = side.cross(forward);
+        let (z,o) = (T::zero(), T::one());
+
+        Transform3::from_matrix(
+            Matrix4::new(
+                side.x(),     side.y(),     side.z(),    -side.dot(eye),
+                up.x(),       up.y(),       up.z(),      -up.dot(eye),
+               -forward.x(), -forward.y(), -forward.z(),  forward.dot(eye),
+                z,            z,            z,            o
+            )
+        )
+    }
+
+    /// Creates an orthographic projection of the given box in view space.
+    ///
+    /// `near` and `far` are distances along the negative Z axis.
+    pub fn orthographic(left: T, right: T,
+                        bottom: T, top: T,
+                        near: T, far: T,
+                        depth: DepthRange) -> Self {
+        let (z,o) = (T::zero(), T::one());
+        let two = o + o;
+
+        let (m33,m34) = match depth {
+            DepthRange::NegativeOneToOne => (z - two / (far-near), z - (far+near) / (far-near)),
+            DepthRange::ZeroToOne => (z - o / (far-near), z - near / (far-near)),
+        };
+
+        Transform3::from_matrix(
+            Matrix4::new(
+                two / (right-left), z, z, z - (right+left) / (right-left),
+                z, two / (top-bottom), z, z - (top+bottom) / (top-bottom),
+                z, z, m33, m34,
+                z, z, z, o
+            )
+        )
+    }
+
+    /// Creates a perspective projection from the sides of the view frustum
+    /// on the near plane, which need not be centred.
+    ///
+    /// `near` and `far` are distances along the negative Z axis.
+    pub fn frustum(left: T, right: T,
+                   bottom: T, top: T,
+                   near: T, far: T,
+                   depth: DepthRange) -> Self {
+        let (z,o) = (T::zero(), T::one());
+        let two = o + o;
+
+        let (m33,m34) = match depth {
+            DepthRange::NegativeOneToOne => (z - (far+near) / (far-near), z - two*far*near / (far-near)),
+            DepthRange::ZeroToOne => (z - far / (far-near), z - far*near / (far-near)),
+        };
+
+        Transform3::from_matrix(
+            Matrix4::new(
+                two*near / (right-left), z, (right+left) / (right-left), z,
+                z, two*near / (top-bottom), (top+bottom) / (top-bottom), z,
+                z, z, m33, m34,
+                z, z, z - o, z
+            )
+        )
+    }
+
+    /// Creates a symmetric perspective projection.
+    /// `fov_y` - the vertical field of view in radians.
+    pub fn perspective_fov(fov_y: T, aspect: T, near: T, far: T,
+                           depth: DepthRange) -> Self {
+        let (half_width, half_height) = half_extents(fov_y, aspect, near);
+
+        Transform3::frustum(T::zero() - half_width, half_width,
+                            T::zero() - half_height, half_height,
+                            near, far, depth)
+    }
+
+    /// Creates a perspective projection with the far plane at infinity.
+    /// `fov_y` - the vertical field of view in radians.
+    pub fn perspective_infinite(fov_y: T, aspect: T, near: T,
+                                depth: DepthRange) -> Self {
+        let (z,o) = (T::zero(), T::one());
+        let two = o + o;
+
+        let m34 = match depth {
+            DepthRange::NegativeOneToOne => z - two*near,
+            DepthRange::ZeroToOne => z - near,
+        };
+
+        Transform3::perspective_matrix(fov_y, aspect, near, z - o, m34)
+    }
 
-        let fov_scale = num::one::<T>() / fov.tan();
+    /// Creates a perspective projection which maps the near plane to a
+    /// depth of one and the far plane to zero.
+    ///
+    /// This spreads floating point depth precision far more evenly, and
+    /// is meant for a `0..1` depth range with a `GREATER` depth test.
+    /// `fov_y` - the vertical field of view in radians.
+    pub fn perspective_reversed(fov_y: T, aspect: T, near: T, far: T) -> Self {
+        Transform3::perspective_matrix(fov_y, aspect, near,
+                                       near / (far-near), far*near / (far-near))
+    }
 
-        let m11 = fov_scale / aspect;
-        let m22 = fov_scale;
+    /// Creates a reversed-Z perspective projection with the far plane
+    /// at infinity.
+    /// `fov_y` - the vertical field of view in radians.
+    pub fn perspective_reversed_infinite(fov_y: T, aspect: T, near: T) -> Self {
+        Transform3::perspective_matrix(fov_y, aspect, near, T::zero(), near)
+    }
 
-        let m33 = -far / (far-near);
-        let m43 = -(far*near)/(far-near);
+    /// Creates a symmetric perspective projection with the given
+    /// depth coefficients.
+    fn perspective_matrix(fov_y: T, aspect: T, near: T, m33: T, m34: T) -> Self {
+        let (half_width, half_height) = half_extents(fov_y, aspect, near);
+        let (z,o) = (T::zero(), T::one());
 
         Transform3::from_matrix(
             Matrix4::new(
-                m11,         num::zero(), num::zero(), num::zero(),
-                num::zero(), m22,         num::zero(), num::zero(),
-                num::zero(), num::zero(), m33,        -num::one::<T>(),
-                num::zero(), num::zero(), m43,         num::one()
+                near / half_width, z, z, z,
+                z, near / half_height, z, z,
+                z, z, m33, m34,
+                z, z, z - o, z
             )
         )
     }
@@ -138,17 +274,28 @@ impl<T: Num+Decimal> Transform3<T>
         self.set_scale(cur_scale * factor)
     }
 
+    /// Gets the scale along each local axis.
+    ///
+    /// The scale is the length of each column of the upper 3x3 matrix. If
+    /// the transform mirrors space, the X scale is negative.
     pub fn get_scale(&self) -> Vector3<T> {
-        Vector3(self.matrix[(0,0)], self.matrix[(1,1)], self.matrix[(2,2)])
-    }
+        let column_length = |col: usize| {
+            (0..3).fold(T::zero(), |acc, row| {
+                acc + self.matrix[(row,col)] * self.matrix[(row,col)]
+            }).sqrt()
+        };
 
-    pub fn set_scale(mut self, factor: Vector3<T>) -> Self {
-        let Vector3(x,y,z) = factor;
-        self.matrix[(0,0)] = x;
-        self.matrix[(1,1)] = y;
-        self.matrix[(2,2)] = z;
+        let x = column_length(0);
+        let upper: Matrix3<T> = Matrix3::from_fn(|i,j| self.matrix[(i,j)]);
 
-        self
+        let x = if upper.determinant() < T::zero() { T::zero() - x } else { x };
+        Vector3(x, column_length(1), column_length(2))
+    }
+
+    /// Sets the scale along each local axis, keeping the rotation.
+    pub fn set_scale(self, factor: Vector3<T>) -> Self {
+        let rotation = self.get_rotation();
+        self.set_rotation_and_scale(rotation.matrix, factor)
     }
 
     pub fn rotate<R>(self, rot: R) -> Self
@@ -158,27 +305,153 @@ impl<T: Num+Decimal> Transform3<T>
         self.set_rotation(rot_mat)
     }
 
-    pub fn set_rotation<R>(mut self, rot: R) -> Self
+    /// Sets the rotation, keeping the scale.
+    pub fn set_rotation<R>(self, rot: R) -> Self
         where R: Into<Rotation3<T>> {
 
         let rot3: Rotation3<T> = rot.into();
-        let mat = rot3.matrix;
-
-        for row in 0..3 {
-            for col in 0..3 {
-                self.matrix[(row,col)] = mat[(row,col)];
-            }
-        }
-        self
+        let scale = self.get_scale();
+        self.set_rotation_and_scale(rot3.matrix, scale)
     }
 
+    /// Gets the rotation, with the scale removed.
     pub fn get_rotation(&self) -> Rotation3<T> {
+        let scale = self.get_scale();
+        let scale = [scale.x(), scale.y(), scale.z()];
 
-        // Get the rotation submatrix
+        // Axes with no scale have lost their direction.
         Rotation3::from_matrix(
-            Matrix3::from_fn(|i,j| self.matrix[(i,j)])
+            Matrix3::from_fn(|i,j| {
+                if scale[j].is_zero() {
+                    math::util::kronecker_delta(i,j)
+                } else {
+                    self.matrix[(i,j)] / scale[j]
+                }
+            })
         )
     }
+
+    /// Creates a transformation which scales, then rotates, then translates.
+    pub fn from_trs<R>(translation: Vector3<T>, rotation: R, scale: Vector3<T>) -> Self
+        where R: Into<Rotation3<T>> {
+        Transform3::identity()
+            .set_rotation_and_scale(rotation.into().matrix, scale)
+            .set_translation(translation)
+    }
+
+    /// Splits the transformation into its translation, rotation and scale.
+    ///
+    /// This is exact for any composition of translations, rotations and
+    /// scales, but loses any shear or projection.
+    pub fn decompose(&self) -> (Vector3<T>, Rotation3<T>, Vector3<T>) {
+        (self.get_translation(), self.get_rotation(), self.get_scale())
+    }
+
+    /// Calculates the inverse transformation, or `None` if it is singular.
+    pub fn try_inverse(&self) -> Option<Self> {
+        self.matrix.try_inverse().map(Transform3::from_matrix)
+    }
+
+    /// Calculates the inverse transformation.
+    /// Panics if the transformation is singular.
+    pub fn inverse(&self) -> Self {
+        Transform3::from_matrix(self.matrix.inverse())
+    }
+
+    /// Transforms a point, including the translation.
+    ///
+    /// If the transformation is a projection, the result is divided by
+    /// the homogeneous coordinate.
+    pub fn transform_point(&self, point: Vector3<T>) -> Vector3<T> {
+        let m = &self.matrix;
+        let Vector3(x,y,z) = point;
+        let row = |r: usize| m[(r,0)]*x + m[(r,1)]*y + m[(r,2)]*z + m[(r,3)];
+
+        let w = row(3);
+        let transformed = Vector3(row(0), row(1), row(2));
+
+        if w == T::one() { transformed } else { transformed / w }
+    }
+
+    /// Transforms a direction, ignoring the translation.
+    pub fn transform_vector(&self, vector: Vector3<T>) -> Vector3<T> {
+        let m = &self.matrix;
+        let Vector3(x,y,z) = vector;
+        let row = |r: usize| m[(r,0)]*x + m[(r,1)]*y + m[(r,2)]*z;
+
+        Vector3(row(0), row(1), row(2))
+    }
+
+    /// Transforms a surface normal, and normalizes it.
+    ///
+    /// Normals are transformed by the inverse transpose, so that they stay
+    /// perpendicular to surfaces under non-uniform scaling.
+    pub fn transform_normal(&self, normal: Vector3<T>) -> Vector3<T> {
+        let upper: Matrix3<T> = Matrix3::from_fn(|i,j| self.matrix[(i,j)]);
+
+        // The adjugate is the inverse scaled by the determinant, so its
+        // transpose gives the same direction without dividing.
+        let adjugate = upper.adjugate();
+        let Vector3(x,y,z) = normal;
+        let row = |r: usize| adjugate[(0,r)]*x + adjugate[(1,r)]*y + adjugate[(2,r)]*z;
+
+        let transformed = Vector3(row(0), row(1), row(2));
+        let transformed = if upper.determinant() < T::zero() {
+            num::zero::<Vector3<T>>() - transformed
+        } else {
+            transformed
+        };
+
+        transformed * transformed.length_squared().rsqrt()
+    }
+
+    /// Interpolates between two transformations.
+    ///
+    /// The translations and scales are interpolated linearly, and the
+    /// rotations spherically.
+    pub fn interpolate(&self, other: &Self, t: T) -> Self {
+        let (translation_a, rotation_a, scale_a) = self.decompose();
+        let (translation_b, rotation_b, scale_b) = other.decompose();
+
+        let lerp = |a: Vector3<T>, b: Vector3<T>| a + (b - a) * t;
+        let rotation = rotation_a.to_quaternion().slerp(rotation_b.to_quaternion(), t);
+
+        Transform3::from_trs(lerp(translation_a, translation_b),
+                             rotation,
+                             lerp(scale_a, scale_b))
+    }
+
+    /// Creates a rigid transformation from a dual quaternion.
+    pub fn from_dual_quaternion(dq: math::DualQuaternion<T>) -> Self {
+        let dq = dq.normalize();
+        Transform3::from_trs(dq.translation(), dq.rotation(), Vector3(T::one(), T::one(), T::one()))
+    }
+
+    /// Converts the rotation and translation to a dual quaternion.
+    /// Any scale is discarded.
+    pub fn to_dual_quaternion(&self) -> math::DualQuaternion<T> {
+        math::DualQuaternion::from_rotation_translation(self.get_rotation().to_quaternion(),
+                                                        self.get_translation())
+    }
+
+    /// Converts the transformation to exact intervals, so that it can
+    /// bound the rounding error of whatever it transforms.
+    pub fn to_interval(&self) -> Transform3<num::Interval<T>>
+        where T: num::Float {
+        Transform3::from_matrix(Matrix4::from_fn(|i,j| num::Interval::point(self.matrix[(i,j)])))
+    }
+
+    /// Replaces the upper 3x3 matrix with a rotation followed by a scale.
+    fn set_rotation_and_scale(mut self, rotation: Matrix3<T>, scale: Vector3<T>) -> Self {
+        let scale = [scale.x(), scale.y(), scale.z()];
+
+        for row in 0..3 {
+            for col in 0..3 {
+                self.matrix[(row,col)] = rotation[(row,col)] * scale[col];
+            }
+        }
+        self
+    }
 }
 
 impl<T: Num+Decimal+num::Signed> From<Vector3<T>> for Rotation3<T>
@@ -188,6 +461,34 @@ impl<T: Num+Decimal+num::Signed> From<Vector3<T>> for Rotation3<T>
     }
 }
 
+impl<T: Num+Decimal> From<math::Quaternion<T>> for Rotation3<T>
+{
+    fn from(quat: math::Quaternion<T>) -> Rotation3<T> {
+        Rotation3::from_quaternion(quat)
+    }
+}
+
+impl<T: Num+Decimal> From<Rotation3<T>> for math::Quaternion<T>
+{
+    fn from(rotation: Rotation3<T>) -> math::Quaternion<T> {
+        rotation.to_quaternion()
+    }
+}
+
+impl<T: Num+Decimal> From<math::DualQuaternion<T>> for Transform3<T>
+{
+    fn from(dq: math::DualQuaternion<T>) -> Transform3<T> {
+        Transform3::from_dual_quaternion(dq)
+    }
+}
+
+impl<T: Num+Decimal> From<Transform3<T>> for math::DualQuaternion<T>
+{
+    fn from(transform: Transform3<T>) -> math::DualQuaternion<T> {
+        transform.to_dual_quaternion()
+    }
+}
+
 impl<T: Num> Into<Matrix4<T>> for Transform3<T>
 {
     fn into(self) -> Matrix4<T> {
@@ -204,4 +505,221 @@ impl<T: Num+Decimal> std::ops::Mul for Transform3<T>
     }
 }
 
+/// Gets the half width and half height of the near plane of a
+/// symmetric view frustum.
+fn half_extents<T: Decimal>(fov_y: T, aspect: T, near: T) -> (T,T) {
+    let half_height = near * (fov_y / (T::one() + T::one())).tan();
+    (half_height * aspect, half_height)
+}
+
+/// Transforms a point, and performs the perspective divide.
+#[cfg(test)]
+fn project(transform: Transform3<f64>, point: Vector3<f64>) -> Vector3<f64> {
+    let m: Matrix4<f64> = transform.into();
+    (m * math::Vector4::from_point(point)).homogeneous_divide()
+}
+
+#[cfg(test)]
+fn assert_close(a: Vector3<f64>, b: Vector3<f64>) {
+    assert!((a - b).length_squared() < 1.0e-18,
+            "{:?} != {:?}", a.xyz(), b.xyz());
+}
+
+#[test]
+fn test_trs_round_trip() {
+    let rotation = math::Quaternion::from_axis_angle(Vector3(0.3, 1.0, -0.2), 1.1);
+    let transform = Transform3::from_trs(Vector3(1.0, -2.0, 3.0), rotation,
+                                         Vector3(2.0, 0.5, 3.0));
+
+    let (translation, rot, scale) = transform.decompose();
+    assert_close(translation, Vector3(1.0, -2.0, 3.0));
+    assert_close(scale, Vector3(2.0, 0.5, 3.0));
+    assert!((rot.to_quaternion().dot(rotation).abs() - 1.0).abs() < 1.0e-12);
+
+    // Scaling a rotated transform scales its local axes.
+    let scaled = transform.scale(Vector3(2.0, 2.0, 1.0));
+    assert_close(scaled.get_scale(), Vector3(4.0, 1.0, 3.0));
+    assert!((scaled.get_rotation().to_quaternion().dot(rotation).abs() - 1.0).abs() < 1.0e-12);
+
+    // Mirroring is kept in the X scale.
+    let mirrored = Transform3::from_trs(Vector3(0.0, 0.0, 0.0), rotation,
+                                        Vector3(-1.0, 2.0, 1.0));
+    assert_close(mirrored.get_scale(), Vector3(-1.0, 2.0, 1.0));
+}
+
+#[test]
+fn test_matrix_vector_convention() {
+    let transform = Transform3::from_trs(Vector3(1.0, -2.0, 3.0),
+                                         math::Quaternion::from_axis_angle(Vector3(0.3, 1.0, -0.2), 1.1),
+                                         Vector3(2.0, 0.5, 3.0));
+    let m: Matrix4<f64> = transform.into();
+    let point = Vector3(0.5, 4.0, -1.5);
+
+    assert_close((m * math::Vector4::from_point(point)).xyz(), transform.transform_point(point));
+    assert_close((m * math::Vector4::from_direction(point)).xyz(), transform.transform_vector(point));
+
+    // The near and far planes map to the ends of the depth range.
+    let (near, far) = (0.1, 100.0);
+    let depth = |projection: Transform3<f64>, distance: f64| {
+        let m: Matrix4<f64> = projection.into();
+        (m * math::Vector4::from_point(Vector3(0.0, 0.0, -distance))).homogeneous_divide().z()
+    };
+    let assert_depths = |projection: Transform3<f64>, (first, last): (f64, f64)| {
+        assert!((depth(projection, near) - first).abs() < 1.0e-9);
+        assert!((depth(projection, far) - last).abs() < 1.0e-9);
+    };
+
+    assert_depths(Transform3::perspective(1.2, near, far, 1.5), (-1.0, 1.0));
+    assert_depths(Transform3::perspective_fov(1.2, 1.5, near, far, DepthRange::NegativeOneToOne), (-1.0, 1.0));
+    assert_depths(Transform3::perspective_fov(1.2, 1.5, near, far, DepthRange::ZeroToOne), (0.0, 1.0));
+}
+
+#[test]
+fn test_simd_matches_transform() {
+    let rotation = math::Quaternion::from_axis_angle(Vector3(0.3, 1.0, -0.2), 1.1);
+    let transform: Transform3<f32> = Transform3::from_trs(Vector3(1.0, -2.0, 3.0), rotation,
+                                                          Vector3(2.0, 0.5, 3.0));
+    let points: Vec<Vector3<f32>> = (0..7).map(|i| Vector3(i as f32, 1.0 - i as f32, 0.5)).collect();
+    let mut output = vec![Vector3(0.0, 0.0, 0.0); points.len()];
+
+    math::simd::transform_points(&transform.into(), &points, &mut output);
+
+    for (&point, &result) in points.iter().zip(output.iter()) {
+        let expected = transform.transform_point(point);
+        assert!((result - expected).length_squared() < 1.0e-8,
+                "{:?} != {:?}", result.xyz(), expected.xyz());
+    }
+}
+
+#[test]
+fn test_transform_inverse() {
+    let transform = Transform3::from_trs(Vector3(4.0, 5.0, 6.0),
+                                         math::Quaternion::from_axis_angle(Vector3(1.0, 1.0, 0.0), 0.7),
+                                         Vector3(1.0, 2.0, 4.0));
+    let point = Vector3(-1.0, 0.5, 2.0);
+
+    assert_close(transform.inverse().transform_point(transform.transform_point(point)), point);
+    assert_close(transform.transform_vector(point) + transform.get_translation(),
+                 transform.transform_point(point));
+
+    let composed = transform * transform.inverse();
+    assert_close(composed.transform_point(point), point);
+
+    let singular = Transform3::from_trs(Vector3(0.0, 0.0, 0.0), Rotation3::identity(),
+                                        Vector3(1.0, 0.0, 1.0));
+    assert!(singular.try_inverse().is_none());
+}
+
+#[test]
+fn test_transform_normal() {
+    // Squashing a 45 degree slope makes its normal steeper.
+    let transform = Transform3::from_trs(Vector3(7.0, 0.0, 0.0), Rotation3::identity(),
+                                         Vector3(1.0, 0.5, 1.0));
+    let half_sqrt2 = 0.5f64.sqrt();
+    let normal = transform.transform_normal(Vector3(-half_sqrt2, half_sqrt2, 0.0));
+
+    let tangent = transform.transform_vector(Vector3(1.0, 1.0, 0.0));
+    assert!(normal.dot(tangent).abs() < 1.0e-12);
+    assert!((normal.length_squared() - 1.0).abs() < 1.0e-12);
+    assert!(normal.y() > 0.0);
+}
+
+#[test]
+fn test_transform_interpolate() {
+    let axis = Vector3(0.0, 0.0, 1.0);
+    let a = Transform3::from_trs(Vector3(0.0, 0.0, 0.0),
+                                 math::Quaternion::from_axis_angle(axis, 0.0),
+                                 Vector3(1.0, 1.0, 1.0));
+    let b = Transform3::from_trs(Vector3(10.0, 0.0, -4.0),
+                                 math::Quaternion::from_axis_angle(axis, 2.0),
+                                 Vector3(3.0, 1.0, 1.0));
+
+    let (translation, rotation, scale) = a.interpolate(&b, 0.5).decompose();
+    assert_close(translation, Vector3(5.0, 0.0, -2.0));
+    assert_close(scale, Vector3(2.0, 1.0, 1.0));
+
+    let (_, angle) = rotation.to_quaternion().to_axis_angle();
+    assert!((angle - 1.0).abs() < 1.0e-12);
+}
+
+#[test]
+fn test_dual_quaternion_round_trip() {
+    let transform = Transform3::from_trs(Vector3(1.0, -3.0, 2.0),
+                                         math::Quaternion::from_axis_angle(Vector3(0.0, 1.0, 1.0), 0.9),
+                                         Vector3(1.0, 1.0, 1.0));
+    let dq: math::DualQuaternion<f64> = transform.into();
+    let point = Vector3(2.0, 0.5, -1.0);
+
+    assert_close(dq.transform_point(point), transform.transform_point(point));
+    assert_close(Transform3::from(dq).transform_point(point), transform.transform_point(point));
+}
+
+#[test]
+fn test_rotation_quaternion_round_trip() {
+    let quat = math::Quaternion::from_axis_angle(Vector3(1.0, -2.0, 0.5), 2.0);
+    let rotation: Rotation3<f64> = quat.into();
+    let round_trip: math::Quaternion<f64> = rotation.into();
+
+    assert!((quat.dot(round_trip).abs() - 1.0).abs() < 1.0e-12);
+}
+
+#[test]
+fn test_look_at() {
+    let view = Transform3::look_at(Vector3(1.0, 2.0, 3.0),
+                                   Vector3(1.0, 2.0, -7.0),
+                                   Vector3(0.0, 1.0, 0.0));
+
+    assert_close(project(view, Vector3(1.0, 2.0, 3.0)), Vector3(0.0, 0.0, 0.0));
+    assert_close(project(view, Vector3(1.0, 2.0, -2.0)), Vector3(0.0, 0.0, -5.0));
+    assert_close(project(view, Vector3(2.0, 4.0, 3.0)), Vector3(1.0, 2.0, 0.0));
+
+    // Looking along +X, the camera's right is +Z.
+    let view = Transform3::look_at(Vector3(0.0, 0.0, 0.0),
+                                   Vector3(5.0, 0.0, 0.0),
+                                   Vector3(0.0, 1.0, 0.0));
+    assert_close(project(view, Vector3(3.0, 0.0, -1.0)), Vector3(-1.0, 0.0, -3.0));
+}
+
+#[test]
+fn test_orthographic() {
+    for &(depth, near_z) in [(DepthRange::NegativeOneToOne, -1.0),
+                             (DepthRange::ZeroToOne, 0.0)].iter() {
+        let ortho = Transform3::orthographic(-2.0, 6.0, -1.0, 3.0, 1.0, 11.0, depth);
+
+        assert_close(project(ortho, Vector3(-2.0, -1.0, -1.0)), Vector3(-1.0, -1.0, near_z));
+        assert_close(project(ortho, Vector3(6.0, 3.0, -11.0)), Vector3(1.0, 1.0, 1.0));
+    }
+}
+
+#[test]
+fn test_frustum() {
+    for &(depth, near_z) in [(DepthRange::NegativeOneToOne, -1.0),
+                             (DepthRange::ZeroToOne, 0.0)].iter() {
+        let frustum = Transform3::frustum(-1.0, 3.0, -2.0, 2.0, 2.0, 10.0, depth);
+
+        assert_close(project(frustum, Vector3(-1.0, -2.0, -2.0)), Vector3(-1.0, -1.0, near_z));
+        assert_close(project(frustum, Vector3(15.0, 10.0, -10.0)), Vector3(1.0, 1.0, 1.0));
+    }
+
+    let fov = Transform3::perspective_fov(std::f64::consts::PI / 2.0, 2.0, 1.0, 100.0,
+                                          DepthRange::ZeroToOne);
+    assert_close(project(fov, Vector3(2.0, 1.0, -1.0)), Vector3(1.0, 1.0, 0.0));
+    assert_close(project(fov, Vector3(-200.0, 100.0, -100.0)), Vector3(-1.0, 1.0, 1.0));
+}
 
+#[test]
+fn test_perspective_variants() {
+    let fov = std::f64::consts::PI / 2.0;
+
+    let infinite = Transform3::perspective_infinite(fov, 1.0, 0.5, DepthRange::NegativeOneToOne);
+    assert_close(project(infinite, Vector3(0.0, 0.0, -0.5)), Vector3(0.0, 0.0, -1.0));
+    assert!(project(infinite, Vector3(0.0, 0.0, -1.0e9)).z() < 1.0);
+
+    let reversed = Transform3::perspective_reversed(fov, 1.0, 0.5, 50.0);
+    assert_close(project(reversed, Vector3(0.5, 0.5, -0.5)), Vector3(1.0, 1.0, 1.0));
+    assert_close(project(reversed, Vector3(0.0, 0.0, -50.0)), Vector3(0.0, 0.0, 0.0));
+
+    let reversed = Transform3::perspective_reversed_infinite(fov, 1.0, 0.5);
+    assert_close(project(reversed, Vector3(0.0, 0.0, -0.5)), Vector3(0.0, 0.0, 1.0));
+    assert!(project(reversed, Vector3(0.0, 0.0, -1.0e9)).z() > 0.0);
+}
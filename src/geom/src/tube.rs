@@ -0,0 +1,166 @@
+//! Tube meshes swept along curves.
+
+use mesh;
+use math::{Scalar,Vector3};
+use math::spline::Curve;
+use num::{self,Integer,Decimal};
+use std;
+
+/// A vertex on a tube.
+#[derive(Copy,Clone)]
+pub struct Vertex
+{
+    pub position: Vector3,
+    /// The unit normal, pointing away from the curve.
+    pub normal: Vector3,
+    /// The texture coordinates. `u` wraps around the tube, and `v` is
+    /// the fraction of the distance along it.
+    pub uv: (Scalar,Scalar),
+}
+
+impl ::Vertex for Vertex
+{
+    type T = Scalar;
+
+    fn coords(self) -> Vector3<Scalar> {
+        self.position
+    }
+}
+
+/// Sweeps a circle along a curve, creating an open tube.
+///
+/// The curve is tessellated to within `tolerance`, and each ring has
+/// `sides` sides. Rings are oriented with parallel transport frames, so
+/// the tube doesn't twist more than the curve does.
+/// Panics if there are fewer than three sides.
+pub fn tube<I,V,C>(curve: &C,
+                   radius: Scalar,
+                   sides: usize,
+                   tolerance: Scalar) -> mesh::StaticData<I,V>
+    where I: Integer, usize: num::TryCast<I>, V: From<Vertex>, C: Curve<Scalar,Vector3> {
+    assert!(sides >= 3, "a tube needs at least three sides");
+
+    let points = curve.tessellate(tolerance);
+    let normalize = |v: Vector3| v * v.length_squared().rsqrt();
+
+    // Approximate the tangents from neighbouring points.
+    let tangents: Vec<Vector3> = (0..points.len()).map(|i| {
+        let before = points[if i == 0 { 0 } else { i - 1 }];
+        let after = points[std::cmp::min(i + 1, points.len() - 1)];
+        normalize(after - before)
+    }).collect();
+
+    // Start with the axis least aligned with the curve, and carry it along
+    // by removing the part parallel to each tangent in turn.
+    let first = tangents[0];
+    let (ax,ay,az) = first.as_positive().into();
+    let axis = if ax <= ay && ax <= az {
+        Vector3(1.0, 0.0, 0.0)
+    } else if ay <= az {
+        Vector3(0.0, 1.0, 0.0)
+    } else {
+        Vector3(0.0, 0.0, 1.0)
+    };
+
+    let mut normal = normalize(axis - first * first.dot(axis));
+
+    // The distance along the line strip to each point, so that the
+    // texture runs from 0 at the start to 1 at the end.
+    let distances: Vec<Scalar> = points.iter().enumerate().scan(0.0, |distance, (i, &point)| {
+        if i > 0 {
+            *distance += (point - points[i - 1]).length_squared().sqrt();
+        }
+        Some(*distance)
+    }).collect();
+    let total = *distances.last().unwrap();
+
+    let mut vertices = Vec::with_capacity(points.len() * (sides + 1));
+
+    for (i, (&point, &tangent)) in points.iter().zip(tangents.iter()).enumerate() {
+        let projected = normal - tangent * tangent.dot(normal);
+        if projected.length_squared() > 1.0e-12 {
+            normal = normalize(projected);
+        }
+        let binormal = tangent.cross(normal);
+
+        let v = if total > 0.0 { distances[i] / total } else { 0.0 };
+
+        // The seam has two vertices, so the texture can wrap.
+        for side in 0..(sides + 1) {
+            let u = side as Scalar / sides as Scalar;
+            let (sin,cos) = (u * Scalar::tau()).sincos();
+            let direction = normal * cos + binormal * sin;
+
+            vertices.push(Vertex {
+                position: point + direction * radius,
+                normal: direction,
+                uv: (u, v),
+            });
+        }
+    }
+
+    let ring = sides + 1;
+    let mut indices = Vec::with_capacity((points.len() - 1) * sides * 6);
+
+    for i in 0..(points.len() - 1) {
+        for side in 0..sides {
+            let a = i * ring + side;
+            let (b, c, d) = (a + 1, a + ring, a + ring + 1);
+
+            indices.extend_from_slice(&[a, b, c, b, d, c]);
+        }
+    }
+
+    let mut builder = mesh::StaticBuilder::new();
+    builder.feed_vertices(vertices.into_iter().map(V::from));
+    builder.feed_indices(mesh::cast_indices(indices).into_iter());
+    builder.into()
+}
+
+#[test]
+fn test_tube_faces_outwards() {
+    use math::spline::CatmullRom;
+
+    let curve = CatmullRom::new(vec![Vector3(0.0, 0.0, 0.0), Vector3(2.0, 1.0, 0.0),
+                                     Vector3(4.0, 0.0, 1.0), Vector3(5.0, -2.0, 2.0)]);
+    let data: mesh::StaticData<u32,Vertex> = tube(&curve, 0.25, 8, 1.0e-2);
+    let buffer = &data.data[0];
+
+    assert_eq!(buffer.vertices.len() % 9, 0);
+    assert_eq!(buffer.indices.len(), (buffer.vertices.len() / 9 - 1) * 8 * 6);
+
+    for vertex in buffer.vertices.iter() {
+        assert!((vertex.normal.length_squared() - 1.0).abs() < 1.0e-4);
+    }
+
+    for triangle in buffer.indices.chunks(3) {
+        let vertex = |i: usize| buffer.vertices[triangle[i] as usize];
+        let (a, b, c) = (vertex(0), vertex(1), vertex(2));
+        let face_normal = (b.position - a.position).cross(c.position - a.position);
+
+        assert!(face_normal.dot(a.normal + b.normal + c.normal) > 0.0);
+    }
+}
+
+#[test]
+fn test_tube_texture_spans_curve() {
+    use math::spline::CatmullRom;
+
+    let points: Vec<Vector3> = (0..33).map(|i| Vector3(i as Scalar, (i % 2) as Scalar, 0.0)).collect();
+    let data: mesh::StaticData<u32,Vertex> = tube(&CatmullRom::new(points), 0.1, 4, 1.0e-2);
+    let vertices = &data.data[0].vertices;
+
+    assert_eq!(vertices.first().unwrap().uv.1, 0.0);
+    assert_eq!(vertices.last().unwrap().uv.1, 1.0);
+    assert!(vertices.windows(2).all(|w| w[0].uv.1 <= w[1].uv.1));
+}
+
+#[test]
+#[should_panic(expected = "can not be addressed")]
+fn test_tube_index_overflow() {
+    use math::spline::CatmullRom;
+
+    // Each ring has more vertices than a u8 can address.
+    let curve = CatmullRom::new(vec![Vector3(0.0, 0.0, 0.0), Vector3(1.0, 0.0, 0.0)]);
+    let _: mesh::StaticData<u8,Vertex> = tube(&curve, 0.1, 300, 1.0e-2);
+}
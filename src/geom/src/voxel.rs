@@ -0,0 +1,440 @@
+//! Voxel grids and greedy meshing.
+//!
+//! Blocks are stored in fixed-size chunks. Each chunk is meshed on its own,
+//! so editing a block only requires the chunk containing it (and the
+//! neighbouring chunk, if the block is on a border) to be regenerated.
+//!
+//! Faces between two solid blocks are culled, and the visible faces of
+//! identical blocks are merged into the largest rectangles possible.
+
+use mesh;
+use math::{Scalar,Vector3};
+use num::{self,Integer};
+use std::collections::{BTreeMap,BTreeSet};
+
+/// The number of blocks along each edge of a chunk.
+pub const CHUNK_SIZE: usize = 16;
+
+/// The position of a chunk, in chunks.
+pub type ChunkPos = (i32,i32,i32);
+/// The position of a block, in blocks.
+pub type BlockPos = (i32,i32,i32);
+
+/// A block in a voxel grid.
+///
+/// Faces of neighbouring blocks are only merged if the blocks are equal,
+/// so any colour or material information should be part of the block.
+pub trait Block : Copy + PartialEq
+{
+    /// Gets the block which represents empty space.
+    fn empty() -> Self;
+
+    /// Checks if the block is empty space.
+    fn is_empty(&self) -> bool;
+}
+
+impl<T: Copy + PartialEq> Block for Option<T>
+{
+    fn empty() -> Self { None }
+    fn is_empty(&self) -> bool { self.is_none() }
+}
+
+/// A vertex generated by the mesher.
+#[derive(Copy,Clone)]
+pub struct Vertex<B: Block>
+{
+    pub position: Vector3,
+    pub normal: Vector3,
+    /// The block which the face belongs to.
+    pub block: B,
+}
+
+impl<B: Block> ::Vertex for Vertex<B>
+{
+    type T = Scalar;
+
+    fn coords(self) -> Vector3<Scalar> {
+        self.position
+    }
+}
+
+/// A cube of blocks.
+#[derive(Clone)]
+pub struct Chunk<B: Block>
+{
+    blocks: Vec<B>,
+}
+
+impl<B: Block> Chunk<B>
+{
+    /// Creates a chunk filled with empty space.
+    pub fn empty() -> Self {
+        Chunk::from_fn(|_,_,_| B::empty())
+    }
+
+    /// Creates a chunk from a function taking the local coordinates
+    /// of each block.
+    pub fn from_fn<F>(f: F) -> Self
+        where F: Fn(usize,usize,usize) -> B {
+        let mut blocks = Vec::with_capacity(CHUNK_SIZE*CHUNK_SIZE*CHUNK_SIZE);
+
+        for z in 0..CHUNK_SIZE {
+            for y in 0..CHUNK_SIZE {
+                for x in 0..CHUNK_SIZE {
+                    blocks.push(f(x,y,z));
+                }
+            }
+        }
+
+        Chunk {
+            blocks: blocks,
+        }
+    }
+
+    /// Gets a block.
+    /// Panics on out of range.
+    pub fn get(&self, (x,y,z): (usize,usize,usize)) -> B {
+        self.blocks[calculate_index(x,y,z)]
+    }
+
+    /// Sets a block.
+    /// Panics on out of range.
+    pub fn set(&mut self, (x,y,z): (usize,usize,usize), block: B) {
+        self.blocks[calculate_index(x,y,z)] = block;
+    }
+
+    /// Checks if every block is empty.
+    pub fn is_empty(&self) -> bool {
+        self.blocks.iter().all(|b| b.is_empty())
+    }
+}
+
+/// An unbounded grid of blocks.
+pub struct Grid<B: Block>
+{
+    chunks: BTreeMap<ChunkPos,Chunk<B>>,
+    /// Chunks which need their meshes regenerated.
+    dirty: BTreeSet<ChunkPos>,
+    /// The width of a block in world units.
+    block_size: Scalar,
+}
+
+impl<B: Block> Grid<B>
+{
+    /// Creates an empty grid.
+    pub fn new(block_size: Scalar) -> Self {
+        Grid {
+            chunks: BTreeMap::new(),
+            dirty: BTreeSet::new(),
+            block_size: block_size,
+        }
+    }
+
+    /// Gets a block. Blocks in missing chunks are empty.
+    pub fn get(&self, pos: BlockPos) -> B {
+        let (chunk_pos, local) = split_position(pos);
+
+        match self.chunks.get(&chunk_pos) {
+            Some(chunk) => chunk.get(local),
+            None => B::empty(),
+        }
+    }
+
+    /// Sets a block, creating its chunk if necessary.
+    ///
+    /// This marks the chunk as dirty, along with any neighbouring
+    /// chunk that shares a face with the block.
+    pub fn set(&mut self, pos: BlockPos, block: B) {
+        if self.get(pos) == block {
+            return;
+        }
+
+        let (chunk_pos, local) = split_position(pos);
+
+        if !self.chunks.contains_key(&chunk_pos) {
+            self.insert_chunk(chunk_pos, Chunk::empty());
+        }
+
+        self.chunks.get_mut(&chunk_pos).unwrap().set(local, block);
+        self.dirty.insert(chunk_pos);
+
+        let (cx,cy,cz) = chunk_pos;
+        let local = [local.0, local.1, local.2];
+        let last = CHUNK_SIZE - 1;
+
+        for axis in 0..3 {
+            let mut offset = [0, 0, 0];
+
+            if local[axis] == 0 {
+                offset[axis] = -1;
+            } else if local[axis] == last {
+                offset[axis] = 1;
+            } else {
+                continue;
+            }
+
+            self.mark_dirty((cx + offset[0], cy + offset[1], cz + offset[2]));
+        }
+    }
+
+    /// Inserts a chunk, replacing any existing chunk.
+    /// The chunk and its neighbours are marked as dirty.
+    pub fn insert_chunk(&mut self, pos: ChunkPos, chunk: Chunk<B>) {
+        let (cx,cy,cz) = pos;
+
+        self.chunks.insert(pos, chunk);
+        self.dirty.insert(pos);
+
+        for &(dx,dy,dz) in NEIGHBOURS.iter() {
+            self.mark_dirty((cx+dx, cy+dy, cz+dz));
+        }
+    }
+
+    /// Removes a chunk.
+    /// Its neighbours are marked as dirty.
+    pub fn remove_chunk(&mut self, pos: ChunkPos) -> Option<Chunk<B>> {
+        let (cx,cy,cz) = pos;
+        let chunk = self.chunks.remove(&pos);
+
+        self.dirty.remove(&pos);
+        for &(dx,dy,dz) in NEIGHBOURS.iter() {
+            self.mark_dirty((cx+dx, cy+dy, cz+dz));
+        }
+
+        chunk
+    }
+
+    /// Gets a chunk.
+    pub fn chunk(&self, pos: ChunkPos) -> Option<&Chunk<B>> {
+        self.chunks.get(&pos)
+    }
+
+    /// Gets the positions of all of the chunks.
+    pub fn chunk_positions(&self) -> Vec<ChunkPos> {
+        self.chunks.keys().cloned().collect()
+    }
+
+    /// Gets the chunks which have changed since this was last called.
+    ///
+    /// These are the only chunks whose meshes need to be regenerated.
+    pub fn take_dirty(&mut self) -> Vec<ChunkPos> {
+        let dirty = self.dirty.iter().cloned().collect();
+        self.dirty.clear();
+        dirty
+    }
+
+    /// Generates the mesh of a chunk.
+    ///
+    /// Vertex positions are in world units.
+    pub fn mesh_chunk<I,V>(&self, pos: ChunkPos) -> mesh::StaticData<I,V>
+        where I: Integer, usize: num::TryCast<I>, V: From<Vertex<B>> {
+        let mut builder = mesh::StaticBuilder::new();
+
+        if let Some(chunk) = self.chunks.get(&pos) {
+            let (vertices, indices) = self.greedy_mesh(pos, chunk);
+
+            builder.feed_vertices(vertices.into_iter().map(V::from));
+            builder.feed_indices(mesh::cast_indices(indices).into_iter());
+        }
+
+        builder.into()
+    }
+
+    fn mark_dirty(&mut self, pos: ChunkPos) {
+        if self.chunks.contains_key(&pos) {
+            self.dirty.insert(pos);
+        }
+    }
+
+    fn greedy_mesh(&self, pos: ChunkPos, chunk: &Chunk<B>) -> (Vec<Vertex<B>>, Vec<usize>) {
+        let size = CHUNK_SIZE as i32;
+        let origin = [pos.0 * size, pos.1 * size, pos.2 * size];
+
+        let mut vertices = Vec::new();
+        let mut indices = Vec::new();
+
+        // Gets a block relative to the chunk origin, which may
+        // lie in a neighbouring chunk.
+        let block_at = |p: [i32; 3]| {
+            if p.iter().all(|&c| c >= 0 && c < size) {
+                chunk.get((p[0] as usize, p[1] as usize, p[2] as usize))
+            } else {
+                self.get((origin[0] + p[0], origin[1] + p[1], origin[2] + p[2]))
+            }
+        };
+
+        for axis in 0..3 {
+            let (u, v) = ((axis + 1) % 3, (axis + 2) % 3);
+
+            for &positive in [false, true].iter() {
+                let step = if positive { 1 } else { -1 };
+
+                for slice in 0..size {
+                    // Find the visible faces on this slice.
+                    let mut mask: Vec<Option<B>> = Vec::with_capacity(CHUNK_SIZE*CHUNK_SIZE);
+
+                    for j in 0..size {
+                        for i in 0..size {
+                            let mut p = [0; 3];
+                            p[axis] = slice; p[u] = i; p[v] = j;
+
+                            let block = block_at(p);
+                            p[axis] += step;
+
+                            if !block.is_empty() && block_at(p).is_empty() {
+                                mask.push(Some(block));
+                            } else {
+                                mask.push(None);
+                            }
+                        }
+                    }
+
+                    // Merge the faces into rectangles.
+                    for j in 0..CHUNK_SIZE {
+                        let mut i = 0;
+
+                        while i < CHUNK_SIZE {
+                            let block = match mask[i + j*CHUNK_SIZE] {
+                                Some(block) => block,
+                                None => { i += 1; continue; },
+                            };
+
+                            let mut width = 1;
+                            while i + width < CHUNK_SIZE && mask[i + width + j*CHUNK_SIZE] == Some(block) {
+                                width += 1;
+                            }
+
+                            let mut height = 1;
+                            while j + height < CHUNK_SIZE && (i..i+width).all(|k| {
+                                mask[k + (j+height)*CHUNK_SIZE] == Some(block)
+                            }) {
+                                height += 1;
+                            }
+
+                            for dj in 0..height {
+                                for di in 0..width {
+                                    mask[i + di + (j+dj)*CHUNK_SIZE] = None;
+                                }
+                            }
+
+                            let mut corner = [0; 3];
+                            corner[axis] = origin[axis] + slice + if positive { 1 } else { 0 };
+                            corner[u] = origin[u] + i as i32;
+                            corner[v] = origin[v] + j as i32;
+
+                            let mut du = [0; 3];
+                            du[u] = width as i32;
+                            let mut dv = [0; 3];
+                            dv[v] = height as i32;
+
+                            let mut normal = [0.0; 3];
+                            normal[axis] = step as Scalar;
+
+                            self.emit_quad(corner, du, dv, positive, normal, block,
+                                           &mut vertices, &mut indices);
+
+                            i += width;
+                        }
+                    }
+                }
+            }
+        }
+
+        (vertices, indices)
+    }
+
+    /// Adds a quad, wound counter-clockwise when seen from the
+    /// direction of its normal.
+    fn emit_quad(&self,
+                 corner: [i32; 3],
+                 du: [i32; 3],
+                 dv: [i32; 3],
+                 positive: bool,
+                 normal: [Scalar; 3],
+                 block: B,
+                 vertices: &mut Vec<Vertex<B>>,
+                 indices: &mut Vec<usize>) {
+        let add = |a: [i32; 3], b: [i32; 3]| [a[0]+b[0], a[1]+b[1], a[2]+b[2]];
+        let to_world = |p: [i32; 3]| {
+            Vector3(p[0] as Scalar, p[1] as Scalar, p[2] as Scalar) * self.block_size
+        };
+
+        let corners = if positive {
+            [corner, add(corner, du), add(add(corner, du), dv), add(corner, dv)]
+        } else {
+            [corner, add(corner, dv), add(add(corner, du), dv), add(corner, du)]
+        };
+
+        let first = vertices.len();
+        for &c in corners.iter() {
+            vertices.push(Vertex {
+                position: to_world(c),
+                normal: Vector3(normal[0], normal[1], normal[2]),
+                block: block,
+            });
+        }
+
+        indices.extend([0, 1, 2, 0, 2, 3].iter().map(|&i| first + i));
+    }
+}
+
+/// The offsets of the chunks which share a face with a chunk.
+const NEIGHBOURS: [(i32,i32,i32); 6] = [
+    (-1, 0, 0), (1, 0, 0),
+    (0, -1, 0), (0, 1, 0),
+    (0, 0, -1), (0, 0, 1),
+];
+
+/// Splits a block position into the chunk position and the local
+/// position inside the chunk.
+fn split_position((x,y,z): BlockPos) -> (ChunkPos,(usize,usize,usize)) {
+    let size = CHUNK_SIZE as i32;
+
+    let split = |c: i32| {
+        let chunk = if c < 0 { (c + 1) / size - 1 } else { c / size };
+        (chunk, (c - chunk*size) as usize)
+    };
+
+    let ((cx,lx), (cy,ly), (cz,lz)) = (split(x), split(y), split(z));
+    ((cx,cy,cz), (lx,ly,lz))
+}
+
+fn calculate_index(x: usize, y: usize, z: usize) -> usize {
+    assert!(x < CHUNK_SIZE && y < CHUNK_SIZE && z < CHUNK_SIZE, "out of bounds block");
+    x + y*CHUNK_SIZE + z*CHUNK_SIZE*CHUNK_SIZE
+}
+
+#[test]
+fn test_greedy_merging() {
+    let mut grid = Grid::new(1.0);
+
+    grid.set((0,0,0), Some(1));
+    grid.set((1,0,0), Some(1));
+    let mesh: mesh::StaticData<u16,Vertex<Option<u8>>> = grid.mesh_chunk((0,0,0));
+    assert_eq!(mesh.data[0].vertices.len(), 6 * 4);
+
+    // Different blocks can't be merged.
+    grid.set((1,0,0), Some(2));
+    let mesh: mesh::StaticData<u16,Vertex<Option<u8>>> = grid.mesh_chunk((0,0,0));
+    assert_eq!(mesh.data[0].vertices.len(), 10 * 4);
+    assert_eq!(mesh.data[0].indices.len(), 10 * 6);
+}
+
+#[test]
+fn test_dirty_chunks() {
+    let mut grid = Grid::new(1.0);
+
+    grid.set((0,0,0), Some(1));
+    grid.set((-1,0,0), Some(1));
+    assert_eq!(grid.take_dirty(), vec![(-1,0,0), (0,0,0)]);
+
+    // The face between the two chunks is hidden.
+    let mesh: mesh::StaticData<u16,Vertex<Option<u8>>> = grid.mesh_chunk((0,0,0));
+    assert_eq!(mesh.data[0].vertices.len(), 5 * 4);
+
+    grid.set((5,5,5), Some(2));
+    assert_eq!(grid.take_dirty(), vec![(0,0,0)]);
+
+    grid.set((0,5,5), Some(2));
+    assert_eq!(grid.take_dirty(), vec![(-1,0,0), (0,0,0)]);
+}
@@ -56,11 +56,13 @@ impl Canvas
                             "OpenGL only supports vertices with 1..4 components");
 
 
+                    let normalized = if format.normalized { libgl::TRUE } else { libgl::FALSE };
+
                     // Tell OpenGL about the current piece of the vertex.
                     libgl::EnableVertexAttribArray(i as GLuint);
                     libgl::VertexAttribPointer(i as GLuint, component_count as GLint,
                                             format.component_type,
-                                            libgl::FALSE, vertex_size, cur_piece_offset as *const c_void);
+                                            normalized, vertex_size, cur_piece_offset as *const c_void);
 
                     cur_piece_offset += piece_size;
                 }
@@ -3,6 +3,7 @@ use {gl,util,CullingMode};
 use input::{self,Event};
 use libgl;
 use geom;
+use color::BlendState;
 
 use std::collections::LinkedList;
 
@@ -89,6 +90,28 @@ impl<B: gl::Backend> Device<B>
         }
     }
 
+    /// Sets how drawn pixels are blended into the framebuffer.
+    /// Blending is disabled if the state is `None`.
+    ///
+    /// Colours are expected to be premultiplied, as they are by
+    /// `color::blend`.
+    pub fn set_blend_state(&mut self, state: Option<BlendState>) {
+        match state {
+            Some(state) => {
+                let source = gl::util::blend_factor(state.source);
+                let destination = gl::util::blend_factor(state.destination);
+
+                unsafe {
+                    libgl::Enable(libgl::BLEND);
+                    libgl::BlendFunc(source, destination);
+                }
+            },
+            None => unsafe {
+                libgl::Disable(libgl::BLEND);
+            },
+        }
+    }
+
     /// Loads mesh data.
     pub fn load_mesh_data<I,V>(&mut self, data: &geom::mesh::StaticData<I,V>)
         -> gl::mesh::Data
@@ -104,11 +127,13 @@ impl<B: gl::Backend> Device<B>
     }
 
     /// Maps from normalized window coordinates to pixel coordinates.
+    /// Points outside of the window are clamped to the nearest pixel
+    /// coordinate which `u32` can represent.
     pub fn map_point_to_pixel(&self, point: (f32,f32)) -> (u32,u32) {
-        use num::Cast;
+        use num::{Cast,SaturatingCast};
 
         let dimensions: (f32,f32) = self.dimensions().cast();
-        util::map_point_to_pixel(point, dimensions).cast()
+        util::map_point_to_pixel(point, dimensions).saturating_cast()
     }
 
     /// Maps from pixel coordinates to normalized window coordinates.
@@ -284,6 +284,12 @@ pub mod uniform
         }
     }
 
+    impl Type for math::Vector4<f32> {
+        fn set(loc: GLint, math::Vector4(v1,v2,v3,v4): math::Vector4<f32>) {
+            unsafe { Uniform4f(loc, v1,v2,v3,v4) }
+        }
+    }
+
     impl Type for math::Matrix4<f32> {
         fn set(loc: GLint, mat: math::Matrix4<f32>) {
             use math::Matrix;
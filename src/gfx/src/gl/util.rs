@@ -1,6 +1,7 @@
 
 use gl::gl;
 use gl::gl::types::*;
+use num::{f16,Unorm8,Unorm16,Snorm8,Snorm16,Snorm10x3};
 use std::mem;
 
 
@@ -9,6 +10,9 @@ pub trait Type : Sized
 {
     fn specifier() -> GLenum;
     fn size() -> usize;
+
+    /// Whether integers are mapped to fractions when read by a shader.
+    fn normalized() -> bool { false }
 }
 
 /// Implements the `Type` trait for a GL-supported type.
@@ -47,10 +51,39 @@ impl_types! {
     i16: SHORT,
     u32: UNSIGNED_INT,
     i32: INT,
+    f16: HALF_FLOAT,
     f32: FLOAT,
     f64: DOUBLE
 }
 
+/// Implements the `Type` trait for normalised integer types, which
+/// share their specifier with the underlying integer.
+macro_rules! impl_normalized_types {
+    { $($ty:ident: $val:ident),* } => {
+        $(
+            impl Type for $ty {
+                fn specifier() -> GLenum {
+                    gl::$val
+                }
+
+                fn size() -> usize {
+                    mem::size_of::<$ty>()
+                }
+
+                fn normalized() -> bool { true }
+            }
+        )*
+    };
+}
+
+impl_normalized_types! {
+    Unorm8: UNSIGNED_BYTE,
+    Snorm8: BYTE,
+    Unorm16: UNSIGNED_SHORT,
+    Snorm16: SHORT,
+    Snorm10x3: INT_2_10_10_10_REV
+}
+
 /// Gets the culling mode `GLenum`.
 pub fn culling_mode(mode: ::CullingMode) -> GLenum
 {
@@ -59,3 +92,21 @@ pub fn culling_mode(mode: ::CullingMode) -> GLenum
         ::CullingMode::Back => gl::BACK,
     }
 }
+
+pub fn blend_factor(factor: ::color::Factor) -> GLenum
+{
+    use color::Factor;
+
+    match factor {
+        Factor::Zero => gl::ZERO,
+        Factor::One => gl::ONE,
+        Factor::SourceColor => gl::SRC_COLOR,
+        Factor::OneMinusSourceColor => gl::ONE_MINUS_SRC_COLOR,
+        Factor::DestinationColor => gl::DST_COLOR,
+        Factor::OneMinusDestinationColor => gl::ONE_MINUS_DST_COLOR,
+        Factor::SourceAlpha => gl::SRC_ALPHA,
+        Factor::OneMinusSourceAlpha => gl::ONE_MINUS_SRC_ALPHA,
+        Factor::DestinationAlpha => gl::DST_ALPHA,
+        Factor::OneMinusDestinationAlpha => gl::ONE_MINUS_DST_ALPHA,
+    }
+}
@@ -1,7 +1,8 @@
+use gl::gl;
 use gl::gl::types::*;
 use gl::Type;
 use math;
-use num;
+use num::{self,f16,Unorm8,Unorm16,Snorm8,Snorm16,Snorm10x3};
 
 /// A vertex format.
 pub trait Format
@@ -17,6 +18,8 @@ pub struct FormatInfo
     pub component_size: u16,
     pub component_count: u16,
     pub component_type: GLenum,
+    // whether integer components are mapped to 0..1 or -1..1
+    pub normalized: bool,
 }
 
 impl FormatInfo {
@@ -25,11 +28,22 @@ impl FormatInfo {
             component_size: 0,
             component_count: 0,
             component_type: 0,
+            normalized: false,
         }
     }
 
+    /// Checks if all of the components are packed into a single value.
+    pub fn is_packed(self) -> bool {
+        self.component_type == gl::INT_2_10_10_10_REV ||
+        self.component_type == gl::UNSIGNED_INT_2_10_10_10_REV
+    }
+
     pub fn total_size(self) -> usize {
-        self.component_size as usize * self.component_count as usize
+        if self.is_packed() {
+            self.component_size as usize
+        } else {
+            self.component_size as usize * self.component_count as usize
+        }
     }
 }
 
@@ -53,6 +67,7 @@ macro_rules! impl_format {
                     component_size: <$underlying as Type>::size() as u16,
                     component_type: <$underlying as Type>::specifier(),
                     component_count: $count,
+                    normalized: <$underlying as Type>::normalized(),
                 }
             }
         }
@@ -65,9 +80,39 @@ impl_format!(u16);
 impl_format!(i16);
 impl_format!(u32);
 impl_format!(i32);
+impl_format!(f16);
 impl_format!(f32);
 impl_format!(f64);
 
+impl_format!(Unorm8);
+impl_format!(Snorm8);
+impl_format!(Unorm16);
+impl_format!(Snorm16);
+
+impl Format for Snorm10x3
+{
+    fn info() -> FormatInfo {
+        FormatInfo {
+            component_size: Snorm10x3::size() as u16,
+            component_count: 4,
+            component_type: Snorm10x3::specifier(),
+            normalized: true,
+        }
+    }
+}
+
+impl<T: Type + num::Num> Format for math::Vector2<T>
+{
+    fn info() -> FormatInfo {
+        FormatInfo {
+            component_size: T::size() as u16,
+            component_count: 2,
+            component_type: T::specifier(),
+            normalized: T::normalized(),
+        }
+    }
+}
+
 impl<T: Type + num::Num> Format for math::Vector3<T>
 {
     fn info() -> FormatInfo {
@@ -75,6 +120,19 @@ impl<T: Type + num::Num> Format for math::Vector3<T>
             component_size: T::size() as u16,
             component_count: 3,
             component_type: T::specifier(),
+            normalized: T::normalized(),
+        }
+    }
+}
+
+impl<T: Type + num::Num> Format for math::Vector4<T>
+{
+    fn info() -> FormatInfo {
+        FormatInfo {
+            component_size: T::size() as u16,
+            component_count: 4,
+            component_type: T::specifier(),
+            normalized: T::normalized(),
         }
     }
 }
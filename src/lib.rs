@@ -5,4 +5,6 @@ extern crate geom;
 extern crate util;
 extern crate color;
 extern crate gfx;
+extern crate noise;
+extern crate random;
 
@@ -0,0 +1,275 @@
+use {Vector3,Quaternion};
+use num::Decimal;
+use std;
+
+#[cfg(test)]
+use quaternion::assert_vec_close;
+
+/// A dual quaternion, representing a rigid transformation.
+///
+/// The real part is the rotation, and the dual part encodes the
+/// translation. Unlike matrices, unit dual quaternions can be blended
+/// without losing their rigidity, which avoids the collapsing joints
+/// of linear blend skinning.
+#[derive(Copy,Clone,Debug,PartialEq)]
+pub struct DualQuaternion<T: Decimal>
+{
+    pub real: Quaternion<T>,
+    pub dual: Quaternion<T>,
+}
+
+impl<T: Decimal> DualQuaternion<T>
+{
+    pub fn new(real: Quaternion<T>, dual: Quaternion<T>) -> Self {
+        DualQuaternion {
+            real: real,
+            dual: dual,
+        }
+    }
+
+    pub fn identity() -> Self {
+        DualQuaternion::new(Quaternion::identity(), zero())
+    }
+
+    /// Creates a transformation which rotates, then translates.
+    /// The rotation must be normalized.
+    pub fn from_rotation_translation(rotation: Quaternion<T>, translation: Vector3<T>) -> Self {
+        let Vector3(x,y,z) = translation;
+        let dual = scale(Quaternion(x, y, z, T::zero()) * rotation, half());
+
+        DualQuaternion::new(rotation, dual)
+    }
+
+    pub fn from_rotation(rotation: Quaternion<T>) -> Self {
+        DualQuaternion::new(rotation, zero())
+    }
+
+    pub fn from_translation(translation: Vector3<T>) -> Self {
+        DualQuaternion::from_rotation_translation(Quaternion::identity(), translation)
+    }
+
+    /// Gets the rotation.
+    pub fn rotation(&self) -> Quaternion<T> {
+        self.real
+    }
+
+    /// Gets the translation.
+    /// The dual quaternion must be normalized.
+    pub fn translation(&self) -> Vector3<T> {
+        let two = T::one() + T::one();
+        let Quaternion(x,y,z,_) = self.dual * self.real.conjugate();
+
+        Vector3(x*two, y*two, z*two)
+    }
+
+    /// Conjugates both parts, which reverses a unit dual quaternion.
+    pub fn conjugate(&self) -> Self {
+        DualQuaternion::new(self.real.conjugate(), self.dual.conjugate())
+    }
+
+    /// Gets the reverse transformation.
+    /// The dual quaternion must be normalized.
+    pub fn inverse(&self) -> Self {
+        self.conjugate()
+    }
+
+    /// Scales the dual quaternion to unit length, and makes the
+    /// dual part orthogonal to the real part.
+    pub fn normalize(&self) -> Self {
+        let inverse_len = self.real.length_inverse();
+        let real = scale(self.real, inverse_len);
+        let dual = scale(self.dual, inverse_len);
+
+        let dual = add(dual, scale(real, T::zero() - real.dot(dual)));
+        DualQuaternion::new(real, dual)
+    }
+
+    /// Transforms a point.
+    /// The dual quaternion must be normalized.
+    pub fn transform_point(&self, point: Vector3<T>) -> Vector3<T> {
+        self.real.rotate(point) + self.translation()
+    }
+
+    /// Transforms a direction, which is only rotated.
+    /// The dual quaternion must be normalized.
+    pub fn transform_vector(&self, vector: Vector3<T>) -> Vector3<T> {
+        self.real.rotate(vector)
+    }
+
+    /// Raises a unit dual quaternion to a power, scaling its
+    /// screw motion.
+    pub fn powf(&self, exponent: T) -> Self {
+        let (two, half) = (T::one() + T::one(), half::<T>());
+        let Quaternion(rx,ry,rz,rw) = self.real;
+        let Quaternion(dx,dy,dz,dw) = self.dual;
+
+        let axis_length_squared = rx*rx + ry*ry + rz*rz;
+
+        // Without a rotation, the motion is a pure translation.
+        if axis_length_squared <= T::constant(1.0e-12) {
+            return DualQuaternion::new(self.real, scale(self.dual, exponent));
+        }
+
+        let sin_half = axis_length_squared.sqrt();
+        let clamped = if rw > T::one() { T::one() } else if rw < T::zero() - T::one() { T::zero() - T::one() } else { rw };
+        let angle = clamped.acos() * two;
+        let axis = Vector3(rx,ry,rz) / sin_half;
+
+        // The distance along the axis, and the moment of the axis.
+        let distance = T::zero() - two * dw / sin_half;
+        let moment = (Vector3(dx,dy,dz) - axis * (distance * half * clamped)) / sin_half;
+
+        let (angle, distance) = (angle * exponent, distance * exponent);
+        let (sin, cos) = (angle * half).sincos();
+
+        let Vector3(ax,ay,az) = axis * sin;
+        let Vector3(mx,my,mz) = moment * sin + axis * (distance * half * cos);
+
+        DualQuaternion::new(Quaternion(ax, ay, az, cos),
+                            Quaternion(mx, my, mz, T::zero() - distance * half * sin))
+    }
+
+    /// Interpolates along the screw motion between two transformations,
+    /// which rotates and translates at a constant speed.
+    ///
+    /// This takes the shortest path. Both must be normalized.
+    pub fn sclerp(&self, other: &Self, t: T) -> Self {
+        let other = if self.real.dot(other.real) < T::zero() { negate(*other) } else { *other };
+        let difference = self.conjugate() * other;
+
+        *self * difference.powf(t)
+    }
+
+    /// Blends weighted transformations with dual quaternion linear
+    /// blending, as used for skinning.
+    ///
+    /// The transformations are flipped into the same hemisphere as the
+    /// first, so they blend along the shortest path.
+    ///
+    /// The weights must not sum to zero. If the blended rotation vanishes,
+    /// as when a transformation's weights cancel out, the identity is
+    /// returned.
+    pub fn blend<I>(weighted: I) -> Self
+        where I: IntoIterator<Item=(DualQuaternion<T>, T)> {
+        let mut weighted = weighted.into_iter();
+        let (first, weight) = match weighted.next() {
+            Some(first) => first,
+            None => return DualQuaternion::identity(),
+        };
+
+        let mut sum = DualQuaternion::new(scale(first.real, weight), scale(first.dual, weight));
+
+        for (dq, weight) in weighted {
+            let weight = if first.real.dot(dq.real) < T::zero() { T::zero() - weight } else { weight };
+
+            sum = DualQuaternion::new(add(sum.real, scale(dq.real, weight)),
+                                      add(sum.dual, scale(dq.dual, weight)));
+        }
+
+        if sum.real.dot(sum.real) == T::zero() {
+            return DualQuaternion::identity();
+        }
+
+        sum.normalize()
+    }
+}
+
+impl<T: Decimal> std::ops::Mul for DualQuaternion<T>
+{
+    type Output = Self;
+
+    /// Composes two transformations, applying `rhs` first.
+    fn mul(self, rhs: Self) -> Self {
+        DualQuaternion::new(self.real * rhs.real,
+                            add(self.real * rhs.dual, self.dual * rhs.real))
+    }
+}
+
+fn zero<T: Decimal>() -> Quaternion<T> {
+    Quaternion(T::zero(), T::zero(), T::zero(), T::zero())
+}
+
+fn half<T: Decimal>() -> T {
+    T::one() / (T::one() + T::one())
+}
+
+fn add<T: Decimal>(a: Quaternion<T>, b: Quaternion<T>) -> Quaternion<T> {
+    Quaternion(a.0 + b.0, a.1 + b.1, a.2 + b.2, a.3 + b.3)
+}
+
+fn scale<T: Decimal>(q: Quaternion<T>, factor: T) -> Quaternion<T> {
+    q.map(|c| c * factor)
+}
+
+fn negate<T: Decimal>(dq: DualQuaternion<T>) -> DualQuaternion<T> {
+    let minus_one = T::zero() - T::one();
+    DualQuaternion::new(scale(dq.real, minus_one), scale(dq.dual, minus_one))
+}
+
+#[test]
+fn test_dual_quaternion_transform() {
+    let rotation = Quaternion::from_axis_angle(Vector3(1.0, 2.0, -1.0), 0.8);
+    let translation = Vector3(3.0, -1.0, 2.0);
+    let dq = DualQuaternion::from_rotation_translation(rotation, translation);
+    let point = Vector3(0.5, 1.0, -2.0);
+
+    assert_vec_close(dq.translation(), translation);
+    assert_vec_close(dq.transform_point(point), rotation.rotate(point) + translation);
+    assert_vec_close(dq.inverse().transform_point(dq.transform_point(point)), point);
+
+    // Composition applies the right hand side first.
+    let other = DualQuaternion::from_rotation_translation(
+        Quaternion::from_axis_angle(Vector3(0.0, 1.0, 0.0), 1.3), Vector3(0.0, 4.0, 1.0));
+    assert_vec_close((dq * other).transform_point(point),
+                 dq.transform_point(other.transform_point(point)));
+
+    let scaled = DualQuaternion::new(dq.real.map(|c| c * 3.0), dq.dual.map(|c| c * 3.0));
+    let normalized = scaled.normalize();
+    assert!((normalized.real.length_squared() - 1.0).abs() < 1.0e-12);
+    assert!(normalized.real.dot(normalized.dual).abs() < 1.0e-12);
+    assert_vec_close(normalized.transform_point(point), dq.transform_point(point));
+}
+
+#[test]
+fn test_dual_quaternion_sclerp() {
+    let axis = Vector3(0.0, 0.0, 1.0);
+    let start = DualQuaternion::from_translation(Vector3(1.0, 0.0, 0.0));
+    let end = DualQuaternion::from_rotation_translation(Quaternion::from_axis_angle(axis, 1.0),
+                                                        Vector3(1.0, 0.0, 4.0));
+
+    assert_vec_close(start.sclerp(&end, 0.0).transform_point(Vector3(0.0, 0.0, 0.0)),
+                     Vector3(1.0, 0.0, 0.0));
+    assert_vec_close(start.sclerp(&end, 1.0).transform_point(Vector3(2.0, 0.0, 0.0)),
+                     end.transform_point(Vector3(2.0, 0.0, 0.0)));
+
+    // A screw motion around the Z axis, through (1,0,0).
+    let middle = start.sclerp(&end, 0.5);
+    let (_, angle) = middle.rotation().to_axis_angle();
+    assert!((angle - 0.5).abs() < 1.0e-12);
+    assert_vec_close(middle.transform_point(Vector3(0.0, 0.0, 0.0)), Vector3(1.0, 0.0, 2.0));
+
+    // The same rotation with the opposite sign takes the same path.
+    assert_vec_close(start.sclerp(&negate(end), 0.5).transform_point(Vector3(0.0, 1.0, 0.0)),
+                     middle.transform_point(Vector3(0.0, 1.0, 0.0)));
+}
+
+#[test]
+fn test_dual_quaternion_blend() {
+    let a = DualQuaternion::from_translation(Vector3(2.0, 0.0, 0.0));
+    let b = DualQuaternion::from_translation(Vector3(0.0, 4.0, 0.0));
+
+    let blended = DualQuaternion::blend(vec![(a, 0.25), (b, 0.75)]);
+    assert_vec_close(blended.translation(), Vector3(0.5, 3.0, 0.0));
+
+    // Blending twisted joints keeps the rotation rigid.
+    let twist = DualQuaternion::from_rotation(Quaternion::from_axis_angle(Vector3(1.0f64, 0.0, 0.0), 3.0));
+    let blended = DualQuaternion::blend(vec![(DualQuaternion::identity(), 0.5), (twist, 0.5)]);
+    let point = Vector3(0.0, 1.0, 0.0);
+    assert!((blended.transform_point(point).length_squared() - 1.0).abs() < 1.0e-12);
+
+    let flipped = DualQuaternion::blend(vec![(twist, 0.5), (negate(twist), 0.5)]);
+    assert_vec_close(flipped.transform_point(point), twist.transform_point(point));
+
+    let cancelled = DualQuaternion::blend(vec![(twist, 1.0), (twist, -1.0)]);
+    assert_eq!(cancelled, DualQuaternion::identity());
+}
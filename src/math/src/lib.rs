@@ -1,23 +1,34 @@
 
 extern crate num;
 
+#[macro_use]
 pub mod vector;
 pub mod vector2;
 pub mod vector3;
+pub mod vector4;
+pub mod vectorn;
 pub mod matrix;
 pub mod matrix3;
 pub mod matrix4;
+pub mod matrixmn;
 pub mod quaternion;
+pub mod dual_quaternion;
+pub mod simd;
+pub mod spline;
 
 pub mod util;
 
 pub use self::vector::Vector;
 pub use self::vector2::Vector2;
 pub use self::vector3::Vector3;
+pub use self::vector4::Vector4;
+pub use self::vectorn::VectorN;
 pub use self::matrix::Matrix;
 pub use self::matrix3::Matrix3;
 pub use self::matrix4::Matrix4;
+pub use self::matrixmn::{MatrixMN,Matrix2};
 pub use self::quaternion::Quaternion;
+pub use self::dual_quaternion::DualQuaternion;
 
 /// A scalar.
 pub type Scalar = f32;
@@ -1,6 +1,6 @@
 
 use util;
-use num::Num;
+use num::{self,Num,Decimal};
 
 /// A matrix.
 /// Note that matrices are stored in row-major form.
@@ -29,6 +29,51 @@ pub trait Matrix<T: Num> : Sized + Copy + Clone
 
     fn as_slice<'a>(&'a self) -> &'a [T];
     fn as_slice_mut<'a>(&'a mut self) -> &'a mut [T];
+
+    /// Swaps the rows and columns of the matrix.
+    fn transpose(&self) -> Self {
+        Self::from_fn(|row,col| self.get(col,row))
+    }
+
+    /// Calculates the determinant.
+    fn determinant(&self) -> T;
+
+    /// Calculates the adjugate, the transpose of the cofactor matrix.
+    ///
+    /// Multiplying a matrix by its adjugate gives the identity scaled
+    /// by the determinant.
+    fn adjugate(&self) -> Self;
+
+    /// Calculates the inverse, or `None` if the matrix is singular.
+    ///
+    /// A matrix is considered singular when its determinant is negligible
+    /// compared to the product of the lengths of its rows, which is the
+    /// largest the determinant could be.
+    fn try_inverse(&self) -> Option<Self>
+        where T: Decimal {
+        let determinant = self.determinant();
+
+        let size = (self.as_slice().len() as f64).sqrt() as usize;
+        let bound = (0..size).fold(T::one(), |acc, row| {
+            acc * self.row(row).iter().fold(T::zero(), |acc, x| acc + x*x).sqrt()
+        });
+
+        let tolerance: T = T::epsilon() * num::cast::<usize,T>(size * size);
+
+        if !(determinant.abs() > bound * tolerance) {
+            return None;
+        }
+
+        let adjugate = self.adjugate();
+        Some(Self::from_fn(|row,col| adjugate.get(row,col) / determinant))
+    }
+
+    /// Calculates the inverse.
+    /// Panics if the matrix is singular.
+    fn inverse(&self) -> Self
+        where T: Decimal {
+        self.try_inverse().expect("cannot invert a singular matrix")
+    }
 }
 
 pub mod row {
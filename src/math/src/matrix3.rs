@@ -1,12 +1,14 @@
 
-use {matrix,Matrix,Vector3};
+use {matrix,Matrix,MatrixMN,Vector3};
 use num::Num;
 use std;
 
+/// A 3x3 matrix.
+/// This wraps the general `MatrixMN`, with constructors for its exact size.
 #[derive(Copy,Clone,Debug,Eq,PartialEq)]
 pub struct Matrix3<T: Num>
 {
-    m: [T; 9],
+    m: MatrixMN<T,3,3>,
 }
 
 impl<T: Num> Matrix3<T>
@@ -15,11 +17,11 @@ impl<T: Num> Matrix3<T>
                m21: T, m22: T, m23: T,
                m31: T, m32: T, m33: T) -> Self {
         Matrix3 {
-            m: [
-                m11, m12, m13,
-                m21, m22, m23,
-                m31, m32, m33,
-            ]
+            m: MatrixMN::new([
+                [m11, m12, m13],
+                [m21, m22, m23],
+                [m31, m32, m33],
+            ])
         }
     }
 }
@@ -29,34 +31,48 @@ impl<T: Num> Matrix<T> for Matrix3<T>
     fn from_fn<F>(f: F) -> Self
         where F: Fn(usize,usize) -> T {
         Matrix3 {
-            m: [
-                f(0,0), f(0,1), f(0,2),
-                f(1,0), f(1,1), f(1,2),
-                f(2,0), f(2,1), f(2,2),
-            ]
+            m: MatrixMN::from_fn(f),
         }
     }
 
     fn get(&self, row: usize, col: usize) -> T {
-        self.m[calculate_index(row as usize, col as usize)]
+        self.m[(row,col)]
     }
 
     fn set(&mut self, row: usize, col: usize, val: T) {
         assert!(row < 3 && col < 3, "out of bounds indices");
 
-        self.m[calculate_index(row as usize, col as usize)] = val;
+        self.m[(row,col)] = val;
     }
 
     fn row<'a>(&'a self, num: usize) -> matrix::row::Row<'a,T> {
-        matrix::row::Row::new(&self.m, num, 3)
+        matrix::row::Row::new(self.m.as_slice(), num, 3)
     }
 
     fn col<'a>(&'a self, num: usize) -> matrix::column::Column<'a,T> {
-        matrix::column::Column::new(&self.m, num, 3, 3)
+        matrix::column::Column::new(self.m.as_slice(), num, 3, 3)
     }
 
-    fn as_slice<'a>(&'a self)-> &'a [T] { &self.m }
-    fn as_slice_mut<'a>(&'a mut self) -> &'a mut [T] { &mut self.m }
+    fn as_slice<'a>(&'a self)-> &'a [T] { self.m.as_slice() }
+    fn as_slice_mut<'a>(&'a mut self) -> &'a mut [T] { self.m.as_slice_mut() }
+
+    fn determinant(&self) -> T {
+        let ((a00,a01,a02),
+             (a10,a11,a12),
+             (a20,a21,a22)) = (*self).into();
+
+        a00*(a11*a22 - a12*a21) + a01*(a12*a20 - a10*a22) + a02*(a10*a21 - a11*a20)
+    }
+
+    fn adjugate(&self) -> Self {
+        let ((a00,a01,a02),
+             (a10,a11,a12),
+             (a20,a21,a22)) = (*self).into();
+
+        Matrix3::new(a11*a22 - a12*a21, a02*a21 - a01*a22, a01*a12 - a02*a11,
+                     a12*a20 - a10*a22, a00*a22 - a02*a20, a02*a10 - a00*a12,
+                     a10*a21 - a11*a20, a01*a20 - a00*a21, a00*a11 - a01*a10)
+    }
 }
 
 impl<T: Num> std::ops::Mul for Matrix3<T>
@@ -64,28 +80,25 @@ impl<T: Num> std::ops::Mul for Matrix3<T>
     type Output = Self;
 
     fn mul(self, rhs: Matrix3<T>) -> Matrix3<T> {
-        let ((m11,m12,m13),
-             (m21,m22,m23),
-             (m31,m32,m33)) = self.into();
-        let ((n11,n12,n13),
-             (n21,n22,n23),
-             (n31,n32,n33)) = rhs.into();
-
-        let mn11 = m11*n11 + m12*n21 + m13*n31;
-        let mn12 = m11*n12 + m12*n22 + m13*n32;
-        let mn13 = m11*n13 + m12*n23 + m13*n33;
-
-        let mn21 = m21*n11 + m22*n21 + m23*n31;
-        let mn22 = m21*n12 + m22*n22 + m23*n32;
-        let mn23 = m21*n13 + m22*n23 + m23*n33;
+        Matrix3 {
+            m: self.m * rhs.m,
+        }
+    }
+}
 
-        let mn31 = m31*n11 + m32*n21 + m33*n31;
-        let mn32 = m31*n12 + m32*n22 + m33*n32;
-        let mn33 = m31*n13 + m32*n23 + m33*n33;
+impl<T: Num> From<MatrixMN<T,3,3>> for Matrix3<T>
+{
+    fn from(m: MatrixMN<T,3,3>) -> Matrix3<T> {
+        Matrix3 {
+            m: m,
+        }
+    }
+}
 
-        Matrix3::new(mn11, mn12, mn13,
-                     mn21, mn22, mn23,
-                     mn31, mn32, mn33)
+impl<T: Num> From<Matrix3<T>> for MatrixMN<T,3,3>
+{
+    fn from(matrix: Matrix3<T>) -> MatrixMN<T,3,3> {
+        matrix.m
     }
 }
 
@@ -109,14 +122,14 @@ impl<T: Num> std::ops::Index<(usize,usize)> for Matrix3<T>
     type Output = T;
 
     fn index<'a>(&'a self, (row,col): (usize,usize)) -> &'a T {
-        &self.m[calculate_index(row,col)]
+        &self.m[(row,col)]
     }
 }
 
 impl<T: Num> std::ops::IndexMut<(usize,usize)> for Matrix3<T>
 {
     fn index_mut<'a>(&'a mut self, (row,col): (usize,usize)) -> &'a mut T {
-        &mut self.m[calculate_index(row,col)]
+        &mut self.m[(row,col)]
     }
 }
 
@@ -129,10 +142,6 @@ impl<T: Num> Into<((T,T,T),(T,T,T),(T,T,T))> for Matrix3<T>
     }
 }
 
-fn calculate_index(row: usize, col: usize) -> usize {
-    row*3 + col
-}
-
 #[test]
 fn test_mat3_mul() {
     let identity = Matrix3::identity();
@@ -153,3 +162,38 @@ fn test_mat3_mul() {
         138., 114., 90.
     ));
 }
+
+#[test]
+fn test_mat3_transpose() {
+    let mat = Matrix3::new(1,2,3,
+                           4,5,6,
+                           7,8,9);
+
+    assert_eq!(mat.transpose(), Matrix3::new(1,4,7,
+                                             2,5,8,
+                                             3,6,9));
+    assert_eq!(mat.transpose().transpose(), mat);
+}
+
+#[test]
+fn test_mat3_inverse() {
+    let mat = Matrix3::new(2.,0.,1.,
+                           1.,3.,2.,
+                           1.,1.,2.);
+
+    assert_eq!(mat.determinant(), 6.);
+    assert_eq!(mat.adjugate(), Matrix3::new( 4.,  1., -3.,
+                                             0.,  3., -3.,
+                                            -2., -2.,  6.));
+
+    let product = mat * mat.inverse();
+    let identity: Matrix3<f64> = Matrix3::identity();
+    for (a,b) in product.as_slice().iter().zip(identity.as_slice()) {
+        assert!((a - b).abs() < 1.0e-12);
+    }
+
+    let singular = Matrix3::new(1.,2.,3.,
+                                4.,5.,6.,
+                                7.,8.,9.);
+    assert_eq!(singular.try_inverse(), None);
+}
@@ -1,12 +1,14 @@
 
-use {matrix,Matrix,Vector3};
+use {matrix,Matrix,MatrixMN};
 use num::Num;
 use std;
 
+/// A 4x4 matrix.
+/// This wraps the general `MatrixMN`, with constructors for its exact size.
 #[derive(Copy,Clone,Debug,Eq,PartialEq)]
 pub struct Matrix4<T: Num>
 {
-    m: [T; 16],
+    m: MatrixMN<T,4,4>,
 }
 
 impl<T: Num> Matrix4<T>
@@ -16,12 +18,12 @@ impl<T: Num> Matrix4<T>
                m31: T, m32: T, m33: T, m34: T,
                m41: T, m42: T, m43: T, m44: T) -> Self {
         Matrix4 {
-            m: [
-                m11, m12, m13, m14,
-                m21, m22, m23, m24,
-                m31, m32, m33, m34,
-                m41, m42, m43, m44,
-            ]
+            m: MatrixMN::new([
+                [m11, m12, m13, m14],
+                [m21, m22, m23, m24],
+                [m31, m32, m33, m34],
+                [m41, m42, m43, m44],
+            ])
         }
     }
 }
@@ -31,35 +33,80 @@ impl<T: Num> Matrix<T> for Matrix4<T>
     fn from_fn<F>(f: F) -> Self
         where F: Fn(usize,usize) -> T {
         Matrix4 {
-            m: [
-                f(0,0), f(0,1), f(0,2), f(0,3),
-                f(1,0), f(1,1), f(1,2), f(1,3),
-                f(2,0), f(2,1), f(2,2), f(2,3),
-                f(3,0), f(3,1), f(3,2), f(3,3),
-            ]
+            m: MatrixMN::from_fn(f),
         }
     }
 
     fn get(&self, row: usize, col: usize) -> T {
-        self.m[calculate_index(row as usize, col as usize)]
+        self.m[(row,col)]
     }
 
     fn set(&mut self, row: usize, col: usize, val: T) {
         assert!(row < 4 && col < 4, "out of bounds indices");
 
-        self.m[calculate_index(row as usize, col as usize)] = val;
+        self.m[(row,col)] = val;
     }
 
     fn row<'a>(&'a self, num: usize) -> matrix::row::Row<'a,T> {
-        matrix::row::Row::new(&self.m, num, 4)
+        matrix::row::Row::new(self.m.as_slice(), num, 4)
     }
 
     fn col<'a>(&'a self, num: usize) -> matrix::column::Column<'a,T> {
-        matrix::column::Column::new(&self.m, num, 4, 4)
+        matrix::column::Column::new(self.m.as_slice(), num, 4, 4)
     }
 
-    fn as_slice<'a>(&'a self)-> &'a [T] { &self.m }
-    fn as_slice_mut<'a>(&'a mut self) -> &'a mut [T] { &mut self.m }
+    fn as_slice<'a>(&'a self)-> &'a [T] { self.m.as_slice() }
+    fn as_slice_mut<'a>(&'a mut self) -> &'a mut [T] { self.m.as_slice_mut() }
+
+    fn determinant(&self) -> T {
+        let (s,c) = self.minors();
+
+        s[0]*c[5] - s[1]*c[4] + s[2]*c[3] + s[3]*c[2] - s[4]*c[1] + s[5]*c[0]
+    }
+
+    fn adjugate(&self) -> Self {
+        let ((a00,a01,a02,a03),
+             (a10,a11,a12,a13),
+             (a20,a21,a22,a23),
+             (a30,a31,a32,a33)) = (*self).into();
+        let (s,c) = self.minors();
+
+        Matrix4::new(
+            a11*c[5] - a12*c[4] + a13*c[3],
+            a02*c[4] - a01*c[5] - a03*c[3],
+            a31*s[5] - a32*s[4] + a33*s[3],
+            a22*s[4] - a21*s[5] - a23*s[3],
+
+            a12*c[2] - a10*c[5] - a13*c[1],
+            a00*c[5] - a02*c[2] + a03*c[1],
+            a32*s[2] - a30*s[5] - a33*s[1],
+            a20*s[5] - a22*s[2] + a23*s[1],
+
+            a10*c[4] - a11*c[2] + a13*c[0],
+            a01*c[2] - a00*c[4] - a03*c[0],
+            a30*s[4] - a31*s[2] + a33*s[0],
+            a21*s[2] - a20*s[4] - a23*s[0],
+
+            a11*c[1] - a10*c[3] - a12*c[0],
+            a00*c[3] - a01*c[1] + a02*c[0],
+            a31*s[1] - a30*s[3] - a32*s[0],
+            a20*s[3] - a21*s[1] + a22*s[0],
+        )
+    }
+}
+
+impl<T: Num> Matrix4<T>
+{
+    /// Calculates the 2x2 minors of the top two rows and of the
+    /// bottom two rows, which the determinant and adjugate are built from.
+    fn minors(&self) -> ([T; 6], [T; 6]) {
+        let m = |row: usize, a: usize, b: usize| {
+            self[(row,a)]*self[(row+1,b)] - self[(row+1,a)]*self[(row,b)]
+        };
+
+        ([m(0,0,1), m(0,0,2), m(0,0,3), m(0,1,2), m(0,1,3), m(0,2,3)],
+         [m(2,0,1), m(2,0,2), m(2,0,3), m(2,1,2), m(2,1,3), m(2,2,3)])
+    }
 }
 
 impl<T: Num> std::ops::Mul for Matrix4<T>
@@ -67,63 +114,25 @@ impl<T: Num> std::ops::Mul for Matrix4<T>
     type Output = Self;
 
     fn mul(self, rhs: Matrix4<T>) -> Matrix4<T> {
-        let ((m11,m12,m13,m14),
-             (m21,m22,m23,m24),
-             (m31,m32,m33,m34),
-             (m41,m42,m43,m44)) = self.into();
-        let ((n11,n12,n13,n14),
-             (n21,n22,n23,n24),
-             (n31,n32,n33,n34),
-             (n41,n42,n43,n44)) = rhs.into();
-
-        let mn11 = m11*n11 + m12*n21 + m13*n31 + m14*n41;
-        let mn12 = m11*n12 + m12*n22 + m13*n32 + m14*n42;
-        let mn13 = m11*n13 + m12*n23 + m13*n33 + m14*n43;
-        let mn14 = m11*n14 + m12*n24 + m13*n34 + m14*n44;
-
-        let mn21 = m21*n11 + m22*n21 + m23*n31 + m24*n41;
-        let mn22 = m21*n12 + m22*n22 + m23*n32 + m24*n42;
-        let mn23 = m21*n13 + m22*n23 + m23*n33 + m24*n43;
-        let mn24 = m21*n14 + m22*n24 + m23*n34 + m24*n44;
-
-        let mn31 = m31*n11 + m32*n21 + m33*n31 + m34*n41;
-        let mn32 = m31*n12 + m32*n22 + m33*n32 + m34*n42;
-        let mn33 = m31*n13 + m32*n23 + m33*n33 + m34*n43;
-        let mn34 = m31*n14 + m32*n24 + m33*n34 + m34*n44;
-
-        let mn41 = m41*n11 + m42*n21 + m43*n31 + m44*n41;
-        let mn42 = m41*n12 + m42*n22 + m43*n32 + m44*n42;
-        let mn43 = m41*n13 + m42*n23 + m43*n33 + m44*n43;
-        let mn44 = m41*n14 + m42*n24 + m43*n34 + m44*n44;
-
-        Matrix4::new(mn11, mn12, mn13, mn14,
-                     mn21, mn22, mn23, mn24,
-                     mn31, mn32, mn33, mn34,
-                     mn41, mn42, mn43, mn44)
+        Matrix4 {
+            m: self.m * rhs.m,
+        }
     }
 }
 
-impl<T: Num> std::ops::Mul<Matrix4<T>> for Vector3<T>
+impl<T: Num> From<MatrixMN<T,4,4>> for Matrix4<T>
 {
-    type Output = Vector3<T>;
-
-    fn mul(self, m: Matrix4<T>) -> Vector3<T> {
-        let (x,y,z) = self.into();
-        let w = T::one();
-
-        let mut xdash = x*m[(0,0)] + y*m[(1,0)] + z*m[(2,0)] + w*m[(3,0)];
-        let mut ydash = x*m[(0,1)] + y*m[(1,1)] + z*m[(2,1)] + w*m[(3,1)];
-        let mut zdash = x*m[(0,2)] + y*m[(1,2)] + z*m[(2,2)] + w*m[(3,2)];
-        let wdash = x*m[(0,3)] + y*m[(1,3)] + z*m[(2,3)] + w*m[(3,3)];
-
-        // Normalise homogenous coordinates
-        if !wdash.is_zero() {
-            xdash = xdash / wdash;
-            ydash = ydash / wdash;
-            zdash = zdash / wdash;
+    fn from(m: MatrixMN<T,4,4>) -> Matrix4<T> {
+        Matrix4 {
+            m: m,
         }
+    }
+}
 
-        Vector3(xdash, ydash, zdash)
+impl<T: Num> From<Matrix4<T>> for MatrixMN<T,4,4>
+{
+    fn from(matrix: Matrix4<T>) -> MatrixMN<T,4,4> {
+        matrix.m
     }
 }
 
@@ -132,14 +141,14 @@ impl<T: Num> std::ops::Index<(usize,usize)> for Matrix4<T>
     type Output = T;
 
     fn index<'a>(&'a self, (row,col): (usize,usize)) -> &'a T {
-        &self.m[calculate_index(row,col)]
+        &self.m[(row,col)]
     }
 }
 
 impl<T: Num> std::ops::IndexMut<(usize,usize)> for Matrix4<T>
 {
     fn index_mut<'a>(&'a mut self, (row,col): (usize,usize)) -> &'a mut T {
-        &mut self.m[calculate_index(row,col)]
+        &mut self.m[(row,col)]
     }
 }
 
@@ -153,10 +162,6 @@ impl<T: Num> Into<((T,T,T,T),(T,T,T,T),(T,T,T,T),(T,T,T,T))> for Matrix4<T>
     }
 }
 
-fn calculate_index(row: usize, col: usize) -> usize {
-    row*4 + col
-}
-
 #[test]
 fn test_mat4_mul() {
     let identity = Matrix4::identity();
@@ -181,3 +186,43 @@ fn test_mat4_mul() {
     ));
 }
 
+
+#[test]
+fn test_mat4_transpose() {
+    let mat = Matrix4::new( 1, 2, 3, 4,
+                            5, 6, 7, 8,
+                            9,10,11,12,
+                           13,14,15,16);
+
+    assert_eq!(mat.transpose(), Matrix4::new(1,5, 9,13,
+                                             2,6,10,14,
+                                             3,7,11,15,
+                                             4,8,12,16));
+}
+
+#[test]
+fn test_mat4_inverse() {
+    let mat = Matrix4::new(1.,1.,1.,0.,
+                           0.,3.,1.,2.,
+                           2.,3.,1.,0.,
+                           1.,0.,2.,1.);
+
+    assert_eq!(mat.determinant(), -4.);
+    assert_eq!(mat.adjugate(), Matrix4::new( 12.,  2., -6., -4.,
+                                             -4., -1.,  1.,  2.,
+                                            -12., -1.,  5.,  2.,
+                                             12.,  0., -4., -4.));
+
+    let product = mat * mat.inverse();
+    let identity: Matrix4<f64> = Matrix4::identity();
+    for (a,b) in product.as_slice().iter().zip(identity.as_slice()) {
+        assert!((a - b).abs() < 1.0e-12);
+    }
+
+    let singular = Matrix4::new(1.,2.,3.,4.,
+                                2.,4.,6.,8.,
+                                0.,1.,0.,1.,
+                                5.,1.,2.,3.);
+    assert_eq!(singular.determinant(), 0.);
+    assert!(singular.try_inverse().is_none());
+}
@@ -0,0 +1,248 @@
+use {matrix,Matrix,VectorN};
+use num::Num;
+use std;
+
+/// A matrix with `M` rows and `N` columns.
+/// Note that matrices are stored in row-major form.
+#[derive(Copy,Clone,Debug,Eq,PartialEq)]
+pub struct MatrixMN<T: Num, const M: usize, const N: usize>
+{
+    rows: [[T; N]; M],
+}
+
+/// A 2x2 matrix.
+pub type Matrix2<T> = MatrixMN<T,2,2>;
+
+impl<T: Num, const M: usize, const N: usize> MatrixMN<T,M,N>
+{
+    /// Creates a matrix from its rows.
+    pub fn new(rows: [[T; N]; M]) -> Self {
+        MatrixMN {
+            rows: rows,
+        }
+    }
+
+    /// Creates a matrix from a function taking row and column numbers.
+    pub fn from_fn<F>(f: F) -> Self
+        where F: Fn(usize,usize) -> T {
+        MatrixMN {
+            rows: std::array::from_fn(|row| std::array::from_fn(|col| f(row,col))),
+        }
+    }
+
+    /// Gets the number of rows.
+    pub fn row_count(&self) -> usize { M }
+
+    /// Gets the number of columns.
+    pub fn column_count(&self) -> usize { N }
+
+    /// Gets an element, or `None` if the indices are out of range.
+    pub fn get(&self, row: usize, col: usize) -> Option<T> {
+        self.rows.get(row).and_then(|r| r.get(col)).cloned()
+    }
+
+    /// Gets a row as a vector.
+    /// Panics on out of range.
+    pub fn row(&self, num: usize) -> VectorN<T,N> {
+        VectorN(self.rows[num])
+    }
+
+    /// Gets a column as a vector.
+    /// Panics on out of range.
+    pub fn column(&self, num: usize) -> VectorN<T,M> {
+        assert!(num < N, "out of bounds column number");
+        VectorN::from_fn(|row| self.rows[row][num])
+    }
+
+    /// Iterates over the rows.
+    pub fn rows<'a>(&'a self) -> std::slice::Iter<'a, [T; N]> {
+        self.rows.iter()
+    }
+
+    /// Iterates over all of the elements, one row at a time.
+    pub fn iter<'a>(&'a self) -> std::slice::Iter<'a, T> {
+        self.as_slice().iter()
+    }
+
+    pub fn as_slice<'a>(&'a self) -> &'a [T] { self.rows.as_flattened() }
+    pub fn as_slice_mut<'a>(&'a mut self) -> &'a mut [T] { self.rows.as_flattened_mut() }
+
+    /// Swaps the rows and columns of the matrix.
+    pub fn transpose(&self) -> MatrixMN<T,N,M> {
+        MatrixMN::from_fn(|row,col| self.rows[col][row])
+    }
+}
+
+impl<T: Num, const N: usize> MatrixMN<T,N,N>
+{
+    /// Gets the identity matrix.
+    pub fn identity() -> Self {
+        MatrixMN::from_fn(|row,col| if row == col { T::one() } else { T::zero() })
+    }
+}
+
+impl<T: Num> Matrix<T> for Matrix2<T>
+{
+    fn from_fn<F>(f: F) -> Self
+        where F: Fn(usize,usize) -> T {
+        MatrixMN::from_fn(f)
+    }
+
+    fn get(&self, row: usize, col: usize) -> T {
+        self[(row,col)]
+    }
+
+    fn set(&mut self, row: usize, col: usize, val: T) {
+        self[(row,col)] = val;
+    }
+
+    fn row<'a>(&'a self, num: usize) -> matrix::row::Row<'a,T> {
+        matrix::row::Row::new(self.as_slice(), num, 2)
+    }
+
+    fn col<'a>(&'a self, num: usize) -> matrix::column::Column<'a,T> {
+        matrix::column::Column::new(self.as_slice(), num, 2, 2)
+    }
+
+    fn as_slice<'a>(&'a self)-> &'a [T] { MatrixMN::as_slice(self) }
+    fn as_slice_mut<'a>(&'a mut self) -> &'a mut [T] { MatrixMN::as_slice_mut(self) }
+
+    fn transpose(&self) -> Self {
+        MatrixMN::transpose(self)
+    }
+
+    fn determinant(&self) -> T {
+        let [[a,b],[c,d]] = self.rows;
+        a*d - b*c
+    }
+
+    fn adjugate(&self) -> Self {
+        let [[a,b],[c,d]] = self.rows;
+        let zero = T::zero();
+
+        MatrixMN::new([[d, zero - b],
+                       [zero - c, a]])
+    }
+}
+
+impl<T: Num, const M: usize, const N: usize> std::ops::Index<(usize,usize)> for MatrixMN<T,M,N>
+{
+    type Output = T;
+
+    fn index<'a>(&'a self, (row,col): (usize,usize)) -> &'a T {
+        &self.rows[row][col]
+    }
+}
+
+impl<T: Num, const M: usize, const N: usize> std::ops::IndexMut<(usize,usize)> for MatrixMN<T,M,N>
+{
+    fn index_mut<'a>(&'a mut self, (row,col): (usize,usize)) -> &'a mut T {
+        &mut self.rows[row][col]
+    }
+}
+
+impl<T: Num, const M: usize, const N: usize> std::ops::Add for MatrixMN<T,M,N>
+{
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        MatrixMN::from_fn(|row,col| self.rows[row][col] + rhs.rows[row][col])
+    }
+}
+
+impl<T: Num, const M: usize, const N: usize> std::ops::Sub for MatrixMN<T,M,N>
+{
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        MatrixMN::from_fn(|row,col| self.rows[row][col] - rhs.rows[row][col])
+    }
+}
+
+impl<T: Num, const M: usize, const N: usize> std::ops::Mul<T> for MatrixMN<T,M,N>
+{
+    type Output = Self;
+
+    fn mul(self, rhs: T) -> Self {
+        MatrixMN::from_fn(|row,col| self.rows[row][col] * rhs)
+    }
+}
+
+impl<T: Num, const M: usize, const N: usize, const P: usize> std::ops::Mul<MatrixMN<T,N,P>> for MatrixMN<T,M,N>
+{
+    type Output = MatrixMN<T,M,P>;
+
+    fn mul(self, rhs: MatrixMN<T,N,P>) -> MatrixMN<T,M,P> {
+        MatrixMN::from_fn(|row,col| {
+            (0..N).fold(T::zero(), |acc, i| acc + self.rows[row][i] * rhs.rows[i][col])
+        })
+    }
+}
+
+/// Multiplies a column vector by a matrix.
+impl<T: Num, const M: usize, const N: usize> std::ops::Mul<VectorN<T,N>> for MatrixMN<T,M,N>
+{
+    type Output = VectorN<T,M>;
+
+    fn mul(self, vec: VectorN<T,N>) -> VectorN<T,M> {
+        VectorN::from_fn(|row| self.row(row).dot(vec))
+    }
+}
+
+/// Multiplies a row vector by a matrix.
+impl<T: Num, const M: usize, const N: usize> std::ops::Mul<MatrixMN<T,M,N>> for VectorN<T,M>
+{
+    type Output = VectorN<T,N>;
+
+    fn mul(self, m: MatrixMN<T,M,N>) -> VectorN<T,N> {
+        VectorN::from_fn(|col| m.column(col).dot(self))
+    }
+}
+
+#[test]
+fn test_matmn_indexing() {
+    let mut mat = MatrixMN::new([[1, 2, 3],
+                                 [4, 5, 6]]);
+
+    assert_eq!((mat.row_count(), mat.column_count()), (2, 3));
+    assert_eq!(mat[(1,2)], 6);
+    assert_eq!(mat.get(1,2), Some(6));
+    assert_eq!(mat.get(2,0), None);
+    assert_eq!(mat.get(0,3), None);
+
+    mat[(0,0)] = 7;
+    assert_eq!(mat.row(0), VectorN([7, 2, 3]));
+    assert_eq!(mat.column(1), VectorN([2, 5]));
+    assert_eq!(mat.iter().cloned().collect::<Vec<_>>(), vec![7, 2, 3, 4, 5, 6]);
+    assert_eq!(mat.transpose(), MatrixMN::new([[7, 4],
+                                               [2, 5],
+                                               [3, 6]]));
+}
+
+#[test]
+fn test_matmn_mul() {
+    let a = MatrixMN::new([[1, 2, 3],
+                           [4, 5, 6]]);
+    let b = MatrixMN::new([[7, 8],
+                           [9, 10],
+                           [11, 12]]);
+
+    assert_eq!(a * b, MatrixMN::new([[58, 64],
+                                     [139, 154]]));
+    assert_eq!(a * MatrixMN::identity(), a);
+    assert_eq!(a * VectorN([1, 0, -1]), VectorN([-2, -2]));
+    assert_eq!(VectorN([1, -1]) * a, VectorN([-3, -3, -3]));
+}
+
+#[test]
+fn test_mat2() {
+    let mat: Matrix2<f64> = MatrixMN::new([[4.0, 7.0],
+                                           [2.0, 4.0]]);
+
+    assert_eq!(mat.determinant(), 2.0);
+    assert_eq!(mat.inverse(), MatrixMN::new([[2.0, -3.5],
+                                             [-1.0, 2.0]]));
+    assert_eq!(mat * mat.inverse(), Matrix2::identity());
+    assert_eq!(Matrix::transpose(&mat), mat.transpose());
+    assert!(MatrixMN::new([[1.0, 2.0], [2.0, 4.0]]).try_inverse().is_none());
+}
@@ -79,6 +79,172 @@ impl<T: Decimal> Quaternion<T>
         self.map(|a| a*inverse_len)
     }
 
+    /// Creates a rotation of `angle` radians around an axis.
+    /// The axis does not need to be normalized.
+    pub fn from_axis_angle(axis: Vector3<T>, angle: T) -> Self {
+        let half = angle / (T::one() + T::one());
+        let (sin,cos) = half.sincos();
+        let Vector3(x,y,z) = axis * (axis.length_squared().rsqrt() * sin);
+
+        Quaternion(x,y,z,cos)
+    }
+
+    /// Creates a quaternion from a rotation matrix.
+    ///
+    /// This is the inverse of `as_rotation_matrix`. The matrix must
+    /// be orthonormal.
+    pub fn from_rotation_matrix(m: Matrix3<T>) -> Self {
+        let one = T::one();
+        let two = one + one;
+        let four = two + two;
+        let trace = m[(0,0)] + m[(1,1)] + m[(2,2)];
+
+        // Divide by the largest of the components, to avoid
+        // dividing by a value close to zero.
+        let quat = if trace > T::zero() {
+            let s = (trace + one).sqrt() * two;
+            Quaternion((m[(2,1)] - m[(1,2)]) / s,
+                       (m[(0,2)] - m[(2,0)]) / s,
+                       (m[(1,0)] - m[(0,1)]) / s,
+                       s / four)
+        } else if m[(0,0)] > m[(1,1)] && m[(0,0)] > m[(2,2)] {
+            let s = (one + m[(0,0)] - m[(1,1)] - m[(2,2)]).sqrt() * two;
+            Quaternion(s / four,
+                       (m[(0,1)] + m[(1,0)]) / s,
+                       (m[(0,2)] + m[(2,0)]) / s,
+                       (m[(2,1)] - m[(1,2)]) / s)
+        } else if m[(1,1)] > m[(2,2)] {
+            let s = (one + m[(1,1)] - m[(0,0)] - m[(2,2)]).sqrt() * two;
+            Quaternion((m[(0,1)] + m[(1,0)]) / s,
+                       s / four,
+                       (m[(1,2)] + m[(2,1)]) / s,
+                       (m[(0,2)] - m[(2,0)]) / s)
+        } else {
+            let s = (one + m[(2,2)] - m[(0,0)] - m[(1,1)]).sqrt() * two;
+            Quaternion((m[(0,2)] + m[(2,0)]) / s,
+                       (m[(1,2)] + m[(2,1)]) / s,
+                       s / four,
+                       (m[(1,0)] - m[(0,1)]) / s)
+        };
+
+        quat.normalize()
+    }
+
+    /// Creates a rotation which points the negative Z axis along `forward`,
+    /// and the positive Y axis as close to `up` as possible.
+    ///
+    /// This matches the orientation of a camera looking along `forward`.
+    pub fn look_rotation(forward: Vector3<T>, up: Vector3<T>) -> Self {
+        let normalize = |v: Vector3<T>| v * v.length_squared().rsqrt();
+
+        let forward = normalize(forward);
+        let side = normalize(forward.cross(up));
+        let up = side.cross(forward);
+        let zero = T::zero();
+
+        // The columns are the rotated axes.
+        Quaternion::from_rotation_matrix(
+            Matrix3::new(side.x(), up.x(), zero - forward.x(),
+                         side.y(), up.y(), zero - forward.y(),
+                         side.z(), up.z(), zero - forward.z())
+        )
+    }
+
+    /// Gets the axis and the angle in radians of the rotation.
+    /// The quaternion must be normalized.
+    pub fn to_axis_angle(self) -> (Vector3<T>, T) {
+        let Quaternion(x,y,z,w) = self;
+        let sin_squared = T::one() - w*w;
+
+        // There is no unique axis for the identity rotation.
+        if sin_squared <= T::zero() {
+            return (Vector3(T::one(), T::zero(), T::zero()), T::zero());
+        }
+
+        let axis = Vector3(x,y,z) * sin_squared.rsqrt();
+        (axis, w.acos() * (T::one() + T::one()))
+    }
+
+    /// Negates the vector part, which reverses a rotation.
+    pub fn conjugate(self) -> Self {
+        let Quaternion(x,y,z,w) = self;
+        let zero = T::zero();
+
+        Quaternion(zero - x, zero - y, zero - z, w)
+    }
+
+    /// Gets the multiplicative inverse.
+    /// For normalized quaternions, this is the same as the conjugate.
+    pub fn inverse(self) -> Self {
+        let inverse_len_squared = T::one() / self.length_squared();
+        self.conjugate().map(|a| a*inverse_len_squared)
+    }
+
+    /// Calculates the dot product.
+    pub fn dot(self, other: Self) -> T {
+        let Quaternion(x1,y1,z1,w1) = self;
+        let Quaternion(x2,y2,z2,w2) = other;
+
+        x1*x2 + y1*y2 + z1*z2 + w1*w2
+    }
+
+    /// Rotates a vector.
+    /// The quaternion must be normalized.
+    pub fn rotate(self, vec: Vector3<T>) -> Vector3<T> {
+        let Quaternion(x,y,z,w) = self;
+        let axis = Vector3(x,y,z);
+
+        // An expansion of `q * v * q'`.
+        let t = axis.cross(vec) * (T::one() + T::one());
+        vec + t * w + axis.cross(t)
+    }
+
+    /// Linearly interpolates between two rotations, and normalizes the result.
+    ///
+    /// This takes the shortest path, but does not rotate at a constant speed.
+    pub fn nlerp(self, other: Self, t: T) -> Self {
+        let other = self.nearest(other);
+        self.blend(other, T::one() - t, t).normalize()
+    }
+
+    /// Spherically interpolates between two rotations.
+    ///
+    /// This takes the shortest path, at a constant angular speed.
+    pub fn slerp(self, other: Self, t: T) -> Self {
+        let other = self.nearest(other);
+        let cos = self.dot(other);
+
+        // Very close rotations would divide by almost zero.
+        if cos > T::one() - T::constant(1.0e-6) {
+            return self.nlerp(other, t);
+        }
+
+        let angle = cos.acos();
+        let sin_inverse = T::one() / angle.sin();
+        let a = ((T::one() - t) * angle).sin() * sin_inverse;
+        let b = (t * angle).sin() * sin_inverse;
+
+        self.blend(other, a, b).normalize()
+    }
+
+    /// Gets whichever of `other` and its negation is closest to `self`.
+    /// Both represent the same rotation.
+    fn nearest(self, other: Self) -> Self {
+        if self.dot(other) < T::zero() {
+            other.map(|a| T::zero() - a)
+        } else {
+            other
+        }
+    }
+
+    /// Calculates `self*a + other*b` component-wise.
+    fn blend(self, other: Self, a: T, b: T) -> Self {
+        let Quaternion(x1,y1,z1,w1) = self;
+        let Quaternion(x2,y2,z2,w2) = other;
+
+        Quaternion(x1*a + x2*b, y1*a + y2*b, z1*a + z2*b, w1*a + w2*b)
+    }
+
     pub fn as_rotation_matrix(self) -> Matrix3<T> {
         let Quaternion(x,y,z,w) = self;
         let Quaternion(x2,y2,z2,_) = self.map(|a|a*a);
@@ -138,3 +304,83 @@ fn test_quaternion_multiplication() {
     assert_eq!(q1*q1, Quaternion(8.,16.,24.,2.));
     assert_eq!(q1*q2, Quaternion(12.,24.,6.,-12.));
 }
+
+/// Asserts that two vectors are equal to within rounding error.
+#[cfg(test)]
+pub fn assert_vec_close(a: Vector3<f64>, b: Vector3<f64>) {
+    assert!((a - b).length_squared() < 1.0e-20, "{:?} != {:?}", a.xyz(), b.xyz());
+}
+
+#[test]
+fn test_quaternion_axis_angle() {
+    let half_pi = std::f64::consts::PI / 2.0;
+    let quat = Quaternion::from_axis_angle(Vector3(0.0, 0.0, 2.0), half_pi);
+
+    assert_vec_close(quat.rotate(Vector3(1.0, 0.0, 0.0)), Vector3(0.0, 1.0, 0.0));
+    assert_vec_close(quat.inverse().rotate(Vector3(0.0, 1.0, 0.0)), Vector3(1.0, 0.0, 0.0));
+
+    let (axis, angle) = quat.to_axis_angle();
+    assert_vec_close(axis, Vector3(0.0, 0.0, 1.0));
+    assert!((angle - half_pi).abs() < 1.0e-12);
+}
+
+#[test]
+fn test_quaternion_rotation_matrix() {
+    let vec = Vector3(0.3, -1.2, 2.5);
+
+    // Exercise each branch of the matrix conversion.
+    for &(axis, angle) in [(Vector3(1.0, 2.0, 3.0), 0.5),
+                           (Vector3(1.0, 0.1, 0.0), 3.0),
+                           (Vector3(0.1, 1.0, 0.0), 3.0),
+                           (Vector3(0.0, 0.1, 1.0), 3.0)].iter() {
+        let quat = Quaternion::<f64>::from_axis_angle(axis, angle);
+        let matrix = quat.as_rotation_matrix();
+        let round_trip = Quaternion::from_rotation_matrix(matrix);
+
+        assert!((quat.dot(round_trip).abs() - 1.0).abs() < 1.0e-12);
+
+        let rotated = Vector3(matrix[(0,0)]*vec.0 + matrix[(0,1)]*vec.1 + matrix[(0,2)]*vec.2,
+                              matrix[(1,0)]*vec.0 + matrix[(1,1)]*vec.1 + matrix[(1,2)]*vec.2,
+                              matrix[(2,0)]*vec.0 + matrix[(2,1)]*vec.1 + matrix[(2,2)]*vec.2);
+        assert_vec_close(quat.rotate(vec), rotated);
+    }
+}
+
+#[test]
+fn test_quaternion_slerp() {
+    let axis = Vector3(0.0, 1.0, 0.0);
+    let a = Quaternion::<f64>::from_axis_angle(axis, 0.2);
+    let b = Quaternion::<f64>::from_axis_angle(axis, 1.4);
+
+    let (_, angle) = a.slerp(b, 0.25).to_axis_angle();
+    assert!((angle - 0.5).abs() < 1.0e-12);
+
+    assert!((a.slerp(b, 0.0).dot(a) - 1.0).abs() < 1.0e-12);
+    assert!((a.nlerp(b, 1.0).dot(b) - 1.0).abs() < 1.0e-12);
+
+    // The negated quaternion is the same rotation, so the
+    // shortest path is unchanged.
+    let negated = b.map(|c| -c);
+    assert!((a.slerp(negated, 0.25).dot(a.slerp(b, 0.25)) - 1.0).abs() < 1.0e-12);
+}
+
+#[test]
+fn test_quaternion_look_rotation() {
+    let quat = Quaternion::look_rotation(Vector3(1.0, 0.0, 0.0), Vector3(0.0, 1.0, 0.0));
+
+    assert_vec_close(quat.rotate(Vector3(0.0, 0.0, -1.0)), Vector3(1.0, 0.0, 0.0));
+    assert_vec_close(quat.rotate(Vector3(0.0, 1.0, 0.0)), Vector3(0.0, 1.0, 0.0));
+}
+
+#[test]
+fn test_quaternion_derivative() {
+    use num::Dual;
+
+    // The velocity of a point rotating about Z at unit angular speed.
+    let (angle, radius) = (0.7, Dual::constant_value(2.0));
+    let quat = Quaternion::from_axis_angle(Vector3::<Dual<f64>>(num::zero(), num::zero(), num::one()),
+                                           Dual::variable(angle));
+    let velocity = quat.rotate(Vector3(radius, num::zero(), num::zero())).map(|c| c.derivative);
+
+    assert_vec_close(velocity, Vector3(-2.0 * f64::sin(angle), 2.0 * f64::cos(angle), 0.0));
+}
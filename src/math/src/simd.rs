@@ -0,0 +1,306 @@
+//! SIMD accelerated kernels for `f32` matrices and vectors.
+//!
+//! The generic operators can't be specialised for `f32`, so the fast paths
+//! are exposed as functions here. The instruction set is picked at runtime,
+//! falling back to scalar code when neither SSE nor AVX is available. Every
+//! path gives the same results as the generic operators, up to rounding.
+
+use {Matrix,Matrix4,Vector3};
+#[cfg(test)]
+use Vector4;
+
+/// The instruction set used by a kernel.
+#[derive(Copy,Clone,Debug,PartialEq,Eq)]
+pub enum Backend
+{
+    Scalar,
+    Sse,
+    Avx,
+}
+
+impl Backend
+{
+    /// Gets the fastest instruction set supported by the processor.
+    pub fn detect() -> Self {
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        {
+            if is_x86_feature_detected!("avx") {
+                return Backend::Avx;
+            }
+            if is_x86_feature_detected!("sse") {
+                return Backend::Sse;
+            }
+        }
+
+        Backend::Scalar
+    }
+
+    /// Checks if the processor supports the instruction set.
+    pub fn is_supported(self) -> bool {
+        match self {
+            Backend::Scalar => true,
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            Backend::Sse => is_x86_feature_detected!("sse"),
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            Backend::Avx => is_x86_feature_detected!("avx"),
+            #[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+            _ => false,
+        }
+    }
+}
+
+/// Multiplies two matrices.
+/// This is equivalent to `a * b`.
+pub fn mul_matrix4(a: &Matrix4<f32>, b: &Matrix4<f32>) -> Matrix4<f32> {
+    mul_matrix4_with(Backend::detect(), a, b)
+}
+
+/// Transforms points by a matrix, writing the results into `output`.
+///
+/// Each point is treated as a column, so this is equivalent to
+/// `(matrix * Vector4::from_point(point)).homogeneous_divide()`, and
+/// gives the same results as `Transform3::transform_point`.
+/// Panics if the slices have different lengths.
+pub fn transform_points(matrix: &Matrix4<f32>,
+                        input: &[Vector3<f32>],
+                        output: &mut [Vector3<f32>]) {
+    transform_points_with(Backend::detect(), matrix, input, output)
+}
+
+/// Transforms points in place.
+pub fn transform_points_in_place(matrix: &Matrix4<f32>, points: &mut [Vector3<f32>]) {
+    let input = points.to_vec();
+    transform_points(matrix, &input, points)
+}
+
+/// Multiplies two matrices with a specific instruction set.
+/// Panics if the instruction set is not supported.
+pub fn mul_matrix4_with(backend: Backend, a: &Matrix4<f32>, b: &Matrix4<f32>) -> Matrix4<f32> {
+    assert!(backend.is_supported(), "the {:?} backend is not supported", backend);
+
+    let mut result = [0.0f32; 16];
+    let (a, b) = (a.as_slice(), b.as_slice());
+
+    match backend {
+        Backend::Scalar => scalar::mul_matrix4(a, b, &mut result),
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        Backend::Sse => unsafe { x86::mul_matrix4_sse(a, b, &mut result) },
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        Backend::Avx => unsafe { x86::mul_matrix4_avx(a, b, &mut result) },
+        #[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+        _ => unreachable!(),
+    }
+
+    Matrix4::from_fn(|row,col| result[row*4 + col])
+}
+
+/// Transforms points with a specific instruction set.
+/// Panics if the instruction set is not supported.
+pub fn transform_points_with(backend: Backend,
+                             matrix: &Matrix4<f32>,
+                             input: &[Vector3<f32>],
+                             output: &mut [Vector3<f32>]) {
+    assert!(backend.is_supported(), "the {:?} backend is not supported", backend);
+    assert_eq!(input.len(), output.len(), "the input and output must be the same length");
+
+    // The kernels take the matrix by columns, as each transformed point
+    // is a sum of the columns weighted by the point's coordinates.
+    let columns = matrix.transpose();
+    let m = columns.as_slice();
+
+    match backend {
+        Backend::Scalar => scalar::transform_points(m, input, output),
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        Backend::Sse => unsafe { x86::transform_points_sse(m, input, output) },
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        Backend::Avx => unsafe { x86::transform_points_avx(m, input, output) },
+        #[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+        _ => unreachable!(),
+    }
+}
+
+/// Divides a transformed point by its homogeneous coordinate,
+/// as `Vector4::homogeneous_divide` does.
+fn project(x: f32, y: f32, z: f32, w: f32) -> Vector3<f32> {
+    if w == 0.0 {
+        Vector3(x, y, z)
+    } else {
+        Vector3(x / w, y / w, z / w)
+    }
+}
+
+mod scalar
+{
+    use Vector3;
+
+    pub fn mul_matrix4(a: &[f32], b: &[f32], result: &mut [f32; 16]) {
+        for row in 0..4 {
+            for col in 0..4 {
+                result[row*4 + col] = (0..4).fold(0.0, |acc, i| acc + a[row*4 + i] * b[i*4 + col]);
+            }
+        }
+    }
+
+    pub fn transform_points(m: &[f32], input: &[Vector3<f32>], output: &mut [Vector3<f32>]) {
+        for (&Vector3(x,y,z), out) in input.iter().zip(output.iter_mut()) {
+            let row = |r: usize| x*m[r] + y*m[4 + r] + z*m[8 + r] + m[12 + r];
+
+            *out = super::project(row(0), row(1), row(2), row(3));
+        }
+    }
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+mod x86
+{
+    #[cfg(target_arch = "x86")]
+    use std::arch::x86::*;
+    #[cfg(target_arch = "x86_64")]
+    use std::arch::x86_64::*;
+
+    use Vector3;
+
+    // Each row of the result is a sum of the rows of `b`, weighted by
+    // the elements in the same row of `a`.
+
+    #[target_feature(enable = "sse")]
+    pub unsafe fn mul_matrix4_sse(a: &[f32], b: &[f32], result: &mut [f32; 16]) {
+        let rows = [_mm_loadu_ps(&b[0]), _mm_loadu_ps(&b[4]),
+                    _mm_loadu_ps(&b[8]), _mm_loadu_ps(&b[12])];
+
+        for row in 0..4 {
+            let mut sum = _mm_mul_ps(_mm_set1_ps(a[row*4]), rows[0]);
+            for i in 1..4 {
+                sum = _mm_add_ps(sum, _mm_mul_ps(_mm_set1_ps(a[row*4 + i]), rows[i]));
+            }
+
+            _mm_storeu_ps(&mut result[row*4], sum);
+        }
+    }
+
+    #[target_feature(enable = "avx")]
+    pub unsafe fn mul_matrix4_avx(a: &[f32], b: &[f32], result: &mut [f32; 16]) {
+        // Calculate two rows of the result at a time, with each
+        // row of `b` duplicated into both halves of a register.
+        let rows = [duplicate(&b[0]), duplicate(&b[4]),
+                    duplicate(&b[8]), duplicate(&b[12])];
+
+        for pair in 0..2 {
+            let (first, second) = (pair*8, pair*8 + 4);
+
+            let mut sum = _mm256_setzero_ps();
+            for i in 0..4 {
+                let weights = _mm256_setr_ps(a[first + i], a[first + i], a[first + i], a[first + i],
+                                             a[second + i], a[second + i], a[second + i], a[second + i]);
+                sum = _mm256_add_ps(sum, _mm256_mul_ps(weights, rows[i]));
+            }
+
+            _mm256_storeu_ps(&mut result[first], sum);
+        }
+    }
+
+    #[target_feature(enable = "sse")]
+    pub unsafe fn transform_points_sse(m: &[f32], input: &[Vector3<f32>], output: &mut [Vector3<f32>]) {
+        let columns = [_mm_loadu_ps(&m[0]), _mm_loadu_ps(&m[4]),
+                       _mm_loadu_ps(&m[8]), _mm_loadu_ps(&m[12])];
+        let mut out = [0.0f32; 4];
+
+        for (&Vector3(x,y,z), point) in input.iter().zip(output.iter_mut()) {
+            let mut sum = _mm_add_ps(_mm_mul_ps(_mm_set1_ps(x), columns[0]), columns[3]);
+            sum = _mm_add_ps(sum, _mm_mul_ps(_mm_set1_ps(y), columns[1]));
+            sum = _mm_add_ps(sum, _mm_mul_ps(_mm_set1_ps(z), columns[2]));
+
+            _mm_storeu_ps(&mut out[0], sum);
+            *point = super::project(out[0], out[1], out[2], out[3]);
+        }
+    }
+
+    #[target_feature(enable = "avx")]
+    pub unsafe fn transform_points_avx(m: &[f32], input: &[Vector3<f32>], output: &mut [Vector3<f32>]) {
+        let columns = [duplicate(&m[0]), duplicate(&m[4]),
+                       duplicate(&m[8]), duplicate(&m[12])];
+        let mut out = [0.0f32; 8];
+
+        // Transform two points at a time.
+        for (input, output) in input.chunks(2).zip(output.chunks_mut(2)) {
+            let Vector3(x1,y1,z1) = input[0];
+            let Vector3(x2,y2,z2) = if input.len() == 2 { input[1] } else { input[0] };
+
+            let mut sum = _mm256_add_ps(_mm256_mul_ps(pair(x1, x2), columns[0]), columns[3]);
+            sum = _mm256_add_ps(sum, _mm256_mul_ps(pair(y1, y2), columns[1]));
+            sum = _mm256_add_ps(sum, _mm256_mul_ps(pair(z1, z2), columns[2]));
+
+            _mm256_storeu_ps(&mut out[0], sum);
+
+            for (i, point) in output.iter_mut().enumerate() {
+                let o = &out[i*4..];
+                *point = super::project(o[0], o[1], o[2], o[3]);
+            }
+        }
+    }
+
+    /// Loads four values into both halves of a register.
+    #[target_feature(enable = "avx")]
+    unsafe fn duplicate(values: &f32) -> __m256 {
+        let half = _mm_loadu_ps(values);
+        _mm256_insertf128_ps(_mm256_castps128_ps256(half), half, 1)
+    }
+
+    /// Broadcasts one value into each half of a register.
+    #[target_feature(enable = "avx")]
+    unsafe fn pair(first: f32, second: f32) -> __m256 {
+        _mm256_setr_ps(first, first, first, first, second, second, second, second)
+    }
+}
+
+#[cfg(test)]
+fn backends() -> Vec<Backend> {
+    [Backend::Scalar, Backend::Sse, Backend::Avx].iter().cloned()
+        .filter(|b| b.is_supported()).collect()
+}
+
+#[cfg(test)]
+fn test_matrix(seed: f32) -> Matrix4<f32> {
+    Matrix4::from_fn(|row,col| ((row*4 + col) as f32 * 0.37 + seed).sin() * 3.0)
+}
+
+#[test]
+fn test_simd_mul_matrix4() {
+    let (a, b) = (test_matrix(0.1), test_matrix(2.3));
+    let expected = a * b;
+
+    for backend in backends() {
+        let result = mul_matrix4_with(backend, &a, &b);
+
+        for (x, y) in result.as_slice().iter().zip(expected.as_slice()) {
+            assert!((x - y).abs() < 1.0e-4, "{:?} differs", backend);
+        }
+    }
+
+    assert_eq!(mul_matrix4(&a, &Matrix4::identity()), a);
+}
+
+#[test]
+fn test_simd_transform_points() {
+    let matrix = test_matrix(0.7);
+    let points: Vec<_> = (0..9).map(|i| {
+        let t = i as f32;
+        Vector3(t.cos() * 4.0, t * 0.5 - 2.0, (t * 1.3).sin())
+    }).collect();
+
+    for backend in backends() {
+        let mut output = vec![Vector3(0.0, 0.0, 0.0); points.len()];
+        transform_points_with(backend, &matrix, &points, &mut output);
+
+        for (&point, &result) in points.iter().zip(output.iter()) {
+            let expected = (matrix * Vector4::from_point(point)).homogeneous_divide();
+            let difference = (result - expected).length_squared();
+
+            assert!(difference < 1.0e-6 * (1.0 + expected.length_squared()), "{:?} differs", backend);
+        }
+    }
+
+    let mut in_place = points.clone();
+    transform_points_in_place(&Matrix4::identity(), &mut in_place);
+    assert!(in_place.iter().zip(points.iter()).all(|(a,b)| a.xyz() == b.xyz()));
+}
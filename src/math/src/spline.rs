@@ -0,0 +1,717 @@
+//! Parametric curves.
+//!
+//! Every curve is defined over a range of parameters, given by its
+//! `domain`. Piecewise curves cover `0..n` for `n` segments, so each whole
+//! number is the start of a segment.
+//!
+//! Curves work over any `ControlPoint`, which includes vectors and
+//! quaternions. Quaternion curves are evaluated component-wise, so the
+//! results should be normalized before they are used as rotations.
+
+use {Vector2,Vector3,Vector4,VectorN,Quaternion};
+use num::{self,Decimal};
+use std;
+
+/// The number of pieces that arc length integration splits each
+/// segment of a curve into.
+const LENGTH_SUBDIVISIONS: usize = 16;
+/// The number of samples on each segment used to find a starting point
+/// when searching for the closest point on a curve.
+const CLOSEST_SAMPLES: usize = 64;
+/// The number of spans each segment is split into before tessellating.
+const TESSELLATION_SPANS: usize = 8;
+/// The number of times a tessellated span can be split in half.
+const MAX_TESSELLATION_DEPTH: u32 = 16;
+
+/// A point which a curve can pass through or be shaped by.
+pub trait ControlPoint<T: Decimal> : Copy
+{
+    /// Calculates `self*a + other*b`.
+    fn combine(self, other: Self, a: T, b: T) -> Self;
+
+    /// Calculates the dot product, treating the point as a vector.
+    fn dot(self, other: Self) -> T;
+}
+
+/// A parametric curve.
+pub trait Curve<T: Decimal, P: ControlPoint<T>>
+{
+    /// Gets the range of parameters that the curve is defined over.
+    fn domain(&self) -> (T,T);
+
+    /// Gets the parameters where the segments of the curve meet,
+    /// including both ends of the domain.
+    ///
+    /// Curves are only smooth within a segment, so integration and
+    /// sampling are done segment by segment.
+    fn breakpoints(&self) -> Vec<T> {
+        let (start, end) = self.domain();
+        vec![start, end]
+    }
+
+    /// Gets the point at a parameter.
+    fn evaluate(&self, t: T) -> P;
+
+    /// Gets the derivative with respect to the parameter.
+    fn derivative(&self, t: T) -> P;
+
+    /// Gets the unit tangent at a parameter.
+    fn tangent(&self, t: T) -> P {
+        let derivative = self.derivative(t);
+        let length = derivative.dot(derivative).sqrt();
+
+        if length.is_zero() {
+            derivative
+        } else {
+            derivative.combine(derivative, T::one() / length, T::zero())
+        }
+    }
+
+    /// Calculates the length of the curve between two parameters.
+    fn length_between(&self, start: T, end: T) -> T {
+        // Five point Gauss-Legendre quadrature over each piece.
+        const NODES: [(f64, f64); 5] = [
+            (0.0, 0.5688888888888889),
+            (-0.5384693101056831, 0.4786286704993665),
+            (0.5384693101056831, 0.4786286704993665),
+            (-0.9061798459386640, 0.2369268850561891),
+            (0.9061798459386640, 0.2369268850561891),
+        ];
+
+        if end < start {
+            return T::zero() - self.length_between(end, start);
+        }
+
+        let pieces: T = num::cast(LENGTH_SUBDIVISIONS);
+
+        spans(self, start, end).into_iter().fold(T::zero(), |acc, (start, end)| {
+            let half_width = (end - start) / (pieces + pieces);
+
+            (0..LENGTH_SUBDIVISIONS).fold(acc, |acc, piece| {
+                let center = start + half_width * (num::cast::<usize,T>(piece * 2 + 1));
+
+                NODES.iter().fold(acc, |acc, &(node, weight)| {
+                    let derivative = self.derivative(center + half_width * T::constant(node));
+                    acc + derivative.dot(derivative).sqrt() * T::constant(weight) * half_width
+                })
+            })
+        })
+    }
+
+    /// Calculates the length of the whole curve.
+    fn arc_length(&self) -> T {
+        let (start, end) = self.domain();
+        self.length_between(start, end)
+    }
+
+    /// Finds the parameter at a distance along the curve.
+    ///
+    /// Distances outside of the curve are clamped to its ends.
+    fn parameter_at_length(&self, distance: T) -> T {
+        let (start, end) = self.domain();
+        let total = self.length_between(start, end);
+
+        if distance <= T::zero() || total.is_zero() {
+            return start;
+        } else if distance >= total {
+            return end;
+        }
+
+        // Newton's method, falling back to bisection whenever
+        // a step would leave the bracket.
+        let (mut low, mut high) = (start, end);
+        let mut t = start + (end - start) * distance / total;
+
+        for _ in 0..32 {
+            let error = self.length_between(start, t) - distance;
+
+            if error.abs() <= total * T::constant(1.0e-6) {
+                break;
+            }
+
+            if error > T::zero() { high = t } else { low = t }
+
+            let speed = { let d = self.derivative(t); d.dot(d).sqrt() };
+            let next = if speed.is_zero() { low - T::one() } else { t - error / speed };
+
+            t = if next > low && next < high {
+                next
+            } else {
+                (low + high) / (T::one() + T::one())
+            };
+        }
+
+        t
+    }
+
+    /// Finds the parameter of the point on the curve closest to `point`.
+    fn closest_point(&self, point: P) -> T {
+        let (start, end) = self.domain();
+        let distance = |t: T| {
+            let offset = self.evaluate(t).combine(point, T::one(), T::zero() - T::one());
+            offset.dot(offset)
+        };
+
+        let mut parameters = vec![start];
+        for (start, end) in spans(self, start, end) {
+            let step = (end - start) / num::cast(CLOSEST_SAMPLES);
+            parameters.extend((1..(CLOSEST_SAMPLES + 1)).map(|i| start + step * num::cast(i)));
+        }
+
+        let nearest = (0..parameters.len()).fold(0, |best, i| {
+            if distance(parameters[i]) < distance(parameters[best]) { i } else { best }
+        });
+
+        // Refine within the neighbouring samples by golden section search.
+        let ratio = T::constant(0.6180339887498949);
+        let mut low = parameters[if nearest == 0 { 0 } else { nearest - 1 }];
+        let mut high = parameters[std::cmp::min(nearest + 1, parameters.len() - 1)];
+
+        for _ in 0..48 {
+            let a = high - (high - low) * ratio;
+            let b = low + (high - low) * ratio;
+
+            if distance(a) < distance(b) { high = b } else { low = a }
+        }
+
+        (low + high) / (T::one() + T::one())
+    }
+
+    /// Approximates the curve by a line strip.
+    ///
+    /// Spans are split in half until the curve is within `tolerance`
+    /// of every line.
+    fn tessellate(&self, tolerance: T) -> Vec<P> {
+        let (start, end) = self.domain();
+
+        let mut points = vec![self.evaluate(start)];
+        for (start, end) in spans(self, start, end) {
+            let step = (end - start) / num::cast(TESSELLATION_SPANS);
+
+            for i in 0..TESSELLATION_SPANS {
+                let (a, b) = (start + step * num::cast(i), start + step * num::cast(i + 1));
+                subdivide(self, a, b, tolerance, 0, &mut points);
+            }
+        }
+
+        points
+    }
+}
+
+/// Splits the range between two parameters at the breakpoints of a curve.
+fn spans<T,P,C>(curve: &C, start: T, end: T) -> Vec<(T,T)>
+    where T: Decimal, P: ControlPoint<T>, C: Curve<T,P> + ?Sized {
+    let mut bounds = vec![start];
+    bounds.extend(curve.breakpoints().into_iter().filter(|&b| b > start && b < end));
+    bounds.push(end);
+
+    bounds.windows(2).map(|w| (w[0], w[1])).collect()
+}
+
+/// Adds the points after `start` on the span to a line strip,
+/// splitting the span if it is not flat enough.
+///
+/// The curve is compared with its chord at the quarters of the span,
+/// as a curve can cross the chord at any single point.
+fn subdivide<T,P,C>(curve: &C, start: T, end: T, tolerance: T, depth: u32, points: &mut Vec<P>)
+    where T: Decimal, P: ControlPoint<T>, C: Curve<T,P> + ?Sized {
+    let middle = (start + end) * T::constant(0.5);
+    let (first, last) = (curve.evaluate(start), curve.evaluate(end));
+
+    let flat = [0.25, 0.5, 0.75].iter().all(|&fraction| {
+        let fraction = T::constant(fraction);
+        let chord = first.combine(last, T::one() - fraction, fraction);
+        let offset = difference(curve.evaluate(start + (end - start) * fraction), chord);
+
+        offset.dot(offset) <= tolerance * tolerance
+    });
+
+    if depth < MAX_TESSELLATION_DEPTH && !flat {
+        subdivide(curve, start, middle, tolerance, depth + 1, points);
+        subdivide(curve, middle, end, tolerance, depth + 1, points);
+    } else {
+        points.push(curve.evaluate(end));
+    }
+}
+
+/// Calculates `a - b`.
+fn difference<T: Decimal, P: ControlPoint<T>>(a: P, b: P) -> P {
+    a.combine(b, T::one(), T::zero() - T::one())
+}
+
+/// Calculates `p * factor`.
+fn scaled<T: Decimal, P: ControlPoint<T>>(p: P, factor: T) -> P {
+    p.combine(p, factor, T::zero())
+}
+
+/// Splits a parameter into a segment number and a parameter within it.
+fn segment<T: Decimal>(t: T, count: usize) -> (usize, T) {
+    let floor = if t > T::zero() { t.floor() } else { T::zero() };
+    let index = std::cmp::min(num::cast::<T,usize>(floor), count - 1);
+
+    (index, t - num::cast(index))
+}
+
+/// A Bézier curve of any degree, over `0..1`.
+#[derive(Clone,Debug)]
+pub struct Bezier<P>
+{
+    points: Vec<P>,
+}
+
+impl<P> Bezier<P>
+{
+    /// Creates a curve from its control points.
+    /// Panics if there are no points.
+    pub fn new(points: Vec<P>) -> Self {
+        assert!(!points.is_empty(), "a curve needs at least one point");
+
+        Bezier {
+            points: points,
+        }
+    }
+
+    pub fn points(&self) -> &[P] { &self.points }
+
+    /// Gets the degree, which is one less than the number of points.
+    pub fn degree(&self) -> usize { self.points.len() - 1 }
+}
+
+/// Evaluates a Bézier curve with de Casteljau's algorithm.
+fn de_casteljau<T: Decimal, P: ControlPoint<T>>(points: &[P], t: T) -> P {
+    let mut points = points.to_vec();
+    let u = T::one() - t;
+
+    for level in (1..points.len()).rev() {
+        for i in 0..level {
+            points[i] = points[i].combine(points[i+1], u, t);
+        }
+    }
+
+    points[0]
+}
+
+impl<T: Decimal, P: ControlPoint<T>> Curve<T,P> for Bezier<P>
+{
+    fn domain(&self) -> (T,T) { (T::zero(), T::one()) }
+
+    fn evaluate(&self, t: T) -> P {
+        de_casteljau(&self.points, t)
+    }
+
+    fn derivative(&self, t: T) -> P {
+        if self.points.len() == 1 {
+            return scaled(self.points[0], T::zero());
+        }
+
+        // The derivative is a Bézier curve of one less degree.
+        let degree: T = num::cast(self.degree());
+        let hodograph: Vec<P> = self.points.windows(2)
+            .map(|w| w[1].combine(w[0], degree, T::zero() - degree)).collect();
+
+        de_casteljau(&hodograph, t)
+    }
+}
+
+/// The cubic Hermite basis functions, and their derivatives.
+fn hermite_basis<T: Decimal>(t: T, derivative: bool) -> [T; 4] {
+    let c = |x: f64| T::constant(x);
+    let (t2, t3) = (t*t, t*t*t);
+
+    if derivative {
+        [c(6.0)*t2 - c(6.0)*t, c(3.0)*t2 - c(4.0)*t + T::one(),
+         c(6.0)*t - c(6.0)*t2, c(3.0)*t2 - c(2.0)*t]
+    } else {
+        [c(2.0)*t3 - c(3.0)*t2 + T::one(), t3 - c(2.0)*t2 + t,
+         c(3.0)*t2 - c(2.0)*t3, t3 - t2]
+    }
+}
+
+/// Evaluates a Hermite segment between two points with tangents.
+fn hermite<T: Decimal, P: ControlPoint<T>>(p0: P, m0: P, p1: P, m1: P, t: T, derivative: bool) -> P {
+    let [h00, h10, h01, h11] = hermite_basis(t, derivative);
+
+    p0.combine(m0, h00, h10).combine(p1.combine(m1, h01, h11), T::one(), T::one())
+}
+
+/// A cubic Hermite spline, through points with given tangents.
+#[derive(Clone,Debug)]
+pub struct Hermite<P>
+{
+    points: Vec<P>,
+    tangents: Vec<P>,
+}
+
+impl<P> Hermite<P>
+{
+    /// Creates a spline from points and the tangents at them.
+    /// Panics if there are fewer than two points, or if there are
+    /// not as many tangents as points.
+    pub fn new(points: Vec<P>, tangents: Vec<P>) -> Self {
+        assert!(points.len() >= 2, "a spline needs at least two points");
+        assert_eq!(points.len(), tangents.len(), "every point needs a tangent");
+
+        Hermite {
+            points: points,
+            tangents: tangents,
+        }
+    }
+
+    pub fn points(&self) -> &[P] { &self.points }
+    pub fn tangents(&self) -> &[P] { &self.tangents }
+}
+
+impl<P> Hermite<P>
+{
+    fn sample<T>(&self, t: T, derivative: bool) -> P
+        where T: Decimal, P: ControlPoint<T> {
+        let (i, t) = segment(t, self.points.len() - 1);
+
+        hermite(self.points[i], self.tangents[i],
+                self.points[i+1], self.tangents[i+1], t, derivative)
+    }
+}
+
+impl<T: Decimal, P: ControlPoint<T>> Curve<T,P> for Hermite<P>
+{
+    fn domain(&self) -> (T,T) { (T::zero(), num::cast(self.points.len() - 1)) }
+    fn breakpoints(&self) -> Vec<T> { (0..self.points.len()).map(num::cast).collect() }
+
+    fn evaluate(&self, t: T) -> P { self.sample(t, false) }
+    fn derivative(&self, t: T) -> P { self.sample(t, true) }
+}
+
+/// A uniform Catmull-Rom spline, which passes through all of its points.
+///
+/// The tangent at each point is parallel to the line between its
+/// neighbours. The end points are treated as their own neighbours.
+#[derive(Clone,Debug)]
+pub struct CatmullRom<P>
+{
+    points: Vec<P>,
+}
+
+impl<P> CatmullRom<P>
+{
+    /// Creates a spline through points.
+    /// Panics if there are fewer than two points.
+    pub fn new(points: Vec<P>) -> Self {
+        assert!(points.len() >= 2, "a spline needs at least two points");
+
+        CatmullRom {
+            points: points,
+        }
+    }
+
+    pub fn points(&self) -> &[P] { &self.points }
+}
+
+impl<P> CatmullRom<P>
+{
+    fn sample<T>(&self, t: T, derivative: bool) -> P
+        where T: Decimal, P: ControlPoint<T> {
+        let last = self.points.len() - 1;
+        let (i, t) = segment(t, last);
+
+        let p0 = self.points[if i == 0 { 0 } else { i - 1 }];
+        let (p1, p2) = (self.points[i], self.points[i+1]);
+        let p3 = self.points[std::cmp::min(i + 2, last)];
+
+        let half = T::constant(0.5);
+        let m1 = scaled(difference(p2, p0), half);
+        let m2 = scaled(difference(p3, p1), half);
+
+        hermite(p1, m1, p2, m2, t, derivative)
+    }
+}
+
+impl<T: Decimal, P: ControlPoint<T>> Curve<T,P> for CatmullRom<P>
+{
+    fn domain(&self) -> (T,T) { (T::zero(), num::cast(self.points.len() - 1)) }
+    fn breakpoints(&self) -> Vec<T> { (0..self.points.len()).map(num::cast).collect() }
+
+    fn evaluate(&self, t: T) -> P { self.sample(t, false) }
+    fn derivative(&self, t: T) -> P { self.sample(t, true) }
+}
+
+/// A B-spline of any degree.
+#[derive(Clone,Debug)]
+pub struct BSpline<T, P>
+{
+    points: Vec<P>,
+    knots: Vec<T>,
+    degree: usize,
+}
+
+impl<T: Decimal, P: ControlPoint<T>> BSpline<T,P>
+{
+    /// Creates a spline from control points and a knot vector.
+    ///
+    /// Panics if there are not `points + degree + 1` knots, if the knots
+    /// decrease, or if there are not more points than the degree.
+    pub fn new(points: Vec<P>, degree: usize, knots: Vec<T>) -> Self {
+        assert!(points.len() > degree, "a spline needs more points than its degree");
+        assert_eq!(knots.len(), points.len() + degree + 1, "wrong number of knots");
+        assert!(knots.windows(2).all(|w| w[0] <= w[1]), "knots must not decrease");
+
+        BSpline {
+            points: points,
+            knots: knots,
+            degree: degree,
+        }
+    }
+
+    /// Creates a spline with evenly spaced knots, clamped so that
+    /// it starts and ends at the first and last points.
+    ///
+    /// The domain is `0..n`, where `n` is the number of points minus
+    /// the degree.
+    pub fn clamped(points: Vec<P>, degree: usize) -> Self {
+        let spans = points.len() - std::cmp::min(degree, points.len());
+        let knots = (0..(points.len() + degree + 1)).map(|i| {
+            let knot = if i < degree { 0 } else { std::cmp::min(i - degree, spans) };
+            num::cast(knot)
+        }).collect();
+
+        BSpline::new(points, degree, knots)
+    }
+
+    pub fn points(&self) -> &[P] { &self.points }
+    pub fn knots(&self) -> &[T] { &self.knots }
+    pub fn degree(&self) -> usize { self.degree }
+}
+
+/// Evaluates a B-spline with de Boor's algorithm.
+fn de_boor<T: Decimal, P: ControlPoint<T>>(points: &[P], degree: usize, knots: &[T], t: T) -> P {
+    let (low, high) = (knots[degree], knots[points.len()]);
+    let t = if t < low { low } else if t > high { high } else { t };
+
+    // Find the knot span containing the parameter.
+    let mut span = degree;
+    while span + 1 < points.len() && knots[span + 1] <= t {
+        span += 1;
+    }
+
+    let mut d: Vec<P> = points[(span - degree)..(span + 1)].to_vec();
+
+    for r in 1..(degree + 1) {
+        for j in (r..(degree + 1)).rev() {
+            let left = knots[j + span - degree];
+            let right = knots[j + 1 + span - r];
+
+            let alpha = if right == left { T::zero() } else { (t - left) / (right - left) };
+            d[j] = d[j-1].combine(d[j], T::one() - alpha, alpha);
+        }
+    }
+
+    d[degree]
+}
+
+impl<T: Decimal, P: ControlPoint<T>> Curve<T,P> for BSpline<T,P>
+{
+    fn domain(&self) -> (T,T) {
+        (self.knots[self.degree], self.knots[self.points.len()])
+    }
+
+    fn breakpoints(&self) -> Vec<T> {
+        let mut knots = self.knots[self.degree..(self.points.len() + 1)].to_vec();
+        knots.dedup();
+        knots
+    }
+
+    fn evaluate(&self, t: T) -> P {
+        de_boor(&self.points, self.degree, &self.knots, t)
+    }
+
+    fn derivative(&self, t: T) -> P {
+        if self.degree == 0 {
+            return scaled(self.points[0], T::zero());
+        }
+
+        // The derivative is a B-spline of one less degree.
+        let p = self.degree;
+        let degree: T = num::cast(p);
+        let points: Vec<P> = (0..(self.points.len() - 1)).map(|i| {
+            let width = self.knots[i + p + 1] - self.knots[i + 1];
+            let factor = if width.is_zero() { T::zero() } else { degree / width };
+
+            self.points[i+1].combine(self.points[i], factor, T::zero() - factor)
+        }).collect();
+
+        de_boor(&points, p - 1, &self.knots[1..(self.knots.len() - 1)], t)
+    }
+}
+
+macro_rules! impl_control_point {
+    ($ty:ident, $($field:tt),+) => {
+        impl<T: Decimal> ControlPoint<T> for $ty<T>
+        {
+            fn combine(self, other: Self, a: T, b: T) -> Self {
+                $ty($(self.$field*a + other.$field*b),+)
+            }
+
+            fn dot(self, other: Self) -> T {
+                T::zero() $(+ self.$field*other.$field)+
+            }
+        }
+    }
+}
+
+impl_control_point!(Vector2, 0, 1);
+impl_control_point!(Vector3, 0, 1, 2);
+impl_control_point!(Vector4, 0, 1, 2, 3);
+impl_control_point!(Quaternion, 0, 1, 2, 3);
+
+impl<T: Decimal, const N: usize> ControlPoint<T> for VectorN<T,N>
+{
+    fn combine(self, other: Self, a: T, b: T) -> Self {
+        VectorN::from_fn(|i| self[i]*a + other[i]*b)
+    }
+
+    fn dot(self, other: Self) -> T {
+        VectorN::dot(self, other)
+    }
+}
+
+#[cfg(test)]
+fn assert_close<P: ControlPoint<f64>>(a: P, b: P, tolerance: f64) {
+    let offset = difference(a, b);
+    assert!(offset.dot(offset).sqrt() <= tolerance);
+}
+
+#[test]
+fn test_bezier() {
+    let curve = Bezier::new(vec![Vector2(0.0, 0.0), Vector2(1.0, 2.0), Vector2(2.0, 0.0)]);
+
+    assert_close(curve.evaluate(0.5), Vector2(1.0, 1.0), 1.0e-12);
+    assert_close(curve.derivative(0.0), Vector2(2.0, 4.0), 1.0e-12);
+    assert_close(curve.tangent(0.5), Vector2(1.0, 0.0), 1.0e-12);
+
+    // A straight, evenly spaced curve.
+    let line = Bezier::new(vec![Vector3(0.0, 0.0, 0.0), Vector3(1.0, 1.0, 0.0),
+                                Vector3(2.0, 2.0, 0.0), Vector3(3.0, 3.0, 0.0)]);
+    let length = 18.0f64.sqrt();
+    assert!((line.arc_length() - length).abs() < 1.0e-9);
+    assert!((line.parameter_at_length(length / 3.0) - 1.0 / 3.0).abs() < 1.0e-6);
+    assert!((line.closest_point(Vector3(3.0, 0.0, 5.0)) - 0.5).abs() < 1.0e-6);
+}
+
+#[test]
+fn test_catmull_rom_and_hermite() {
+    let points = vec![Vector3(0.0, 0.0, 0.0), Vector3(1.0, 2.0, 0.0),
+                      Vector3(3.0, 2.0, 1.0), Vector3(4.0, 0.0, 1.0)];
+    let curve = CatmullRom::new(points.clone());
+
+    assert_eq!(curve.domain(), (0.0, 3.0));
+    for (i, &point) in points.iter().enumerate() {
+        assert_close(curve.evaluate(i as f64), point, 1.0e-12);
+    }
+
+    // The tangent is parallel to the neighbouring points.
+    assert_close(curve.derivative(1.0), Vector3(1.5, 1.0, 0.5), 1.0e-12);
+    assert_close(curve.derivative(1.0), curve.derivative(0.999999), 1.0e-4);
+
+    let hermite = Hermite::new(vec![Vector2(0.0, 0.0), Vector2(1.0, 0.0)],
+                               vec![Vector2(0.0, 1.0), Vector2(0.0, -1.0)]);
+    assert_close(hermite.derivative(0.0), Vector2(0.0, 1.0), 1.0e-12);
+    assert_close(hermite.derivative(1.0), Vector2(0.0, -1.0), 1.0e-12);
+    assert_close(hermite.evaluate(0.5), Vector2(0.5, 0.25), 1.0e-12);
+}
+
+#[test]
+fn test_bspline() {
+    let points = vec![Vector2(0.0, 0.0), Vector2(1.0, 3.0), Vector2(3.0, 3.0), Vector2(4.0, 0.0)];
+
+    // A clamped B-spline without interior knots is a Bézier curve.
+    let spline = BSpline::clamped(points.clone(), 3);
+    let bezier = Bezier::new(points.clone());
+    assert_eq!(spline.knots(), &[0.0, 0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 1.0]);
+
+    for i in 0..11 {
+        let t = i as f64 / 10.0;
+        assert_close(spline.evaluate(t), bezier.evaluate(t), 1.0e-12);
+        assert_close(spline.derivative(t), bezier.derivative(t), 1.0e-9);
+    }
+
+    let quadratic = BSpline::clamped(points.clone(), 2);
+    assert_eq!(quadratic.domain(), (0.0, 2.0));
+    assert_close(quadratic.evaluate(0.0), points[0], 1.0e-12);
+    assert_close(quadratic.evaluate(2.0), points[3], 1.0e-12);
+
+    let h = 1.0e-6;
+    let numeric = scaled(difference(quadratic.evaluate(0.7 + h), quadratic.evaluate(0.7 - h)), 0.5 / h);
+    assert_close(quadratic.derivative(0.7), numeric, 1.0e-5);
+}
+
+#[test]
+fn test_tessellate() {
+    // A quarter circle, approximated by a cubic.
+    let k = 0.5522847498;
+    let curve = Bezier::new(vec![Vector2(1.0, 0.0), Vector2(1.0, k),
+                                 Vector2(k, 1.0), Vector2(0.0, 1.0)]);
+    assert!((curve.arc_length() - std::f64::consts::FRAC_PI_2).abs() < 1.0e-3);
+
+    let coarse = curve.tessellate(1.0e-2);
+    let fine = curve.tessellate(1.0e-4);
+    assert!(fine.len() > coarse.len());
+
+    assert_close(fine[0], Vector2(1.0, 0.0), 1.0e-12);
+    assert_close(*fine.last().unwrap(), Vector2(0.0, 1.0), 1.0e-12);
+
+    // Every chord's midpoint is close to the circle.
+    for pair in fine.windows(2) {
+        let middle = pair[0].combine(pair[1], 0.5, 0.5);
+        assert!((middle.dot(middle).sqrt() - 1.0).abs() < 1.0e-3);
+    }
+}
+
+#[test]
+fn test_many_segments() {
+    // A zig-zag, which has a sharp change of direction in every segment.
+    let points: Vec<_> = (0..65).map(|i| Vector2(i as f64, if i % 2 == 0 { 0.0 } else { 1.0 })).collect();
+    let curve = CatmullRom::new(points);
+
+    let segments: f64 = (0..64).map(|i| curve.length_between(i as f64, (i + 1) as f64)).sum();
+    assert!((curve.arc_length() - segments).abs() < 1.0e-9);
+
+    // Compare against a fine polyline.
+    let polyline: f64 = (0..6400).map(|i| {
+        let offset = difference(curve.evaluate((i + 1) as f64 / 100.0), curve.evaluate(i as f64 / 100.0));
+        offset.dot(offset).sqrt()
+    }).sum();
+    assert!((curve.arc_length() - polyline).abs() < 1.0e-3 * polyline);
+
+    let distance = curve.length_between(0.0, 40.0);
+    assert!((curve.parameter_at_length(distance) - 40.0).abs() < 1.0e-4);
+    assert!((curve.closest_point(Vector2(40.0, -1.0)) - 40.0).abs() < 1.0e-4);
+
+    let strip = curve.tessellate(1.0e-3);
+    assert!(strip.len() > 64 * 8);
+    assert_close(*strip.last().unwrap(), Vector2(64.0, 0.0), 1.0e-12);
+}
+
+#[test]
+fn test_tessellate_crossing_chord() {
+    // This curve crosses its chord at its midpoint.
+    let curve = Bezier::new(vec![Vector2(0.0, 0.0), Vector2(1.0, 1.0),
+                                 Vector2(2.0, -1.0), Vector2(3.0, 0.0)]);
+    let mut points = vec![curve.evaluate(0.0)];
+    subdivide(&curve, 0.0, 1.0, 1.0e-2, 0, &mut points);
+
+    assert!(points.len() > 2);
+}
+
+#[test]
+fn test_quaternion_curve() {
+    let axis = Vector3(0.0, 0.0, 1.0);
+    let rotations = vec![Quaternion::from_axis_angle(axis, 0.0),
+                         Quaternion::from_axis_angle(axis, 0.5),
+                         Quaternion::from_axis_angle(axis, 1.0)];
+    let curve = CatmullRom::new(rotations.clone());
+
+    let (_, angle) = curve.evaluate(1.0f64).normalize().to_axis_angle();
+    assert!((angle - 0.5).abs() < 1.0e-12);
+
+    let (_, angle) = curve.evaluate(0.5).normalize().to_axis_angle();
+    assert!(angle > 0.0 && angle < 0.5);
+}
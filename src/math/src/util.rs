@@ -1,33 +1,46 @@
 
 use num;
-use std::marker::PhantomData;
-use std;
 
+/// An iterator over the components of a vector.
 #[derive(Copy,Clone)]
-#[allow(raw_pointer_derive)]
 pub struct Components<'a, T:'a + Copy>
 {
-    cur: *const T,
-    end: *const T,
-    phantom: PhantomData<&'a T>,
+    source: Source<'a, T>,
+    index: usize,
+}
+
+#[derive(Copy,Clone)]
+enum Source<'a, T:'a + Copy>
+{
+    /// Components borrowed from the vector.
+    Borrowed(&'a [T]),
+    /// Up to four components copied out of the vector.
+    Copied([T; 4], usize),
 }
 
 impl<'a, T: Copy> Components<'a, T>
 {
-    pub fn new(start: *const T,
-               end: *const T) -> Self {
+    /// Iterates over a slice of components.
+    pub fn new(components: &'a [T]) -> Self {
         Components {
-            cur: start,
-            end: end,
-            phantom: PhantomData,
+            source: Source::Borrowed(components),
+            index: 0,
         }
     }
 
-    pub fn with_length(start: *const T,
-                       size: usize) -> Self {
-        let end = (start as usize + (size * std::mem::size_of::<T>())) as *const T;
+    /// Iterates over a copy of up to four components.
+    /// Panics if there are more than four components, or none.
+    pub fn from_values(components: &[T]) -> Self {
+        assert!(components.len() > 0 && components.len() <= 4,
+                "only one to four components can be copied");
+
+        let mut values = [components[0]; 4];
+        values[..components.len()].copy_from_slice(components);
 
-        Components::new(start, end)
+        Components {
+            source: Source::Copied(values, components.len()),
+            index: 0,
+        }
     }
 }
 
@@ -36,16 +49,26 @@ impl<'a, T: Copy> Iterator for Components<'a, T>
     type Item = T;
 
     fn next(&mut self) -> Option<T> {
-        if self.cur != self.end {
-            let item = unsafe { *self.cur };
+        let item = match self.source {
+            Source::Borrowed(components) => components.get(self.index).cloned(),
+            Source::Copied(ref values, len) => values[..len].get(self.index).cloned(),
+        };
 
-            self.cur = (self.cur as usize + std::mem::size_of::<T>()) as *const T;
+        if item.is_some() {
+            self.index += 1;
+        }
 
-            Some(item)
+        item
+    }
 
-        } else { // end of components
-            None
-        }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = match self.source {
+            Source::Borrowed(components) => components.len(),
+            Source::Copied(_, len) => len,
+        };
+
+        let remaining = len - self.index;
+        (remaining, Some(remaining))
     }
 }
 
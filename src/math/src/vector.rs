@@ -20,5 +20,301 @@ pub trait Vector<T: Num> : std::iter::FromIterator<T> + Copy + Clone {
         where T: Decimal {
         self.length_squared().rsqrt()
     }
+
+    /// Calculates the dot product.
+    fn dot(self, other: Self) -> T {
+        self.components().zip(other.components())
+            .fold(T::zero(), |acc, (a,b)| acc + a*b)
+    }
+
+    /// Scales the vector to unit length.
+    fn normalize(self) -> Self
+        where T: Decimal {
+        let inverse_len = self.length_inverse();
+        self.components().map(|c| c * inverse_len).collect()
+    }
+
+    /// Linearly interpolates towards another vector.
+    fn lerp(self, other: Self, t: T) -> Self {
+        self.components().zip(other.components())
+            .map(|(a,b)| a + (b - a) * t).collect()
+    }
+
+    /// Reflects the vector off a surface with the given unit normal.
+    fn reflect(self, normal: Self) -> Self {
+        let scale = self.dot(normal) * (T::one() + T::one());
+        self.components().zip(normal.components())
+            .map(|(v,n)| v - n * scale).collect()
+    }
+
+    /// Projects the vector onto another vector.
+    fn project(self, onto: Self) -> Self {
+        let scale = self.dot(onto) / onto.dot(onto);
+        onto.components().map(|c| c * scale).collect()
+    }
+
+    /// Takes the smaller of each pair of components.
+    fn min(self, other: Self) -> Self {
+        self.components().zip(other.components())
+            .map(|(a,b)| if b < a { b } else { a }).collect()
+    }
+
+    /// Takes the larger of each pair of components.
+    fn max(self, other: Self) -> Self {
+        self.components().zip(other.components())
+            .map(|(a,b)| if b > a { b } else { a }).collect()
+    }
+
+    /// Clamps each component between the components of two vectors.
+    fn clamp(self, min: Self, max: Self) -> Self {
+        self.max(min).min(max)
+    }
+
+    /// Calculates the squared distance to another vector.
+    fn distance_squared(self, other: Self) -> T {
+        self.components().zip(other.components())
+            .fold(T::zero(), |acc, (a,b)| acc + (a-b)*(a-b))
+    }
+
+    /// Calculates the distance to another vector.
+    fn distance(self, other: Self) -> T
+        where T: Decimal {
+        self.distance_squared(other).sqrt()
+    }
+
+    /// Calculates the angle between two vectors in radians.
+    fn angle_between(self, other: Self) -> T
+        where T: Decimal {
+        let one = T::one();
+        let cos = self.dot(other) * (self.length_squared() * other.length_squared()).rsqrt();
+
+        // Rounding can push the cosine just outside of its domain.
+        if cos >= one {
+            T::zero()
+        } else if cos <= T::zero() - one {
+            T::pi()
+        } else {
+            cos.acos()
+        }
+    }
+}
+
+/// Implements the compound assignment operators on a vector type,
+/// in terms of the binary operators.
+///
+/// Extra generic parameters can be given in brackets before the type.
+macro_rules! impl_assign_ops {
+    ($ty:ident) => {
+        impl_assign_ops!([] $ty<T>);
+    };
+    ([$($generics:tt)*] $ty:ty) => {
+        impl<T: Num $($generics)*> ::std::ops::AddAssign for $ty
+        {
+            fn add_assign(&mut self, rhs: Self) { *self = *self + rhs; }
+        }
+
+        impl<T: Num $($generics)*> ::std::ops::SubAssign for $ty
+        {
+            fn sub_assign(&mut self, rhs: Self) { *self = *self - rhs; }
+        }
+
+        impl<T: Num $($generics)*> ::std::ops::MulAssign for $ty
+        {
+            fn mul_assign(&mut self, rhs: Self) { *self = *self * rhs; }
+        }
+
+        impl<T: Num $($generics)*> ::std::ops::MulAssign<T> for $ty
+        {
+            fn mul_assign(&mut self, rhs: T) { *self = *self * rhs; }
+        }
+
+        impl<T: Num $($generics)*> ::std::ops::DivAssign for $ty
+        {
+            fn div_assign(&mut self, rhs: Self) { *self = *self / rhs; }
+        }
+
+        impl<T: Num $($generics)*> ::std::ops::DivAssign<T> for $ty
+        {
+            fn div_assign(&mut self, rhs: T) { *self = *self / rhs; }
+        }
+    }
 }
 
+/// Implements a fixed size vector type in terms of the `VectorN` with
+/// the same number of components.
+///
+/// The fields of the type are given by index, and the arithmetic is
+/// performed by converting to and from the general vector.
+macro_rules! impl_vector {
+    ($ty:ident, $n:tt, $($field:tt),+) => {
+        impl<T: Num> $ty<T>
+        {
+            /// Creates a vector with identical components.
+            pub fn with_components(val: T) -> Self {
+                ::VectorN::with_components(val).into()
+            }
+
+            /// Maps from one vector to another.
+            pub fn map<U, F>(self, f: F) -> $ty<U>
+                where U: Num, F: Fn(T) -> U {
+                self.to_vector_n().map(f).into()
+            }
+
+            /// Folds the vector into a single value.
+            pub fn fold<B, F>(self, init: B, f: F) -> B
+                where F: FnMut(B, T) -> B {
+                self.to_vector_n().fold(init, f)
+            }
+
+            pub fn dot(self, other: Self) -> T {
+                self.to_vector_n().dot(other.to_vector_n())
+            }
+
+            /// Casts the components to a different type.
+            pub fn cast<V>(self) -> $ty<V> where V: Num {
+                self.to_vector_n().cast().into()
+            }
+
+            /// Calculates the squared length of the vector.
+            pub fn length_squared(self) -> T {
+                self.to_vector_n().length_squared()
+            }
+
+            /// Takes the absolute value of all of the components.
+            pub fn as_positive(self) -> Self {
+                self.to_vector_n().as_positive().into()
+            }
+
+            pub fn components<'a>(&'a self) -> ::util::Components<'a, T> {
+                ::util::Components::from_values(&[$(self.$field),+])
+            }
+
+            /// Converts to the general vector with the same size.
+            pub fn to_vector_n(self) -> ::VectorN<T,$n> {
+                self.into()
+            }
+        }
+
+        impl<T: Num> From<$ty<T>> for ::VectorN<T,$n>
+        {
+            fn from(vector: $ty<T>) -> Self {
+                ::VectorN([$(vector.$field),+])
+            }
+        }
+
+        impl<T: Num> From<::VectorN<T,$n>> for $ty<T>
+        {
+            fn from(vector: ::VectorN<T,$n>) -> Self {
+                $ty($(vector[$field]),+)
+            }
+        }
+
+        impl<T: Num> ::Vector<T> for $ty<T>
+        {
+            fn components<'a>(&'a self) -> ::util::Components<'a, T> {
+                $ty::components(self)
+            }
+
+            fn length_squared(self) -> T {
+                $ty::length_squared(self)
+            }
+
+            fn dot(self, other: Self) -> T {
+                $ty::dot(self, other)
+            }
+        }
+
+        impl<T: Num> ::std::iter::FromIterator<T> for $ty<T>
+        {
+            /// Panics if there are too few items.
+            fn from_iter<I>(i: I) -> Self
+                where I: IntoIterator<Item=T> {
+                ::VectorN::<T,$n>::from_iter(i).into()
+            }
+        }
+
+        impl<T: Num> ::num::Zero for $ty<T>
+        {
+            fn zero() -> Self {
+                $ty::with_components(T::zero())
+            }
+
+            fn is_zero(self) -> bool {
+                self.to_vector_n().is_zero()
+            }
+        }
+
+        impl<T: Num> ::num::One for $ty<T>
+        {
+            fn one() -> Self {
+                $ty::with_components(T::one())
+            }
+        }
+
+        impl<T: Num> ::std::ops::Add for $ty<T>
+        {
+            type Output = Self;
+
+            fn add(self, rhs: Self) -> Self {
+                (self.to_vector_n() + rhs.to_vector_n()).into()
+            }
+        }
+
+        impl<T: Num> ::std::ops::Sub for $ty<T>
+        {
+            type Output = Self;
+
+            fn sub(self, rhs: Self) -> Self {
+                (self.to_vector_n() - rhs.to_vector_n()).into()
+            }
+        }
+
+        impl<T: Num> ::std::ops::Neg for $ty<T>
+            where T: ::num::Signed
+        {
+            type Output = Self;
+
+            fn neg(self) -> Self {
+                (-self.to_vector_n()).into()
+            }
+        }
+
+        impl<T: Num> ::std::ops::Mul for $ty<T>
+        {
+            type Output = Self;
+
+            fn mul(self, rhs: Self) -> Self {
+                (self.to_vector_n() * rhs.to_vector_n()).into()
+            }
+        }
+
+        impl<T: Num> ::std::ops::Mul<T> for $ty<T>
+        {
+            type Output = Self;
+
+            fn mul(self, rhs: T) -> Self {
+                (self.to_vector_n() * rhs).into()
+            }
+        }
+
+        impl<T: Num> ::std::ops::Div for $ty<T>
+        {
+            type Output = Self;
+
+            fn div(self, rhs: Self) -> Self {
+                (self.to_vector_n() / rhs.to_vector_n()).into()
+            }
+        }
+
+        impl<T: Num> ::std::ops::Div<T> for $ty<T>
+        {
+            type Output = Self;
+
+            fn div(self, rhs: T) -> Self {
+                (self.to_vector_n() / rhs).into()
+            }
+        }
+
+        impl_assign_ops!($ty);
+    }
+}
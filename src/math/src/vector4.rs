@@ -0,0 +1,119 @@
+use std::ops;
+use num::Num;
+use {Scalar,Vector3,Matrix4};
+#[cfg(test)]
+use Vector;
+
+/// A four dimensional vector.
+///
+/// This is most often used for homogeneous coordinates, where
+/// points have a `w` of one and directions have a `w` of zero.
+#[repr(C)]
+#[derive(Copy,Clone)]
+pub struct Vector4<T: Num = Scalar>(pub T,pub T,pub T,pub T);
+
+impl<T: Num> Vector4<T>
+{
+    /// Creates the homogeneous coordinates of a point.
+    pub fn from_point(point: Vector3<T>) -> Self {
+        let Vector3(x,y,z) = point;
+        Vector4(x, y, z, T::one())
+    }
+
+    /// Creates the homogeneous coordinates of a direction.
+    pub fn from_direction(direction: Vector3<T>) -> Self {
+        let Vector3(x,y,z) = direction;
+        Vector4(x, y, z, T::zero())
+    }
+
+    pub fn x(self) -> T { self.0 }
+    pub fn y(self) -> T { self.1 }
+    pub fn z(self) -> T { self.2 }
+    pub fn w(self) -> T { self.3 }
+
+    /// Drops the `w` component.
+    pub fn xyz(self) -> Vector3<T> {
+        let Vector4(x,y,z,_) = self;
+        Vector3(x,y,z)
+    }
+
+    /// Divides by the `w` component, converting homogeneous
+    /// coordinates back into a point.
+    ///
+    /// Directions, with a `w` of zero, are returned unchanged.
+    pub fn homogeneous_divide(self) -> Vector3<T> {
+        let Vector4(x,y,z,w) = self;
+
+        if w.is_zero() {
+            Vector3(x,y,z)
+        } else {
+            Vector3(x/w, y/w, z/w)
+        }
+    }
+}
+
+impl_vector!(Vector4, 4, 0, 1, 2, 3);
+
+impl<T: Num> From<Vector4<T>> for (T,T,T,T)
+{
+    fn from(vector: Vector4<T>) -> (T,T,T,T) {
+        let Vector4(x,y,z,w) = vector;
+        (x,y,z,w)
+    }
+}
+
+impl<T: Num> From<(T,T,T,T)> for Vector4<T> {
+    fn from((x,y,z,w): (T,T,T,T)) -> Self {
+        Vector4(x,y,z,w)
+    }
+}
+
+/// Transforms the vector as a column, as `M * v` does in GLSL.
+///
+/// This matches `Transform3`, which keeps the translation in the last
+/// column. Points are transformed with `Vector4::from_point` and
+/// `homogeneous_divide`.
+impl<T: Num> ops::Mul<Vector4<T>> for Matrix4<T>
+{
+    type Output = Vector4<T>;
+
+    fn mul(self, v: Vector4<T>) -> Vector4<T> {
+        let (x,y,z,w) = v.into();
+        let row = |r: usize| self[(r,0)]*x + self[(r,1)]*y + self[(r,2)]*z + self[(r,3)]*w;
+
+        Vector4(row(0), row(1), row(2), row(3))
+    }
+}
+
+#[test]
+fn test_vec4_homogeneous() {
+    let point = Vector4::from_point(Vector3(1.0, 2.0, 3.0));
+    assert_eq!((point * 2.0).homogeneous_divide().xyz(), (1.0, 2.0, 3.0));
+
+    let direction = Vector4::from_direction(Vector3(1.0, 2.0, 3.0));
+    assert_eq!(direction.homogeneous_divide().xyz(), (1.0, 2.0, 3.0));
+
+    let translation = Matrix4::new(1.0, 0.0, 0.0, 5.0,
+                                   0.0, 1.0, 0.0, 6.0,
+                                   0.0, 0.0, 1.0, 7.0,
+                                   0.0, 0.0, 0.0, 1.0);
+    assert_eq!((translation * point).xyz().xyz(), (6.0, 8.0, 10.0));
+    assert_eq!((translation * direction).xyz().xyz(), (1.0, 2.0, 3.0));
+}
+
+#[test]
+fn test_vec4_ops() {
+    let a = Vector4(1.0, 2.0, 3.0, 4.0);
+    let b = Vector4(4.0, 3.0, 2.0, 1.0);
+
+    assert_eq!((a + b).fold(0.0, |acc, c| acc + c), 20.0);
+    assert_eq!(a.dot(b), 20.0);
+    assert_eq!(Vector::dot(a, b), 20.0);
+    assert_eq!(a.distance_squared(b), 20.0);
+    assert_eq!((a / b * b - a).length_squared(), 0.0);
+
+    let mut c = a;
+    c -= b;
+    c /= 3.0;
+    assert_eq!(Into::<(f64,f64,f64,f64)>::into(c), (-1.0, -1.0/3.0, 1.0/3.0, 1.0));
+}
@@ -0,0 +1,252 @@
+use std;
+use std::ops;
+use num::{self,Num};
+use {util,Vector};
+#[cfg(test)]
+use Vector3;
+
+/// A vector with any number of components.
+///
+/// Components are accessed by index, or by iterating. The fixed size
+/// vectors are implemented in terms of the vector with the same size,
+/// and convert to and from it.
+#[derive(Copy,Clone,Debug,Eq,PartialEq)]
+pub struct VectorN<T: Num, const N: usize>(pub [T; N]);
+
+impl<T: Num, const N: usize> VectorN<T,N>
+{
+    /// Creates a vector from a function taking the component index.
+    pub fn from_fn<F>(f: F) -> Self
+        where F: FnMut(usize) -> T {
+        VectorN(std::array::from_fn(f))
+    }
+
+    /// Creates a vector with identical components.
+    pub fn with_components(val: T) -> Self {
+        VectorN([val; N])
+    }
+
+    /// Gets the number of components.
+    pub fn len(&self) -> usize { N }
+
+    /// Gets a component, or `None` if the index is out of range.
+    pub fn get(&self, index: usize) -> Option<T> {
+        self.0.get(index).cloned()
+    }
+
+    pub fn iter<'a>(&'a self) -> std::slice::Iter<'a, T> {
+        self.0.iter()
+    }
+
+    pub fn iter_mut<'a>(&'a mut self) -> std::slice::IterMut<'a, T> {
+        self.0.iter_mut()
+    }
+
+    pub fn as_slice<'a>(&'a self) -> &'a [T] { &self.0 }
+    pub fn as_slice_mut<'a>(&'a mut self) -> &'a mut [T] { &mut self.0 }
+
+    /// Maps from one vector to another.
+    pub fn map<U, F>(self, f: F) -> VectorN<U,N>
+        where U: Num, F: Fn(T) -> U {
+        VectorN::from_fn(|i| f(self.0[i]))
+    }
+
+    /// Folds the vector into a single value.
+    pub fn fold<B, F>(self, init: B, f: F) -> B
+        where F: FnMut(B, T) -> B {
+        self.0.iter().cloned().fold(init, f)
+    }
+
+    pub fn dot(self, other: Self) -> T {
+        (0..N).fold(T::zero(), |acc, i| acc + self.0[i]*other.0[i])
+    }
+
+    /// Casts the components to a different type.
+    pub fn cast<V>(self) -> VectorN<V,N> where V: Num {
+        self.map(|a| num::cast(a))
+    }
+
+    /// Calculates the squared length of the vector.
+    pub fn length_squared(self) -> T {
+        self.dot(self)
+    }
+
+    /// Takes the absolute value of all of the components.
+    pub fn as_positive(self) -> Self {
+        self.map(|c| c.abs())
+    }
+
+    /// Combines each pair of components.
+    fn zip_with<F>(self, other: Self, f: F) -> Self
+        where F: Fn(T, T) -> T {
+        VectorN::from_fn(|i| f(self.0[i], other.0[i]))
+    }
+}
+
+impl<T: Num, const N: usize> Vector<T> for VectorN<T,N>
+{
+    fn components<'a>(&'a self) -> util::Components<'a, T> {
+        util::Components::new(&self.0)
+    }
+
+    fn length_squared(self) -> T {
+        VectorN::length_squared(self)
+    }
+
+    fn dot(self, other: Self) -> T {
+        VectorN::dot(self, other)
+    }
+}
+
+impl<T: Num, const N: usize> std::iter::FromIterator<T> for VectorN<T,N>
+{
+    /// Panics if there are too few items.
+    fn from_iter<I>(i: I) -> Self
+        where I: IntoIterator<Item=T> {
+        let mut it = i.into_iter();
+        VectorN::from_fn(|_| it.next().expect("too few components for the vector"))
+    }
+}
+
+impl<T: Num, const N: usize> num::Zero for VectorN<T,N>
+{
+    fn zero() -> Self {
+        VectorN::with_components(T::zero())
+    }
+
+    fn is_zero(self) -> bool {
+        self.0.iter().all(|c| c.is_zero())
+    }
+}
+
+impl<T: Num, const N: usize> num::One for VectorN<T,N>
+{
+    fn one() -> Self {
+        VectorN::with_components(T::one())
+    }
+}
+
+impl<T: Num, const N: usize> ops::Index<usize> for VectorN<T,N>
+{
+    type Output = T;
+
+    fn index<'a>(&'a self, index: usize) -> &'a T {
+        &self.0[index]
+    }
+}
+
+impl<T: Num, const N: usize> ops::IndexMut<usize> for VectorN<T,N>
+{
+    fn index_mut<'a>(&'a mut self, index: usize) -> &'a mut T {
+        &mut self.0[index]
+    }
+}
+
+impl<T: Num, const N: usize> ops::Add for VectorN<T,N>
+{
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        self.zip_with(rhs, |a,b| a+b)
+    }
+}
+
+impl<T: Num, const N: usize> ops::Sub for VectorN<T,N>
+{
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        self.zip_with(rhs, |a,b| a-b)
+    }
+}
+
+impl<T: Num, const N: usize> ops::Neg for VectorN<T,N>
+    where T: num::Signed
+{
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        self.map(|c| -c)
+    }
+}
+
+impl<T: Num, const N: usize> ops::Mul for VectorN<T,N>
+{
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        self.zip_with(rhs, |a,b| a*b)
+    }
+}
+
+impl<T: Num, const N: usize> ops::Mul<T> for VectorN<T,N>
+{
+    type Output = Self;
+
+    fn mul(self, rhs: T) -> Self {
+        self.map(|c| c*rhs)
+    }
+}
+
+impl<T: Num, const N: usize> ops::Div for VectorN<T,N>
+{
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self {
+        self.zip_with(rhs, |a,b| a/b)
+    }
+}
+
+impl<T: Num, const N: usize> ops::Div<T> for VectorN<T,N>
+{
+    type Output = Self;
+
+    fn div(self, rhs: T) -> Self {
+        self.map(|c| c/rhs)
+    }
+}
+
+impl_assign_ops!([, const N: usize] VectorN<T,N>);
+
+impl<T: Num, const N: usize> From<[T; N]> for VectorN<T,N>
+{
+    fn from(components: [T; N]) -> Self {
+        VectorN(components)
+    }
+}
+
+#[test]
+fn test_vecn_indexing() {
+    let mut vec = VectorN([1, 2, 3, 4, 5]);
+
+    assert_eq!(vec.len(), 5);
+    assert_eq!(vec[4], 5);
+    assert_eq!(vec.get(4), Some(5));
+    assert_eq!(vec.get(5), None);
+
+    vec[0] = 10;
+    for c in vec.iter_mut() {
+        *c *= 2;
+    }
+
+    assert_eq!(vec.iter().cloned().collect::<Vec<_>>(), vec![20, 4, 6, 8, 10]);
+    assert_eq!(vec.components().count(), 5);
+}
+
+#[test]
+fn test_vecn_ops() {
+    let a = VectorN([1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+    let b = VectorN::with_components(2.0);
+
+    assert_eq!(a + b - b, a);
+    assert_eq!((a * b / 2.0).0, a.0);
+    assert_eq!(a.dot(b), 42.0);
+    assert_eq!(a.lerp(b, 0.5), VectorN([1.5, 2.0, 2.5, 3.0, 3.5, 4.0]));
+
+    let mut c = a;
+    c -= a;
+    assert!(num::Zero::is_zero(c));
+
+    let vec3: Vector3<f64> = VectorN::from(Vector3(1.0, 2.0, 3.0)).into();
+    assert_eq!(vec3.xyz(), (1.0, 2.0, 3.0));
+}
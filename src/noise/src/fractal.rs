@@ -0,0 +1,218 @@
+//! Fractal combinations of noise functions.
+//!
+//! Fractal noise adds together several octaves of a source noise, each
+//! with a higher frequency and a lower amplitude than the last. Each
+//! octave is also offset, so that the lattices of the octaves don't line up.
+
+use {Noise,Point};
+use num::Decimal;
+
+/// The offset between the points used for consecutive octaves.
+const OCTAVE_OFFSET: f64 = 19.19;
+
+/// Implements the octave settings on a fractal noise type.
+macro_rules! impl_octaves {
+    ($ty:ident) => {
+        impl<N, T: Decimal> $ty<N,T>
+        {
+            /// Creates the fractal from a source noise.
+            ///
+            /// By default there are six octaves, the frequency doubles
+            /// and the amplitude halves with each octave.
+            pub fn new(source: N) -> Self {
+                $ty {
+                    source: source,
+                    octaves: 6,
+                    frequency: T::one(),
+                    lacunarity: T::constant(2.0),
+                    persistence: T::constant(0.5),
+                }
+            }
+
+            /// Sets the number of octaves.
+            pub fn octaves(mut self, octaves: usize) -> Self {
+                self.octaves = octaves;
+                self
+            }
+
+            /// Sets the frequency of the first octave.
+            pub fn frequency(mut self, frequency: T) -> Self {
+                self.frequency = frequency;
+                self
+            }
+
+            /// Sets the factor the frequency is multiplied by with each octave.
+            pub fn lacunarity(mut self, lacunarity: T) -> Self {
+                self.lacunarity = lacunarity;
+                self
+            }
+
+            /// Sets the factor the amplitude is multiplied by with each octave.
+            pub fn persistence(mut self, persistence: T) -> Self {
+                self.persistence = persistence;
+                self
+            }
+
+            /// Sums the octaves after passing each through a function,
+            /// and divides by the total amplitude.
+            fn sum<P,F>(&self, point: P, f: F) -> T
+                where N: Noise<T,P>, P: Point<T>, F: Fn(T) -> T {
+                let mut frequency = self.frequency;
+                let mut amplitude = T::one();
+                let mut total = T::zero();
+                let mut total_amplitude = T::zero();
+
+                for octave in 0..self.octaves {
+                    let offset = T::constant(octave as f64 * OCTAVE_OFFSET);
+                    let value = self.source.get(point.scale(frequency).translate(offset));
+
+                    total = total + f(value) * amplitude;
+                    total_amplitude = total_amplitude + amplitude;
+
+                    frequency = frequency * self.lacunarity;
+                    amplitude = amplitude * self.persistence;
+                }
+
+                if total_amplitude.is_zero() {
+                    total
+                } else {
+                    total / total_amplitude
+                }
+            }
+        }
+    }
+}
+
+/// Fractional Brownian motion.
+///
+/// The output has the same range as the source.
+pub struct Fbm<N, T: Decimal>
+{
+    source: N,
+    octaves: usize,
+    frequency: T,
+    lacunarity: T,
+    persistence: T,
+}
+
+/// Ridged multifractal noise.
+///
+/// Each octave is folded around zero and inverted, which turns the zero
+/// crossings of the source into sharp ridges. For sources within `-1..1`,
+/// the output lies within `0..1`.
+pub struct Ridged<N, T: Decimal>
+{
+    source: N,
+    octaves: usize,
+    frequency: T,
+    lacunarity: T,
+    persistence: T,
+}
+
+/// Turbulence.
+///
+/// The absolute value of each octave is used, which gives billowing
+/// shapes. For sources within `-1..1`, the output lies within `0..1`.
+pub struct Turbulence<N, T: Decimal>
+{
+    source: N,
+    octaves: usize,
+    frequency: T,
+    lacunarity: T,
+    persistence: T,
+}
+
+impl_octaves!(Fbm);
+impl_octaves!(Ridged);
+impl_octaves!(Turbulence);
+
+impl<N, T, P> Noise<T,P> for Fbm<N,T>
+    where N: Noise<T,P>, T: Decimal, P: Point<T>
+{
+    fn get(&self, point: P) -> T {
+        self.sum(point, |v| v)
+    }
+}
+
+impl<N, T, P> Noise<T,P> for Ridged<N,T>
+    where N: Noise<T,P>, T: Decimal, P: Point<T>
+{
+    fn get(&self, point: P) -> T {
+        self.sum(point, |v| {
+            let ridge = T::one() - v.abs();
+            ridge * ridge
+        })
+    }
+}
+
+impl<N, T, P> Noise<T,P> for Turbulence<N,T>
+    where N: Noise<T,P>, T: Decimal, P: Point<T>
+{
+    fn get(&self, point: P) -> T {
+        self.sum(point, |v| v.abs())
+    }
+}
+
+/// Domain warping.
+///
+/// Before the source is sampled, the point is displaced along each axis
+/// by another noise function.
+pub struct Warp<N, W, T: Decimal>
+{
+    source: N,
+    warp: W,
+    strength: T,
+}
+
+impl<N, W, T: Decimal> Warp<N,W,T>
+{
+    /// Creates a warp, where `strength` scales the displacement.
+    pub fn new(source: N, warp: W, strength: T) -> Self {
+        Warp {
+            source: source,
+            warp: warp,
+            strength: strength,
+        }
+    }
+}
+
+impl<N, W, T, P> Noise<T,P> for Warp<N,W,T>
+    where N: Noise<T,P>, W: Noise<T,P>, T: Decimal, P: Point<T>
+{
+    fn get(&self, point: P) -> T {
+        // Offset the warp for each axis, so that every axis
+        // is displaced differently.
+        let displaced = point.map_axes(|axis, c| {
+            let offset = T::constant((axis + 1) as f64 * OCTAVE_OFFSET);
+            c + self.warp.get(point.translate(offset)) * self.strength
+        });
+
+        self.source.get(displaced)
+    }
+}
+
+#[test]
+fn test_fractal_ranges() {
+    use Simplex;
+
+    let simplex = Simplex::new(3);
+    let fbm = Fbm::new(&simplex).octaves(4);
+    let ridged = Ridged::new(&simplex).frequency(0.5);
+    let turbulence = Turbulence::new(&simplex).persistence(0.6);
+    let warp = Warp::new(&simplex, &fbm, 0.5);
+
+    for i in 0..500 {
+        let t = i as f64 * 0.097;
+        let point = (t, t * 0.3 + 1.0);
+
+        let values: [f64; 4] = [fbm.get(point), ridged.get(point),
+                                turbulence.get(point), warp.get(point)];
+
+        assert!(values[0].abs() <= 1.0);
+        assert!(values[1] >= 0.0 && values[1] <= 1.0);
+        assert!(values[2] >= 0.0 && values[2] <= 1.0);
+        assert!(values[3].abs() <= 1.0);
+    }
+
+    assert_eq!(fbm.get((0.25, 0.75)), Fbm::new(Simplex::new(3)).octaves(4).get((0.25, 0.75)));
+}
@@ -0,0 +1,145 @@
+//! Coherent noise.
+//!
+//! Every noise function is built from a seed, and always gives the same
+//! output for the same seed and point.
+
+extern crate num;
+
+pub use self::perlin::Perlin;
+pub use self::simplex::Simplex;
+pub use self::worley::{Worley,Feature};
+pub use self::fractal::{Fbm,Ridged,Turbulence,Warp};
+
+pub mod perlin;
+pub mod simplex;
+pub mod worley;
+pub mod fractal;
+
+mod permutation;
+
+use num::Decimal;
+
+/// A noise function.
+///
+/// `P` is the type of point the function is sampled at, such as
+/// `(T,T)` for 2D noise.
+pub trait Noise<T: Decimal, P: Point<T>>
+{
+    /// Samples the noise at a point.
+    fn get(&self, point: P) -> T;
+}
+
+/// A point that noise can be sampled at.
+pub trait Point<T: Decimal> : Copy
+{
+    /// Gets the coordinates padded out to four, along with the
+    /// number of dimensions.
+    fn axes(self) -> ([T; 4], usize);
+
+    /// Maps every coordinate, given its axis number.
+    fn map_axes<F>(self, f: F) -> Self
+        where F: FnMut(usize, T) -> T;
+
+    /// Multiplies every coordinate by a factor.
+    fn scale(self, factor: T) -> Self {
+        self.map_axes(|_,c| c * factor)
+    }
+
+    /// Adds an amount to every coordinate.
+    fn translate(self, amount: T) -> Self {
+        self.map_axes(|_,c| c + amount)
+    }
+}
+
+impl<T: Decimal> Point<T> for (T,T)
+{
+    fn map_axes<F>(self, mut f: F) -> Self
+        where F: FnMut(usize, T) -> T {
+        (f(0, self.0), f(1, self.1))
+    }
+
+    fn axes(self) -> ([T; 4], usize) {
+        ([self.0, self.1, T::zero(), T::zero()], 2)
+    }
+}
+
+impl<T: Decimal> Point<T> for (T,T,T)
+{
+    fn map_axes<F>(self, mut f: F) -> Self
+        where F: FnMut(usize, T) -> T {
+        (f(0, self.0), f(1, self.1), f(2, self.2))
+    }
+
+    fn axes(self) -> ([T; 4], usize) {
+        ([self.0, self.1, self.2, T::zero()], 3)
+    }
+}
+
+impl<T: Decimal> Point<T> for (T,T,T,T)
+{
+    fn map_axes<F>(self, mut f: F) -> Self
+        where F: FnMut(usize, T) -> T {
+        (f(0, self.0), f(1, self.1), f(2, self.2), f(3, self.3))
+    }
+
+    fn axes(self) -> ([T; 4], usize) {
+        ([self.0, self.1, self.2, self.3], 4)
+    }
+}
+
+impl<'a, T, P, N> Noise<T,P> for &'a N
+    where T: Decimal, P: Point<T>, N: Noise<T,P>
+{
+    fn get(&self, point: P) -> T {
+        (**self).get(point)
+    }
+}
+
+/// Splits a coordinate into its cell and the offset inside the cell.
+fn split<T: Decimal>(c: T) -> (i64, T) {
+    let floor = c.floor();
+    (num::cast(floor), c - floor)
+}
+
+/// Gets the dot product of a pseudo-random gradient with an offset.
+///
+/// The gradients point towards the edges of a square in 2D, the edges of
+/// a cube in 3D and the edges of a tesseract in 4D.
+fn gradient_dot<T: Decimal>(hash: usize, offset: &[T]) -> T {
+    // Picks `x` or `-x` based on a bit of the hash.
+    let signed = |bit: usize, x: T| if (hash >> bit) & 1 == 0 { x } else { T::zero() - x };
+
+    match offset.len() {
+        2 => {
+            let (x,y) = (offset[0], offset[1]);
+            match hash & 7 {
+                0 | 1 | 2 | 3 => signed(0, x) + signed(1, y),
+                4 | 5 => signed(0, x),
+                _ => signed(0, y),
+            }
+        },
+        3 => {
+            let (x,y,z) = (offset[0], offset[1], offset[2]);
+            match hash % 12 {
+                0 | 1 | 2 | 3 => signed(0, x) + signed(1, y),
+                4 | 5 | 6 | 7 => signed(0, x) + signed(1, z),
+                _ => signed(0, y) + signed(1, z),
+            }
+        },
+        4 => {
+            // Every gradient has one zero component.
+            let zero_axis = (hash >> 3) & 3;
+            let mut sum = T::zero();
+            let mut bit = 0;
+
+            for axis in 0..4 {
+                if axis != zero_axis {
+                    sum = sum + signed(bit, offset[axis]);
+                    bit += 1;
+                }
+            }
+            sum
+        },
+        _ => unreachable!(),
+    }
+}
@@ -0,0 +1,105 @@
+//! Perlin's improved gradient noise.
+
+use {Noise,Point,split,gradient_dot};
+use permutation::Permutation;
+use num::{self,Decimal};
+
+/// Gradient noise, in 2, 3 or 4 dimensions.
+///
+/// The output lies roughly within `-1..1`, and is zero at every
+/// lattice point.
+pub struct Perlin
+{
+    permutation: Permutation,
+}
+
+impl Perlin
+{
+    pub fn new(seed: u32) -> Self {
+        Perlin {
+            permutation: Permutation::new(seed),
+        }
+    }
+}
+
+impl<T: Decimal, P: Point<T>> Noise<T,P> for Perlin
+{
+    fn get(&self, point: P) -> T {
+        let (coords, dimensions) = point.axes();
+        let mut cell = [0i64; 4];
+        let mut fraction = [T::zero(); 4];
+
+        for axis in 0..dimensions {
+            let (c,f) = split(coords[axis]);
+            cell[axis] = c;
+            fraction[axis] = f;
+        }
+
+        // Calculate the influence of the gradient at every corner of the
+        // cell. Bit `n` of the corner number is its offset along axis `n`.
+        let corner_count = 1 << dimensions;
+        let mut values = [T::zero(); 16];
+
+        for corner in 0..corner_count {
+            let mut lattice = [0i64; 4];
+            let mut offset = [T::zero(); 4];
+
+            for axis in 0..dimensions {
+                let bit = (corner >> axis) & 1;
+                lattice[axis] = cell[axis] + bit as i64;
+                offset[axis] = fraction[axis] - num::cast(bit);
+            }
+
+            let hash = self.permutation.hash(&lattice[..dimensions]);
+            values[corner] = gradient_dot(hash, &offset[..dimensions]);
+        }
+
+        // Interpolate away one axis at a time.
+        let mut count = corner_count;
+        for axis in 0..dimensions {
+            let t = fade(fraction[axis]);
+            count /= 2;
+
+            for i in 0..count {
+                let (a,b) = (values[2*i], values[2*i+1]);
+                values[i] = a + (b - a) * t;
+            }
+        }
+
+        values[0]
+    }
+}
+
+/// The quintic smoothing curve `6t^5 - 15t^4 + 10t^3`.
+fn fade<T: Decimal>(t: T) -> T {
+    t * t * t * (t * (t * T::constant(6.0) - T::constant(15.0)) + T::constant(10.0))
+}
+
+#[test]
+fn test_perlin_determinism() {
+    let a = Perlin::new(42);
+    let b = Perlin::new(42);
+    let c = Perlin::new(43);
+
+    let point = (0.3f64, 1.7, -2.2);
+    assert_eq!(a.get(point), b.get(point));
+    assert!(a.get(point) != c.get(point));
+
+    // Noise is zero on the lattice.
+    assert_eq!(a.get((3.0f32, -5.0)), 0.0);
+    assert_eq!(a.get((1.0f64, 2.0, 3.0, 4.0)), 0.0);
+}
+
+#[test]
+fn test_perlin_range() {
+    let noise = Perlin::new(7);
+
+    for i in 0..1000 {
+        let t = i as f64 * 0.137;
+        let values: [f64; 3] = [noise.get((t, t * 0.7)),
+                                noise.get((t, t * 0.3, -t)),
+                                noise.get((t, 1.0 - t, t * 0.5, t * 2.0))];
+
+        assert!(values.iter().all(|v| v.abs() <= 1.5));
+    }
+}
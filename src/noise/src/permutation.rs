@@ -0,0 +1,45 @@
+/// A seeded permutation of `0..256`, used to hash lattice points.
+pub struct Permutation
+{
+    /// The permutation, repeated twice to avoid wrapping indices.
+    table: [u8; 512],
+}
+
+impl Permutation
+{
+    pub fn new(seed: u32) -> Self {
+        let mut values = [0u8; 256];
+        for i in 0..256 {
+            values[i] = i as u8;
+        }
+
+        // Fisher-Yates shuffle, driven by a SplitMix64 sequence.
+        let mut state = seed as u64;
+        for i in (1..256).rev() {
+            state = state.wrapping_add(0x9E3779B97F4A7C15);
+            let mut z = state;
+            z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+            z = z ^ (z >> 31);
+
+            let j = (z % (i as u64 + 1)) as usize;
+            values.swap(i, j);
+        }
+
+        let mut table = [0u8; 512];
+        for i in 0..512 {
+            table[i] = values[i & 255];
+        }
+
+        Permutation {
+            table: table,
+        }
+    }
+
+    /// Hashes a lattice point.
+    pub fn hash(&self, coords: &[i64]) -> usize {
+        coords.iter().fold(0, |acc, &c| {
+            self.table[acc + (c & 255) as usize] as usize
+        })
+    }
+}
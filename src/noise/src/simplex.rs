@@ -0,0 +1,129 @@
+//! Simplex noise.
+
+use {Noise,Point,gradient_dot};
+use permutation::Permutation;
+use num::{self,Decimal};
+
+/// Simplex noise, in 2, 3 or 4 dimensions.
+///
+/// Space is divided into simplices rather than cubes, so fewer lattice
+/// points contribute to each sample than with Perlin noise, and there
+/// are fewer directional artifacts. The output lies within `-1..1`.
+pub struct Simplex
+{
+    permutation: Permutation,
+}
+
+impl Simplex
+{
+    pub fn new(seed: u32) -> Self {
+        Simplex {
+            permutation: Permutation::new(seed),
+        }
+    }
+}
+
+impl<T: Decimal, P: Point<T>> Noise<T,P> for Simplex
+{
+    fn get(&self, point: P) -> T {
+        let (coords, dimensions) = point.axes();
+        let one = T::one();
+        let n: T = num::cast(dimensions);
+
+        // The factors which skew the simplex grid onto a cubic grid.
+        let skew = ((n + one).sqrt() - one) / n;
+        let unskew = (one - one / (n + one).sqrt()) / n;
+
+        let coords = &coords[..dimensions];
+        let s = coords.iter().fold(T::zero(), |acc, &c| acc + c) * skew;
+
+        let mut cell = [0i64; 4];
+        for axis in 0..dimensions {
+            cell[axis] = num::cast((coords[axis] + s).floor());
+        }
+
+        let t = cell[..dimensions].iter().fold(T::zero(), |acc, &c| acc + num::cast(c)) * unskew;
+
+        let mut origin = [T::zero(); 4];
+        for axis in 0..dimensions {
+            origin[axis] = coords[axis] - (num::cast::<i64,T>(cell[axis]) - t);
+        }
+
+        // Walk through the corners of the simplex containing the point by
+        // stepping along the axes in order of decreasing offset.
+        let mut order = [0, 1, 2, 3];
+        for i in 1..dimensions {
+            let mut j = i;
+            while j > 0 && origin[order[j]] > origin[order[j-1]] {
+                order.swap(j, j-1);
+                j -= 1;
+            }
+        }
+
+        let (radius, scale) = match dimensions {
+            2 => (T::constant(0.5), T::constant(70.0)),
+            3 => (T::constant(0.6), T::constant(32.0)),
+            _ => (T::constant(0.6), T::constant(27.0)),
+        };
+
+        let mut step = [0i64; 4];
+        let mut total = T::zero();
+
+        for corner in 0..(dimensions + 1) {
+            if corner > 0 {
+                step[order[corner-1]] = 1;
+            }
+
+            let corner_unskew = unskew * num::cast(corner);
+            let mut offset = [T::zero(); 4];
+            let mut lattice = [0i64; 4];
+
+            for axis in 0..dimensions {
+                offset[axis] = origin[axis] - num::cast(step[axis]) + corner_unskew;
+                lattice[axis] = cell[axis] + step[axis];
+            }
+
+            let falloff = offset[..dimensions].iter().fold(radius, |acc, &o| acc - o*o);
+
+            if falloff > T::zero() {
+                let falloff = falloff * falloff;
+                let hash = self.permutation.hash(&lattice[..dimensions]);
+
+                total = total + falloff * falloff * gradient_dot(hash, &offset[..dimensions]);
+            }
+        }
+
+        total * scale
+    }
+}
+
+#[test]
+fn test_simplex_determinism() {
+    let a = Simplex::new(1);
+    let b = Simplex::new(1);
+
+    for i in 0..100 {
+        let t = i as f32 * 0.31;
+        let point = (t, t * 0.5 - 3.0, t * 1.3);
+
+        assert_eq!(a.get(point), b.get(point));
+    }
+}
+
+#[test]
+fn test_simplex_range() {
+    let noise = Simplex::new(99);
+    let mut nonzero = 0;
+
+    for i in 0..1000 {
+        let t = i as f64 * 0.173;
+        let values: [f64; 3] = [noise.get((t, -t * 0.9)),
+                                noise.get((t, t * 0.4, 2.0 - t)),
+                                noise.get((t * 0.3, t, -t, t * 0.6))];
+
+        assert!(values.iter().all(|v| v.abs() <= 1.0));
+        nonzero += values.iter().filter(|v| v.abs() > 1.0e-3).count();
+    }
+
+    assert!(nonzero > 2000);
+}
@@ -0,0 +1,120 @@
+//! Worley (cellular) noise.
+
+use {Noise,Point,split};
+use permutation::Permutation;
+use num::{self,Decimal};
+
+/// Which distance Worley noise outputs.
+#[derive(Copy,Clone,Debug,PartialEq,Eq)]
+pub enum Feature
+{
+    /// The distance to the closest feature point.
+    F1,
+    /// The distance to the second closest feature point.
+    F2,
+    /// The difference between the two, which gives cell edges.
+    F2MinusF1,
+}
+
+/// Worley noise, in 2, 3 or 4 dimensions.
+///
+/// Every lattice cell contains one randomly placed feature point, and the
+/// output is based on the distances to the closest of them. Outputs are
+/// never negative, and are rarely above one.
+pub struct Worley
+{
+    permutation: Permutation,
+    feature: Feature,
+}
+
+impl Worley
+{
+    pub fn new(seed: u32) -> Self {
+        Worley {
+            permutation: Permutation::new(seed),
+            feature: Feature::F1,
+        }
+    }
+
+    /// Sets the distance which is output.
+    pub fn feature(mut self, feature: Feature) -> Self {
+        self.feature = feature;
+        self
+    }
+}
+
+impl<T: Decimal, P: Point<T>> Noise<T,P> for Worley
+{
+    fn get(&self, point: P) -> T {
+        let (coords, dimensions) = point.axes();
+        let mut cell = [0i64; 4];
+        let mut fraction = [T::zero(); 4];
+
+        for axis in 0..dimensions {
+            let (c,f) = split(coords[axis]);
+            cell[axis] = c;
+            fraction[axis] = f;
+        }
+
+        let mut f1 = T::max();
+        let mut f2 = T::max();
+
+        // Search the surrounding cells. Digit `n` of the neighbour number
+        // in base 3 is its offset along axis `n`.
+        let neighbour_count = 3usize.pow(dimensions as u32);
+
+        for neighbour in 0..neighbour_count {
+            let mut key = [0i64; 5];
+            let mut delta = [T::zero(); 4];
+            let mut digits = neighbour;
+
+            for axis in 0..dimensions {
+                let offset = (digits % 3) as i64 - 1;
+                digits /= 3;
+
+                key[axis] = cell[axis] + offset;
+                delta[axis] = num::cast::<i64,T>(offset) - fraction[axis];
+            }
+
+            // Jitter the feature point inside its cell.
+            let mut distance_squared = T::zero();
+            for axis in 0..dimensions {
+                key[dimensions] = axis as i64;
+                let hash = self.permutation.hash(&key[..(dimensions + 1)]);
+                let jitter = (num::cast::<usize,T>(hash) + T::constant(0.5)) / T::constant(256.0);
+
+                let d = delta[axis] + jitter;
+                distance_squared = distance_squared + d*d;
+            }
+
+            if distance_squared < f1 {
+                f2 = f1;
+                f1 = distance_squared;
+            } else if distance_squared < f2 {
+                f2 = distance_squared;
+            }
+        }
+
+        match self.feature {
+            Feature::F1 => f1.sqrt(),
+            Feature::F2 => f2.sqrt(),
+            Feature::F2MinusF1 => f2.sqrt() - f1.sqrt(),
+        }
+    }
+}
+
+#[test]
+fn test_worley_features() {
+    let f1 = Worley::new(5);
+    let f2 = Worley::new(5).feature(Feature::F2);
+    let edges = Worley::new(5).feature(Feature::F2MinusF1);
+
+    for i in 0..500 {
+        let t = i as f64 * 0.211;
+        let point = (t, 0.5 - t * 0.8, t * 0.25);
+
+        let (a, b, c): (f64, f64, f64) = (f1.get(point), f2.get(point), edges.get(point));
+        assert!(a >= 0.0 && a <= b);
+        assert!((c - (b - a)).abs() < 1.0e-12);
+    }
+}
@@ -0,0 +1,210 @@
+//! Numeric casts which detect overflow.
+
+/// A conversion which fails if the value does not fit in the target type.
+pub trait TryCast<T>
+{
+    /// Converts the value, or gives `None` if it is out of range or NaN.
+    ///
+    /// Conversions to floating point types may round, but only fail if
+    /// the value is too large to be finite.
+    fn try_cast(self) -> Option<T>;
+}
+
+/// A conversion which clamps the value to the range of the target type.
+pub trait SaturatingCast<T>
+{
+    /// Converts the value, clamping it to the nearest representable value.
+    /// NaN becomes zero when converting to an integer.
+    fn saturating_cast(self) -> T;
+}
+
+/// Implements the casts from an integer to each integer type.
+macro_rules! impl_int_to_int {
+    ($from:ident; $($to:ident),*) => {
+        $(
+            impl TryCast<$to> for $from {
+                fn try_cast(self) -> Option<$to> {
+                    let value = self as i128;
+
+                    if value < ::std::$to::MIN as i128 || value > ::std::$to::MAX as i128 {
+                        None
+                    } else {
+                        Some(self as $to)
+                    }
+                }
+            }
+
+            impl SaturatingCast<$to> for $from {
+                fn saturating_cast(self) -> $to {
+                    let value = self as i128;
+
+                    if value < ::std::$to::MIN as i128 {
+                        ::std::$to::MIN
+                    } else if value > ::std::$to::MAX as i128 {
+                        ::std::$to::MAX
+                    } else {
+                        self as $to
+                    }
+                }
+            }
+        )*
+    }
+}
+
+/// Implements the casts from a float to each integer type.
+macro_rules! impl_float_to_int {
+    ($from:ident; $($to:ident),*) => {
+        $(
+            impl TryCast<$to> for $from {
+                fn try_cast(self) -> Option<$to> {
+                    // Both bounds are powers of two, so they are exact.
+                    let lower = ::std::$to::MIN as $from;
+                    let upper = ((::std::$to::MAX / 2 + 1) as $from) * 2.0;
+                    let truncated = self.trunc();
+
+                    if truncated >= lower && truncated < upper {
+                        Some(truncated as $to)
+                    } else {
+                        None
+                    }
+                }
+            }
+
+            impl SaturatingCast<$to> for $from {
+                fn saturating_cast(self) -> $to {
+                    // Float to integer `as` casts saturate.
+                    self as $to
+                }
+            }
+        )*
+    }
+}
+
+/// Implements the casts from an integer to each float type.
+macro_rules! impl_int_to_float {
+    ($from:ident; $($to:ident),*) => {
+        $(
+            impl TryCast<$to> for $from {
+                fn try_cast(self) -> Option<$to> { Some(self as $to) }
+            }
+
+            impl SaturatingCast<$to> for $from {
+                fn saturating_cast(self) -> $to { self as $to }
+            }
+        )*
+    }
+}
+
+/// Implements the casts from a float to each float type.
+macro_rules! impl_float_to_float {
+    ($from:ident; $($to:ident),*) => {
+        $(
+            impl TryCast<$to> for $from {
+                fn try_cast(self) -> Option<$to> {
+                    let value = self as $to;
+
+                    if self.is_finite() && value.is_infinite() { None } else { Some(value) }
+                }
+            }
+
+            impl SaturatingCast<$to> for $from {
+                fn saturating_cast(self) -> $to {
+                    let value = self as $to;
+
+                    if self.is_finite() && value.is_infinite() {
+                        if value > 0.0 { ::std::$to::MAX } else { ::std::$to::MIN }
+                    } else {
+                        value
+                    }
+                }
+            }
+        )*
+    }
+}
+
+/// Implements every cast from a primitive type.
+macro_rules! impl_casts {
+    (int $from:ident) => {
+        impl_int_to_int!($from; u8, i8, u16, i16, u32, i32, u64, i64, usize, isize);
+        impl_int_to_float!($from; f32, f64);
+    };
+    (float $from:ident) => {
+        impl_float_to_int!($from; u8, i8, u16, i16, u32, i32, u64, i64, usize, isize);
+        impl_float_to_float!($from; f32, f64);
+    };
+}
+
+impl_casts!(int u8);
+impl_casts!(int i8);
+impl_casts!(int u16);
+impl_casts!(int i16);
+impl_casts!(int u32);
+impl_casts!(int i32);
+impl_casts!(int u64);
+impl_casts!(int i64);
+impl_casts!(int usize);
+impl_casts!(int isize);
+impl_casts!(float f32);
+impl_casts!(float f64);
+
+/// Implements the casts between tuples, which succeed only if every
+/// element can be cast.
+macro_rules! impl_tuple_casts {
+    ($($name:ident),*) => {
+        impl<T,V> TryCast<($(impl_tuple_casts!(@ty V $name),)*)> for ($(impl_tuple_casts!(@ty T $name),)*)
+            where T: TryCast<V>
+        {
+            fn try_cast(self) -> Option<($(impl_tuple_casts!(@ty V $name),)*)> {
+                let ($($name,)*) = self;
+                Some(($($name.try_cast()?,)*))
+            }
+        }
+
+        impl<T,V> SaturatingCast<($(impl_tuple_casts!(@ty V $name),)*)> for ($(impl_tuple_casts!(@ty T $name),)*)
+            where T: SaturatingCast<V>
+        {
+            fn saturating_cast(self) -> ($(impl_tuple_casts!(@ty V $name),)*) {
+                let ($($name,)*) = self;
+                ($($name.saturating_cast(),)*)
+            }
+        }
+    };
+    (@ty $ty:ident $name:ident) => { $ty };
+}
+
+impl_tuple_casts!(a, b);
+impl_tuple_casts!(a, b, c);
+impl_tuple_casts!(a, b, c, d);
+
+#[test]
+fn test_try_cast() {
+    assert_eq!(TryCast::<u8>::try_cast(255i32), Some(255u8));
+    assert_eq!(TryCast::<u8>::try_cast(256i32), None);
+    assert_eq!(TryCast::<u16>::try_cast(-1i64), None);
+    assert_eq!(TryCast::<i64>::try_cast(::std::u64::MAX), None);
+    assert_eq!(TryCast::<u64>::try_cast(::std::u64::MAX), Some(::std::u64::MAX));
+
+    assert_eq!(TryCast::<u32>::try_cast(-0.5f32), Some(0));
+    assert_eq!(TryCast::<u32>::try_cast(-1.0f32), None);
+    assert_eq!(TryCast::<u8>::try_cast(255.9f64), Some(255));
+    assert_eq!(TryCast::<u8>::try_cast(256.0f64), None);
+    assert_eq!(TryCast::<i64>::try_cast(9.3e18f64), None);
+    assert_eq!(TryCast::<i32>::try_cast(::std::f32::NAN), None);
+
+    assert_eq!(TryCast::<f32>::try_cast(1.0e300f64), None);
+    assert!(TryCast::<f32>::try_cast(::std::f64::INFINITY).unwrap().is_infinite());
+
+    assert_eq!(TryCast::<(u16,u16)>::try_cast((1usize, 2usize)), Some((1, 2)));
+    assert_eq!(TryCast::<(u16,u16,u16)>::try_cast((1usize, 70000usize, 2usize)), None);
+}
+
+#[test]
+fn test_saturating_cast() {
+    assert_eq!(SaturatingCast::<u8>::saturating_cast(300i32), 255u8);
+    assert_eq!(SaturatingCast::<u8>::saturating_cast(-5i32), 0u8);
+    assert_eq!(SaturatingCast::<i8>::saturating_cast(::std::u64::MAX), 127i8);
+    assert_eq!(SaturatingCast::<u32>::saturating_cast(-3.5f32), 0u32);
+    assert_eq!(SaturatingCast::<i32>::saturating_cast(::std::f64::NAN), 0i32);
+    assert_eq!(SaturatingCast::<f32>::saturating_cast(-1.0e300f64), ::std::f32::MIN);
+    assert_eq!(SaturatingCast::<(u32,u32)>::saturating_cast((-1.0f32, 5.0e10f32)), (0, ::std::u32::MAX));
+}
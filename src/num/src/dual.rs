@@ -0,0 +1,398 @@
+//! Forward-mode automatic differentiation.
+
+use {Num,Decimal,Signed,Zero,One,Bounded,Cast,CastNum,NumCast};
+use std::ops::{Add,Sub,Mul,Div,Neg,Rem};
+use std::cmp::Ordering;
+
+/// A dual number, which carries a derivative along with its value.
+///
+/// Evaluating any generic function on dual numbers gives the exact
+/// derivative of the function with respect to whichever inputs were
+/// seeded with a derivative of one.
+///
+/// Comparisons only look at the values, so that branches in the function
+/// being differentiated take the same path as they would with plain numbers.
+#[derive(Copy,Clone,Debug)]
+pub struct Dual<T>
+{
+    pub value: T,
+    pub derivative: T,
+}
+
+impl<T: Decimal> Dual<T>
+{
+    pub fn new(value: T, derivative: T) -> Self {
+        Dual {
+            value: value,
+            derivative: derivative,
+        }
+    }
+
+    /// Creates a value which does not vary.
+    pub fn constant_value(value: T) -> Self {
+        Dual::new(value, T::zero())
+    }
+
+    /// Creates the variable which is being differentiated against.
+    pub fn variable(value: T) -> Self {
+        Dual::new(value, T::one())
+    }
+
+    /// Applies the chain rule, given the value of a function and its
+    /// derivative at this value.
+    fn chain(self, value: T, derivative: T) -> Self {
+        Dual::new(value, derivative * self.derivative)
+    }
+}
+
+impl<T: Decimal> From<T> for Dual<T>
+{
+    fn from(value: T) -> Self {
+        Dual::constant_value(value)
+    }
+}
+
+impl<T: Decimal> Add for Dual<T>
+{
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Dual::new(self.value + rhs.value, self.derivative + rhs.derivative)
+    }
+}
+
+impl<T: Decimal> Sub for Dual<T>
+{
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Dual::new(self.value - rhs.value, self.derivative - rhs.derivative)
+    }
+}
+
+impl<T: Decimal> Mul for Dual<T>
+{
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        Dual::new(self.value * rhs.value,
+                  self.derivative * rhs.value + self.value * rhs.derivative)
+    }
+}
+
+impl<T: Decimal> Div for Dual<T>
+{
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self {
+        Dual::new(self.value / rhs.value,
+                  (self.derivative * rhs.value - self.value * rhs.derivative) / (rhs.value * rhs.value))
+    }
+}
+
+impl<T: Decimal> Rem for Dual<T>
+{
+    type Output = Self;
+
+    fn rem(self, rhs: Self) -> Self {
+        let quotient = (self.value / rhs.value).trunc();
+
+        Dual::new(self.value % rhs.value,
+                  self.derivative - quotient * rhs.derivative)
+    }
+}
+
+impl<T: Decimal> Neg for Dual<T>
+{
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Dual::new(T::zero() - self.value, T::zero() - self.derivative)
+    }
+}
+
+impl<T: Decimal> PartialEq for Dual<T>
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<T: Decimal> PartialOrd for Dual<T>
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.value.partial_cmp(&other.value)
+    }
+}
+
+impl<T: Decimal> Zero for Dual<T>
+{
+    fn zero() -> Self { Dual::constant_value(T::zero()) }
+    fn is_zero(self) -> bool { self.value.is_zero() }
+}
+
+impl<T: Decimal> One for Dual<T>
+{
+    fn one() -> Self { Dual::constant_value(T::one()) }
+}
+
+impl<T: Decimal> Bounded for Dual<T>
+{
+    fn min() -> Self { Dual::constant_value(T::min()) }
+    fn max() -> Self { Dual::constant_value(T::max()) }
+}
+
+impl<T,V> Cast<V> for Dual<T>
+    where T: Cast<V>
+{
+    fn cast(self) -> V {
+        self.value.cast()
+    }
+}
+
+impl<T: Decimal> CastNum for Dual<T> { }
+
+impl<T: Decimal> NumCast for Dual<T>
+{
+    fn from<I>(val: I) -> Self
+        where I: CastNum {
+        Dual::constant_value(T::from(val))
+    }
+}
+
+impl<T: Decimal> Num for Dual<T>
+{
+    fn abs(self) -> Self {
+        if self.value < T::zero() { Dual::new(T::zero() - self.value, T::zero() - self.derivative) } else { self }
+    }
+}
+
+impl<T: Decimal> Signed for Dual<T> { }
+
+impl<T: Decimal> Decimal for Dual<T>
+{
+    fn constant(val: f64) -> Self { Dual::constant_value(T::constant(val)) }
+
+    fn pi() -> Self { Dual::constant_value(T::pi()) }
+    fn tau() -> Self { Dual::constant_value(T::tau()) }
+    fn e() -> Self { Dual::constant_value(T::e()) }
+    fn epsilon() -> Self { Dual::constant_value(T::epsilon()) }
+
+    // Rounding is piecewise constant.
+    fn floor(self) -> Self { self.chain(self.value.floor(), T::zero()) }
+    fn ceil(self) -> Self { self.chain(self.value.ceil(), T::zero()) }
+    fn round(self) -> Self { self.chain(self.value.round(), T::zero()) }
+    fn trunc(self) -> Self { self.chain(self.value.trunc(), T::zero()) }
+
+    fn recip(self) -> Self {
+        let recip = self.value.recip();
+        self.chain(recip, T::zero() - recip * recip)
+    }
+
+    fn powi(self, n: i32) -> Self {
+        // x^0 is constant, even at zero where x^-1 is infinite.
+        if n == 0 {
+            return self.chain(T::one(), T::zero());
+        }
+
+        let n_value: T = T::from(n);
+        self.chain(self.value.powi(n), n_value * self.value.powi(n - 1))
+    }
+
+    fn powf(self, n: Self) -> Self {
+        let value = self.value.powf(n.value);
+        let base = if n.value.is_zero() {
+            T::zero()
+        } else {
+            n.value * self.value.powf(n.value - T::one()) * self.derivative
+        };
+
+        // Only take the logarithm when the exponent varies, as it
+        // is undefined for negative bases.
+        let derivative = if n.derivative.is_zero() {
+            base
+        } else {
+            base + value * ln(self.value) * n.derivative
+        };
+
+        Dual::new(value, derivative)
+    }
+
+    fn sqrt(self) -> Self {
+        let sqrt = self.value.sqrt();
+        self.chain(sqrt, half::<T>() / sqrt)
+    }
+
+    fn rsqrt(self) -> Self {
+        let rsqrt = self.value.rsqrt();
+        self.chain(rsqrt, T::zero() - half::<T>() * rsqrt / self.value)
+    }
+
+    fn cbrt(self) -> Self {
+        let cbrt = self.value.cbrt();
+        self.chain(cbrt, (T::constant(3.0) * cbrt * cbrt).recip())
+    }
+
+    fn sin(self) -> Self { self.chain(self.value.sin(), self.value.cos()) }
+    fn cos(self) -> Self { self.chain(self.value.cos(), T::zero() - self.value.sin()) }
+
+    fn sincos(self) -> (Self,Self) {
+        let (sin, cos) = self.value.sincos();
+        (self.chain(sin, cos), self.chain(cos, T::zero() - sin))
+    }
+
+    fn tan(self) -> Self {
+        let tan = self.value.tan();
+        self.chain(tan, T::one() + tan * tan)
+    }
+
+    fn asin(self) -> Self {
+        self.chain(self.value.asin(), (T::one() - self.value * self.value).rsqrt())
+    }
+
+    fn acos(self) -> Self {
+        self.chain(self.value.acos(), T::zero() - (T::one() - self.value * self.value).rsqrt())
+    }
+
+    fn atan(self) -> Self {
+        self.chain(self.value.atan(), (T::one() + self.value * self.value).recip())
+    }
+
+    fn atan2(self, other: Self) -> Self {
+        let (y, x) = (self.value, other.value);
+        let derivative = (x * self.derivative - y * other.derivative) / (x * x + y * y);
+
+        Dual::new(y.atan2(x), derivative)
+    }
+
+    fn sinh(self) -> Self { self.chain(self.value.sinh(), self.value.cosh()) }
+    fn cosh(self) -> Self { self.chain(self.value.cosh(), self.value.sinh()) }
+
+    fn tanh(self) -> Self {
+        let tanh = self.value.tanh();
+        self.chain(tanh, T::one() - tanh * tanh)
+    }
+
+    fn asinh(self) -> Self {
+        self.chain(self.value.asinh(), (self.value * self.value + T::one()).rsqrt())
+    }
+
+    fn acosh(self) -> Self {
+        self.chain(self.value.acosh(), (self.value * self.value - T::one()).rsqrt())
+    }
+
+    fn atanh(self) -> Self {
+        self.chain(self.value.atanh(), (T::one() - self.value * self.value).recip())
+    }
+
+    fn exp(self) -> Self {
+        let exp = self.value.exp();
+        self.chain(exp, exp)
+    }
+
+    fn log(self, base: Self) -> Self {
+        // log_b(a) = ln(a) / ln(b)
+        Dual::new(ln(self.value), self.derivative / self.value) /
+            Dual::new(ln(base.value), base.derivative / base.value)
+    }
+
+    fn to_degrees(self) -> Self { self.chain(self.value.to_degrees(), T::one().to_degrees()) }
+    fn to_radians(self) -> Self { self.chain(self.value.to_radians(), T::one().to_radians()) }
+}
+
+/// Calculates the value and derivative of a function of one variable.
+pub fn derivative<T,F>(f: F, x: T) -> (T,T)
+    where T: Decimal, F: Fn(Dual<T>) -> Dual<T> {
+    let result = f(Dual::variable(x));
+    (result.value, result.derivative)
+}
+
+/// Calculates the gradient of a function of several variables.
+///
+/// The function is evaluated once per variable.
+pub fn gradient<T,F>(f: F, point: &[T]) -> Vec<T>
+    where T: Decimal, F: Fn(&[Dual<T>]) -> Dual<T> {
+    (0..point.len()).map(|i| f(&seed(point, i)).derivative).collect()
+}
+
+/// Calculates the Jacobian matrix of a function of several variables.
+///
+/// Row `i` holds the gradient of output `i`.
+/// The function is evaluated once per variable.
+pub fn jacobian<T,F>(f: F, point: &[T]) -> Vec<Vec<T>>
+    where T: Decimal, F: Fn(&[Dual<T>]) -> Vec<Dual<T>> {
+    let columns: Vec<Vec<Dual<T>>> = (0..point.len()).map(|i| f(&seed(point, i))).collect();
+    let outputs = columns.first().map(|c| c.len()).unwrap_or(0);
+
+    (0..outputs).map(|row| {
+        columns.iter().map(|column| column[row].derivative).collect()
+    }).collect()
+}
+
+/// Lifts a point into dual numbers, differentiating against one variable.
+fn seed<T: Decimal>(point: &[T], variable: usize) -> Vec<Dual<T>> {
+    point.iter().enumerate().map(|(i,&x)| {
+        if i == variable { Dual::variable(x) } else { Dual::constant_value(x) }
+    }).collect()
+}
+
+fn ln<T: Decimal>(x: T) -> T {
+    x.log(T::e())
+}
+
+fn half<T: Decimal>() -> T {
+    T::one() / (T::one() + T::one())
+}
+
+#[cfg(test)]
+fn assert_close(a: f64, b: f64) {
+    assert!((a - b).abs() < 1.0e-12, "{} != {}", a, b);
+}
+
+#[test]
+fn test_dual_arithmetic() {
+    // d/dx (x^3 - 2x) / x = 2x
+    let (value, slope) = derivative(|x| (x.powi(3) - Dual::constant(2.0) * x) / x, 3.0);
+    assert_close(value, 7.0);
+    assert_close(slope, 6.0);
+
+    let (_, slope) = derivative(|x| x.sin() * x.exp(), 0.5f64);
+    assert_close(slope, 0.5f64.exp() * (0.5f64.sin() + 0.5f64.cos()));
+
+    let (_, slope) = derivative(|x| x.powf(x), 2.0f64);
+    assert_close(slope, 4.0 * (2.0f64.ln() + 1.0));
+
+    let (_, slope) = derivative(|x| x.log(Dual::constant(10.0)), 5.0f64);
+    assert_close(slope, 1.0 / (5.0 * 10.0f64.ln()));
+
+    let (_, slope) = derivative(|x| x.abs().sqrt(), -4.0f64);
+    assert_close(slope, -0.25);
+
+    // A zero power is constant, even at zero.
+    assert_eq!(derivative(|x| x.powi(0), 0.0f64), (1.0, 0.0));
+    assert_eq!(derivative(|x| x.powf(Dual::constant(0.0)), 0.0f64), (1.0, 0.0));
+    assert_eq!(derivative(|x| x.powi(2), 0.0f64), (0.0, 0.0));
+
+    // Comparisons ignore the derivative.
+    assert!(Dual::variable(1.0) == Dual::constant_value(1.0));
+    assert!(Dual::variable(1.0) < Dual::constant_value(2.0));
+}
+
+#[test]
+fn test_dual_gradient() {
+    // f(x,y) = x^2 y + atan2(y, x)
+    let f = |p: &[Dual<f64>]| p[0] * p[0] * p[1] + p[1].atan2(p[0]);
+    let grad = gradient(f, &[1.0, 2.0]);
+
+    assert_close(grad[0], 2.0 * 2.0 - 2.0 / 5.0);
+    assert_close(grad[1], 1.0 + 1.0 / 5.0);
+
+    // Polar to cartesian coordinates.
+    let polar = |p: &[Dual<f64>]| vec![p[0] * p[1].cos(), p[0] * p[1].sin()];
+    let jac = jacobian(polar, &[2.0, 0.5]);
+
+    assert_close(jac[0][0], 0.5f64.cos());
+    assert_close(jac[0][1], -2.0 * 0.5f64.sin());
+    assert_close(jac[1][0], 0.5f64.sin());
+    assert_close(jac[1][1], 2.0 * 0.5f64.cos());
+}
@@ -0,0 +1,652 @@
+//! Fixed point numbers.
+
+use {Num,Decimal,Signed,Zero,One,Bounded,Cast,CastNum,NumCast};
+use std::ops::{Add,Sub,Mul,Div,Neg,Rem};
+use std;
+
+/// An integer which can store the bits of a fixed point number.
+pub trait Bits : Copy + Clone + Default + Eq + Ord + std::hash::Hash + std::fmt::Debug
+{
+    /// The number of bits in the integer.
+    const BITS: u32;
+
+    fn to_i128(self) -> i128;
+    /// Converts from a wider integer, clamping it to the range of this type.
+    fn from_i128(value: i128) -> Self;
+}
+
+macro_rules! impl_bits {
+    ($ty:ident) => {
+        impl Bits for $ty
+        {
+            const BITS: u32 = ::std::$ty::MAX.count_ones() + 1;
+
+            fn to_i128(self) -> i128 { self as i128 }
+
+            fn from_i128(value: i128) -> $ty {
+                if value < ::std::$ty::MIN as i128 {
+                    ::std::$ty::MIN
+                } else if value > ::std::$ty::MAX as i128 {
+                    ::std::$ty::MAX
+                } else {
+                    value as $ty
+                }
+            }
+        }
+    }
+}
+
+impl_bits!(i8);
+impl_bits!(i16);
+impl_bits!(i32);
+impl_bits!(i64);
+
+/// A fixed point number, stored in the integer `I` with `FRAC`
+/// fractional bits.
+///
+/// Every operation, including the transcendental functions, is
+/// computed with integer arithmetic, so results are identical on
+/// every machine. Results which do not fit saturate to the bounds
+/// of the type, and domain errors (such as the square root of a
+/// negative number) give the closest valid result.
+#[derive(Copy,Clone,Default,PartialEq,Eq,PartialOrd,Ord,Hash)]
+pub struct Fixed<I, const FRAC: u32>
+{
+    bits: I,
+}
+
+/// A 32-bit number with 16 integer and 16 fractional bits.
+pub type I16F16 = Fixed<i32,16>;
+/// A 64-bit number with 32 integer and 32 fractional bits.
+pub type I32F32 = Fixed<i64,32>;
+/// A 16-bit number with 8 integer and 8 fractional bits.
+pub type I8F8 = Fixed<i16,8>;
+
+impl<I: Bits, const FRAC: u32> Fixed<I,FRAC>
+{
+    /// Creates a number from its underlying bits.
+    pub fn from_bits(bits: I) -> Self {
+        Fixed {
+            bits: bits,
+        }
+    }
+
+    /// Gets the underlying bits.
+    pub fn to_bits(self) -> I { self.bits }
+
+    /// Rounds a floating point number to the nearest fixed point number.
+    pub fn from_f64(value: f64) -> Self {
+        let scaled = (value * (FRAC as f64).exp2()).round();
+        Fixed::from_raw(scaled as i128)
+    }
+
+    pub fn to_f64(self) -> f64 {
+        self.raw() as f64 / (FRAC as f64).exp2()
+    }
+
+    fn raw(self) -> i128 { self.bits.to_i128() }
+
+    fn from_raw(raw: i128) -> Self { Fixed::from_bits(I::from_i128(raw)) }
+
+    /// Converts to the working precision of the transcendental functions.
+    fn to_wide(self) -> i128 {
+        if FRAC <= wide::FRAC {
+            self.raw() << (wide::FRAC - FRAC)
+        } else {
+            wide::round_shift(self.raw(), FRAC - wide::FRAC)
+        }
+    }
+
+    fn from_wide(value: i128) -> Self {
+        if FRAC <= wide::FRAC {
+            Fixed::from_raw(wide::round_shift(value, wide::FRAC - FRAC))
+        } else {
+            Fixed::from_raw(value.saturating_mul(1 << (FRAC - wide::FRAC)))
+        }
+    }
+
+    fn map_wide<F>(self, f: F) -> Self
+        where F: Fn(i128) -> i128 {
+        Fixed::from_wide(f(self.to_wide()))
+    }
+}
+
+impl<I: Bits, const FRAC: u32> std::fmt::Debug for Fixed<I,FRAC>
+{
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.to_f64().fmt(fmt)
+    }
+}
+
+impl<I: Bits, const FRAC: u32> std::fmt::Display for Fixed<I,FRAC>
+{
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.to_f64().fmt(fmt)
+    }
+}
+
+impl<I: Bits, const FRAC: u32> Add for Fixed<I,FRAC>
+{
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self { Fixed::from_raw(self.raw() + rhs.raw()) }
+}
+
+impl<I: Bits, const FRAC: u32> Sub for Fixed<I,FRAC>
+{
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self { Fixed::from_raw(self.raw() - rhs.raw()) }
+}
+
+impl<I: Bits, const FRAC: u32> Mul for Fixed<I,FRAC>
+{
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        Fixed::from_raw(wide::round_shift(self.raw() * rhs.raw(), FRAC))
+    }
+}
+
+impl<I: Bits, const FRAC: u32> Div for Fixed<I,FRAC>
+{
+    type Output = Self;
+
+    /// Divides, rounding towards zero.
+    /// Dividing by zero saturates, and zero divided by zero is zero.
+    fn div(self, rhs: Self) -> Self {
+        if rhs.raw() == 0 {
+            return Fixed::from_raw(self.raw().signum() * std::i128::MAX);
+        }
+        Fixed::from_raw((self.raw() << FRAC) / rhs.raw())
+    }
+}
+
+impl<I: Bits, const FRAC: u32> Rem for Fixed<I,FRAC>
+{
+    type Output = Self;
+
+    /// The remainder of a division towards zero.
+    /// The remainder after dividing by zero is zero.
+    fn rem(self, rhs: Self) -> Self {
+        if rhs.raw() == 0 {
+            return Fixed::zero();
+        }
+        Fixed::from_raw(self.raw() % rhs.raw())
+    }
+}
+
+impl<I: Bits, const FRAC: u32> Neg for Fixed<I,FRAC>
+{
+    type Output = Self;
+
+    fn neg(self) -> Self { Fixed::from_raw(-self.raw()) }
+}
+
+impl<I: Bits, const FRAC: u32> Zero for Fixed<I,FRAC>
+{
+    fn zero() -> Self { Fixed::from_raw(0) }
+    fn is_zero(self) -> bool { self.raw() == 0 }
+}
+
+impl<I: Bits, const FRAC: u32> One for Fixed<I,FRAC>
+{
+    fn one() -> Self { Fixed::from_raw(1 << FRAC) }
+}
+
+impl<I: Bits, const FRAC: u32> Bounded for Fixed<I,FRAC>
+{
+    fn min() -> Self { Fixed::from_raw(std::i128::MIN) }
+    fn max() -> Self { Fixed::from_raw(std::i128::MAX) }
+}
+
+impl<I, V, const FRAC: u32> Cast<V> for Fixed<I,FRAC>
+    where I: Bits, f64: Cast<V>
+{
+    fn cast(self) -> V {
+        self.to_f64().cast()
+    }
+}
+
+impl<I: Bits, const FRAC: u32> CastNum for Fixed<I,FRAC> { }
+
+impl<I: Bits, const FRAC: u32> NumCast for Fixed<I,FRAC>
+{
+    fn from<N>(val: N) -> Self
+        where N: CastNum {
+        Fixed::from_f64(val.cast())
+    }
+}
+
+impl<I: Bits, const FRAC: u32> Num for Fixed<I,FRAC>
+{
+    fn abs(self) -> Self { Fixed::from_raw(self.raw().abs()) }
+}
+
+impl<I: Bits, const FRAC: u32> Signed for Fixed<I,FRAC> { }
+
+impl<I: Bits, const FRAC: u32> Decimal for Fixed<I,FRAC>
+{
+    fn constant(val: f64) -> Self { Fixed::from_f64(val) }
+
+    fn pi() -> Self { Fixed::from_wide(wide::PI) }
+    fn tau() -> Self { Fixed::from_wide(wide::PI * 2) }
+    fn e() -> Self { Fixed::from_wide(wide::E) }
+    fn epsilon() -> Self { Fixed::from_raw(1) }
+
+    fn floor(self) -> Self { Fixed::from_raw((self.raw() >> FRAC) << FRAC) }
+    fn ceil(self) -> Self { -(-self).floor() }
+    fn round(self) -> Self { Fixed::from_raw(wide::round_shift(self.raw(), FRAC) << FRAC) }
+    fn trunc(self) -> Self { if self.raw() < 0 { self.ceil() } else { self.floor() } }
+
+    fn recip(self) -> Self { Fixed::one() / self }
+
+    fn powi(self, n: i32) -> Self {
+        if n < 0 {
+            return self.powi(-n).recip();
+        }
+
+        let (mut result, mut base, mut n) = (Fixed::one(), self, n);
+        while n > 0 {
+            if n & 1 == 1 { result = result * base; }
+            base = base * base;
+            n >>= 1;
+        }
+        result
+    }
+
+    /// Integer exponents are exact powers, so they work with any base.
+    /// Otherwise, zero gives zero and negative bases, which have no real
+    /// power, also give zero.
+    fn powf(self, n: Self) -> Self {
+        // The bits are at most 64 wide, so the whole part fits an i64.
+        let whole = n.raw() >> FRAC;
+        if whole << FRAC == n.raw() {
+            if let Some(exponent) = ::checked_cast::<i64,i32>(whole as i64) {
+                return self.powi(exponent);
+            }
+        }
+
+        if self.raw() <= 0 {
+            return Fixed::zero();
+        }
+        Fixed::from_wide(wide::exp(wide::mul(n.to_wide(), wide::ln(self.to_wide()))))
+    }
+
+    /// The square root, rounded down. Negative numbers give zero.
+    fn sqrt(self) -> Self {
+        if self.raw() <= 0 {
+            return Fixed::zero();
+        }
+        Fixed::from_raw(wide::isqrt((self.raw() as u128) << FRAC) as i128)
+    }
+
+    fn rsqrt(self) -> Self { self.sqrt().recip() }
+
+    fn cbrt(self) -> Self {
+        if self.raw() == 0 {
+            return Fixed::zero();
+        }
+
+        let magnitude = wide::exp(wide::ln(self.to_wide().abs()) / 3);
+        Fixed::from_wide(if self.raw() < 0 { -magnitude } else { magnitude })
+    }
+
+    fn sin(self) -> Self { self.map_wide(|x| wide::sincos(x).0) }
+    fn cos(self) -> Self { self.map_wide(|x| wide::sincos(x).1) }
+
+    fn sincos(self) -> (Self,Self) {
+        let (sin, cos) = wide::sincos(self.to_wide());
+        (Fixed::from_wide(sin), Fixed::from_wide(cos))
+    }
+
+    fn tan(self) -> Self {
+        let (sin, cos) = wide::sincos(self.to_wide());
+        Fixed::from_wide(wide::div(sin, cos))
+    }
+
+    fn asin(self) -> Self {
+        let x = wide::clamp_unit(self.to_wide());
+        Fixed::from_wide(wide::atan2(x, wide::sqrt(wide::ONE - wide::mul(x, x))))
+    }
+
+    fn acos(self) -> Self {
+        let x = wide::clamp_unit(self.to_wide());
+        Fixed::from_wide(wide::atan2(wide::sqrt(wide::ONE - wide::mul(x, x)), x))
+    }
+
+    fn atan(self) -> Self { self.map_wide(wide::atan) }
+
+    fn atan2(self, other: Self) -> Self {
+        Fixed::from_wide(wide::atan2(self.to_wide(), other.to_wide()))
+    }
+
+    fn sinh(self) -> Self {
+        self.map_wide(|x| (wide::exp(x) - wide::exp(-x)) / 2)
+    }
+
+    fn cosh(self) -> Self {
+        self.map_wide(|x| (wide::exp(x) + wide::exp(-x)) / 2)
+    }
+
+    fn tanh(self) -> Self {
+        self.map_wide(|x| {
+            let x = if x > 20 * wide::ONE { 20 * wide::ONE } else if x < -20 * wide::ONE { -20 * wide::ONE } else { x };
+            let exp = wide::exp(2 * x);
+            wide::div(exp - wide::ONE, exp + wide::ONE)
+        })
+    }
+
+    fn asinh(self) -> Self {
+        self.map_wide(|x| {
+            let magnitude = wide::ln(wide::hypot_one(x.abs()) + x.abs());
+            if x < 0 { -magnitude } else { magnitude }
+        })
+    }
+
+    fn acosh(self) -> Self {
+        self.map_wide(|x| {
+            if x <= wide::ONE {
+                return 0;
+            }
+            // sqrt(x^2 - 1) = sqrt((x - 1)(x + 1)), which avoids squaring large numbers.
+            let root = wide::isqrt(((x - wide::ONE) as u128) * ((x + wide::ONE) as u128)) as i128;
+            wide::ln(x + root)
+        })
+    }
+
+    fn atanh(self) -> Self {
+        self.map_wide(|x| {
+            let x = wide::clamp_unit(x);
+            wide::ln(wide::div(wide::ONE + x, wide::ONE - x)) / 2
+        })
+    }
+
+    fn exp(self) -> Self { self.map_wide(wide::exp) }
+
+    fn log(self, base: Self) -> Self {
+        Fixed::from_wide(wide::div(wide::ln(self.to_wide()), wide::ln(base.to_wide())))
+    }
+
+    fn to_degrees(self) -> Self {
+        self * Fixed::constant(180.0) / Fixed::pi()
+    }
+
+    fn to_radians(self) -> Self {
+        self * Fixed::pi() / Fixed::constant(180.0)
+    }
+}
+
+/// Fixed point arithmetic at the working precision of the
+/// transcendental functions, with 48 fractional bits in an `i128`.
+mod wide
+{
+    pub const FRAC: u32 = 48;
+    pub const ONE: i128 = 1 << FRAC;
+
+    pub const PI: i128 = 884279719003555;
+    pub const LN2: i128 = 195103586505167;
+    pub const E: i128 = 765128314358509;
+
+    /// Large enough to saturate any fixed point type, but small
+    /// enough to add and shift without overflow.
+    const HUGE: i128 = 1 << 120;
+
+    /// Shifts right, rounding halves up.
+    pub fn round_shift(value: i128, shift: u32) -> i128 {
+        if shift == 0 {
+            value
+        } else {
+            value.saturating_add(1 << (shift - 1)) >> shift
+        }
+    }
+
+    pub fn mul(a: i128, b: i128) -> i128 {
+        round_shift(a.saturating_mul(b), FRAC)
+    }
+
+    pub fn div(a: i128, b: i128) -> i128 {
+        if b == 0 {
+            return a.signum() * HUGE;
+        }
+
+        // Scale both down until the dividend can be shifted.
+        let (mut a, mut b) = (a, b);
+        while a.unsigned_abs() >= (HUGE >> FRAC) as u128 && b.unsigned_abs() > 1 {
+            a >>= 1;
+            b >>= 1;
+        }
+        (a << FRAC) / b
+    }
+
+    pub fn clamp_unit(x: i128) -> i128 {
+        if x < -ONE { -ONE } else if x > ONE { ONE } else { x }
+    }
+
+    /// The integer square root, rounded down.
+    pub fn isqrt(n: u128) -> u128 {
+        if n == 0 {
+            return 0;
+        }
+
+        // Start above the root, and descend with Newton's method.
+        let mut x = 1u128 << ((128 - n.leading_zeros() + 1) / 2);
+        loop {
+            let y = (x + n / x) >> 1;
+            if y >= x {
+                return x;
+            }
+            x = y;
+        }
+    }
+
+    pub fn sqrt(x: i128) -> i128 {
+        if x <= 0 { 0 } else { isqrt((x as u128) << FRAC) as i128 }
+    }
+
+    /// Calculates `sqrt(x^2 + 1)` for a non-negative `x`.
+    pub fn hypot_one(x: i128) -> i128 {
+        // Beyond this, the one makes no difference.
+        if x > ONE << 24 {
+            x
+        } else {
+            sqrt(mul(x, x) + ONE)
+        }
+    }
+
+    pub fn exp(x: i128) -> i128 {
+        if x > 80 * ONE {
+            return HUGE;
+        }
+        if x < -40 * ONE {
+            return 0;
+        }
+
+        // Split into x = k*ln(2) + r, so that exp(x) = 2^k * exp(r).
+        let k = round_shift(div(x, LN2), FRAC);
+        let r = x - k * LN2;
+
+        let (mut sum, mut term, mut n) = (ONE, ONE, 1);
+        while term != 0 {
+            term = mul(term, r) / n;
+            sum += term;
+            n += 1;
+        }
+
+        if k >= 0 {
+            let k = k as u32;
+            if sum.leading_zeros() <= k + 8 { HUGE } else { sum << k }
+        } else {
+            round_shift(sum, (-k) as u32)
+        }
+    }
+
+    /// The natural logarithm. Numbers which are not positive give a
+    /// large negative number.
+    pub fn ln(x: i128) -> i128 {
+        if x <= 0 {
+            return -HUGE;
+        }
+
+        // Split into x = 2^k * m, where 1 <= m < 2.
+        let k = (127 - x.leading_zeros() as i128) - FRAC as i128;
+        let m = if k >= 0 { x >> k } else { x << -k };
+
+        // ln(m) = 2 atanh((m - 1) / (m + 1))
+        let z = div(m - ONE, m + ONE);
+        let z2 = mul(z, z);
+        let (mut sum, mut term, mut n) = (0, z, 0);
+        while term != 0 {
+            sum += term / (2*n + 1);
+            term = mul(term, z2);
+            n += 1;
+        }
+
+        2*sum + k * LN2
+    }
+
+    /// Calculates the sine and cosine together.
+    pub fn sincos(x: i128) -> (i128,i128) {
+        // Reduce to -pi..pi, then to an octant around a multiple of pi/2.
+        let tau = 2 * PI;
+        let x = x % tau;
+        let x = if x > PI { x - tau } else if x < -PI { x + tau } else { x };
+
+        let quadrant = round_shift(div(x, PI / 2), FRAC);
+        let y = x - quadrant * (PI / 2);
+        let y2 = mul(y, y);
+
+        let (mut sin, mut term, mut n) = (0, y, 1);
+        while term != 0 {
+            sin += term;
+            term = -mul(term, y2) / ((2*n) * (2*n + 1));
+            n += 1;
+        }
+
+        let (mut cos, mut term, mut n) = (0, ONE, 1);
+        while term != 0 {
+            cos += term;
+            term = -mul(term, y2) / ((2*n - 1) * (2*n));
+            n += 1;
+        }
+
+        match quadrant.rem_euclid(4) {
+            0 => (sin, cos),
+            1 => (cos, -sin),
+            2 => (-sin, -cos),
+            _ => (-cos, sin),
+        }
+    }
+
+    pub fn atan(x: i128) -> i128 {
+        if x < 0 {
+            return -atan(-x);
+        }
+        if x > ONE {
+            return PI / 2 - atan(div(ONE, x));
+        }
+
+        // atan(x) = 2 atan(x / (1 + sqrt(1 + x^2))), which brings x
+        // below tan(pi/8) so the series converges quickly.
+        let x = div(x, ONE + hypot_one(x));
+        let x2 = mul(x, x);
+
+        let (mut sum, mut term, mut n) = (0, x, 0);
+        while term != 0 {
+            sum += term / (2*n + 1);
+            term = -mul(term, x2);
+            n += 1;
+        }
+        2 * sum
+    }
+
+    pub fn atan2(y: i128, x: i128) -> i128 {
+        if x == 0 && y == 0 {
+            return 0;
+        }
+
+        if x.abs() >= y.abs() {
+            let angle = atan(div(y, x));
+
+            if x > 0 { angle } else if y >= 0 { angle + PI } else { angle - PI }
+        } else {
+            let angle = atan(div(x, y));
+
+            if y > 0 { PI / 2 - angle } else { -PI / 2 - angle }
+        }
+    }
+}
+
+/// Checks a result is within the rounding of its input.
+#[cfg(test)]
+fn assert_close(fixed: I16F16, expected: f64) {
+    let tolerance = 4.0e-5 * expected.abs().max(1.0);
+    assert!((fixed.to_f64() - expected).abs() < tolerance, "{} != {}", fixed, expected);
+}
+
+#[test]
+fn test_fixed_arithmetic() {
+    let (a, b) = (I16F16::from_f64(2.5), I16F16::from_f64(-0.75));
+
+    assert_eq!(a + b, I16F16::from_f64(1.75));
+    assert_eq!(a * b, I16F16::from_f64(-1.875));
+    assert_eq!(a / b, I16F16::from_bits(-218453));
+    assert_eq!(a % I16F16::one(), I16F16::from_f64(0.5));
+    assert_eq!(-b, I16F16::from_f64(0.75));
+    assert!(b < a);
+
+    assert_eq!(b.floor(), -I16F16::one());
+    assert_eq!(b.ceil(), I16F16::zero());
+    assert_eq!(b.trunc(), I16F16::zero());
+    assert_eq!(a.round(), I16F16::from_f64(3.0));
+
+    // Overflow saturates.
+    assert_eq!(::max::<I16F16>() + I16F16::one(), ::max::<I16F16>());
+    assert_eq!(I16F16::from_f64(30000.0) * I16F16::from_f64(-4.0), ::min::<I16F16>());
+    assert_eq!(I16F16::one() / I16F16::zero(), ::max::<I16F16>());
+
+    assert_eq!(::cast::<I16F16,i32>(I16F16::from_f64(-3.0)), -3);
+    assert_eq!(::cast::<f32,I8F8>(0.5).to_bits(), 128);
+}
+
+#[test]
+fn test_fixed_functions() {
+    assert_eq!(I16F16::from_f64(2.0).sqrt().to_bits(), 92681);
+    assert_eq!(I16F16::from_f64(-2.0).sqrt(), I16F16::zero());
+    assert_eq!(I32F32::from_f64(16.0).sqrt(), I32F32::from_f64(4.0));
+
+    for i in -40..40 {
+        let x = i as f64 * 0.37;
+        let fixed = I16F16::from_f64(x);
+
+        assert_close(fixed.sin(), x.sin());
+        assert_close(fixed.cos(), x.cos());
+        assert_close(fixed.atan(), x.atan());
+        assert_close(fixed.atan2(I16F16::from_f64(-1.5)), x.atan2(-1.5));
+        assert_close((fixed / I16F16::constant(4.0)).exp(), (x / 4.0).exp());
+    }
+
+    for &x in [0.0009765625, 0.375, 1.0, 2.0, 10.0, 1000.0].iter() {
+        let fixed = I16F16::from_f64(x);
+
+        assert_close(fixed.log(I16F16::e()), x.ln());
+        assert_close(fixed.cbrt(), x.cbrt());
+        assert_close(fixed.asinh(), x.asinh());
+    }
+
+    assert_close(I16F16::from_f64(0.5).asin(), 0.5f64.asin());
+    assert_close(I16F16::from_f64(-0.5).acos(), (-0.5f64).acos());
+    assert_close(I16F16::from_f64(2.0).powf(I16F16::from_f64(0.5)), 2.0f64.sqrt());
+    assert_eq!(I16F16::from_f64(-2.0).powf(I16F16::from_f64(2.0)), I16F16::from_f64(4.0));
+    assert_eq!(I16F16::from_f64(-2.0).powf(I16F16::from_f64(3.0)), I16F16::from_f64(-8.0));
+    assert_eq!(I16F16::zero().powf(I16F16::zero()), I16F16::one());
+    assert_eq!(I16F16::zero().powf(I16F16::from_f64(0.5)), I16F16::zero());
+    assert_eq!(I16F16::from_f64(-2.0).powf(I16F16::from_f64(0.5)), I16F16::zero());
+    assert_close(I16F16::from_f64(3.0).acosh(), 3.0f64.acosh());
+    assert_close(I16F16::from_f64(0.5).atanh(), 0.5f64.atanh());
+    assert_close(I16F16::from_f64(0.5).tanh(), 0.5f64.tanh());
+    assert_close(I16F16::pi(), ::std::f64::consts::PI);
+
+    // The same inputs always give the same bits.
+    assert_eq!(I32F32::from_f64(1.0).sin(), I32F32::from_bits(3614090360));
+}
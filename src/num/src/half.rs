@@ -0,0 +1,218 @@
+//! Half precision floating point numbers.
+
+use {Num,Signed,Zero,One,Bounded,Cast,CastNum,NumCast};
+use std::ops::{Add,Sub,Mul,Div,Neg,Rem};
+use std::cmp::Ordering;
+use std;
+
+/// A 16-bit IEEE 754 floating point number.
+///
+/// Arithmetic is performed in single precision, and rounded back to the
+/// nearest half precision number.
+#[allow(non_camel_case_types)]
+#[derive(Copy,Clone,Default)]
+pub struct f16(u16);
+
+impl f16
+{
+    /// Creates a number from its bit pattern.
+    pub fn from_bits(bits: u16) -> Self { f16(bits) }
+    /// Gets the bit pattern of the number.
+    pub fn to_bits(self) -> u16 { self.0 }
+
+    /// Rounds a single precision number to the nearest half precision number.
+    /// Numbers too large to represent become infinite.
+    pub fn from_f32(value: f32) -> Self {
+        let bits = value.to_bits();
+        let sign = ((bits >> 16) & 0x8000) as u16;
+        let exponent = ((bits >> 23) & 0xff) as i32;
+        let mantissa = bits & 0x7fffff;
+
+        // Infinity and NaN, keeping NaNs quiet.
+        if exponent == 0xff {
+            let nan = if mantissa != 0 { 0x200 } else { 0 };
+            return f16(sign | 0x7c00 | nan);
+        }
+
+        let exponent = exponent - 127 + 15;
+
+        if exponent >= 0x1f {
+            return f16(sign | 0x7c00);
+        }
+
+        let (half, shift) = if exponent <= 0 {
+            // Too small to be normal, so the implicit bit becomes explicit.
+            if exponent < -10 {
+                return f16(sign);
+            }
+            let shift = (14 - exponent) as u32;
+            ((mantissa | 0x800000) >> shift, shift)
+        } else {
+            (((exponent as u32) << 10) | (mantissa >> 13), 13)
+        };
+
+        // Round to nearest, ties to even. A carry out of the mantissa
+        // correctly increments the exponent.
+        let remainder = (mantissa | if exponent <= 0 { 0x800000 } else { 0 }) & ((1 << shift) - 1);
+        let halfway = 1 << (shift - 1);
+        let half = if remainder > halfway || (remainder == halfway && half & 1 == 1) { half + 1 } else { half };
+
+        f16(sign | half as u16)
+    }
+
+    /// Converts the number to single precision, which is exact.
+    pub fn to_f32(self) -> f32 {
+        let bits = self.0 as u32;
+        let sign = (bits & 0x8000) << 16;
+        let exponent = (bits >> 10) & 0x1f;
+        let mantissa = bits & 0x3ff;
+
+        match exponent {
+            0 => {
+                let magnitude = mantissa as f32 / (1 << 24) as f32;
+                if sign == 0 { magnitude } else { -magnitude }
+            },
+            0x1f => f32::from_bits(sign | 0x7f800000 | (mantissa << 13)),
+            _ => f32::from_bits(sign | ((exponent + 127 - 15) << 23) | (mantissa << 13)),
+        }
+    }
+
+    pub fn is_nan(self) -> bool {
+        (self.0 & 0x7c00) == 0x7c00 && (self.0 & 0x3ff) != 0
+    }
+}
+
+impl From<f32> for f16
+{
+    fn from(value: f32) -> f16 { f16::from_f32(value) }
+}
+
+impl From<f16> for f32
+{
+    fn from(value: f16) -> f32 { value.to_f32() }
+}
+
+impl std::fmt::Debug for f16
+{
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.to_f32().fmt(fmt)
+    }
+}
+
+impl std::fmt::Display for f16
+{
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.to_f32().fmt(fmt)
+    }
+}
+
+macro_rules! impl_binary_op {
+    ($tr:ident, $method:ident) => {
+        impl $tr for f16
+        {
+            type Output = f16;
+
+            fn $method(self, rhs: f16) -> f16 {
+                f16::from_f32(self.to_f32().$method(rhs.to_f32()))
+            }
+        }
+    }
+}
+
+impl_binary_op!(Add, add);
+impl_binary_op!(Sub, sub);
+impl_binary_op!(Mul, mul);
+impl_binary_op!(Div, div);
+impl_binary_op!(Rem, rem);
+
+impl Neg for f16
+{
+    type Output = f16;
+
+    fn neg(self) -> f16 { f16(self.0 ^ 0x8000) }
+}
+
+impl PartialEq for f16
+{
+    fn eq(&self, other: &f16) -> bool {
+        self.to_f32() == other.to_f32()
+    }
+}
+
+impl PartialOrd for f16
+{
+    fn partial_cmp(&self, other: &f16) -> Option<Ordering> {
+        self.to_f32().partial_cmp(&other.to_f32())
+    }
+}
+
+impl Zero for f16
+{
+    fn zero() -> f16 { f16(0) }
+    fn is_zero(self) -> bool { (self.0 & 0x7fff) == 0 }
+}
+
+impl One for f16
+{
+    fn one() -> f16 { f16(0x3c00) }
+}
+
+impl Bounded for f16
+{
+    fn min() -> f16 { f16(0xfbff) }
+    fn max() -> f16 { f16(0x7bff) }
+}
+
+impl<V> Cast<V> for f16
+    where f32: Cast<V>
+{
+    fn cast(self) -> V {
+        self.to_f32().cast()
+    }
+}
+
+impl CastNum for f16 { }
+
+impl NumCast for f16
+{
+    fn from<I>(val: I) -> f16
+        where I: CastNum {
+        f16::from_f32(val.cast())
+    }
+}
+
+impl Num for f16
+{
+    fn abs(self) -> f16 { f16(self.0 & 0x7fff) }
+}
+
+impl Signed for f16 { }
+
+#[test]
+fn test_f16_conversion() {
+    for &value in [0.0f32, 1.0, -2.5, 0.333251953125, 65504.0, 6.103515625e-5, 5.9604645e-8].iter() {
+        assert_eq!(f16::from_f32(value).to_f32(), value);
+    }
+
+    assert_eq!(f16::from_f32(1.0).to_bits(), 0x3c00);
+    assert_eq!(f16::from_f32(-2.0).to_bits(), 0xc000);
+    assert_eq!(f16::from_f32(65520.0).to_f32(), std::f32::INFINITY);
+    assert!(f16::from_f32(std::f32::NAN).is_nan());
+
+    // Halfway cases round to even.
+    assert_eq!(f16::from_f32(1.0 + 1.0 / 2048.0).to_bits(), 0x3c00);
+    assert_eq!(f16::from_f32(1.0 + 3.0 / 2048.0).to_bits(), 0x3c02);
+    assert_eq!(f16::from_f32(3.0 * 5.9604645e-8 / 2.0).to_bits(), 0x0002);
+}
+
+#[test]
+fn test_f16_arithmetic() {
+    let (a, b) = (f16::from_f32(1.5), f16::from_f32(0.25));
+
+    assert_eq!((a + b).to_f32(), 1.75);
+    assert_eq!((a * b).to_f32(), 0.375);
+    assert_eq!((-a).abs(), a);
+    assert!(b < a);
+    assert_eq!(::cast::<f16,i32>(f16::from_f32(-3.0)), -3);
+    assert_eq!(::cast::<f64,f16>(0.5).to_bits(), 0x3800);
+}
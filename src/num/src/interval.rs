@@ -0,0 +1,529 @@
+//! Interval arithmetic.
+
+use {Num,Decimal,Signed,Zero,One,Bounded,Cast,CastNum,NumCast};
+use std::ops::{Add,Sub,Mul,Div,Neg,Rem};
+use std::cmp::Ordering;
+
+/// A binary floating point number, which can be stepped to its neighbours.
+pub trait Float : Decimal
+{
+    /// Gets the smallest number greater than this one.
+    fn next_up(self) -> Self;
+    /// Gets the largest number less than this one.
+    fn next_down(self) -> Self;
+    /// Gets positive infinity.
+    fn infinity() -> Self;
+}
+
+/// A closed range of numbers, guaranteed to contain the exact result
+/// of every operation performed on it.
+///
+/// Each bound is rounded outwards by one unit in the last place after
+/// every operation, which covers the rounding of both the arithmetic
+/// operators and the standard library functions. Only operations with
+/// an exact zero or one, rounding to an integer, and constants which
+/// convert exactly keep their bounds unchanged.
+///
+/// An interval compares less than another only if it is entirely below
+/// it, and overlapping intervals are unordered. This means a predicate
+/// such as `det > zero` only holds when its sign is certain.
+#[derive(Copy,Clone,Debug)]
+pub struct Interval<T>
+{
+    lower: T,
+    upper: T,
+}
+
+impl<T: Float> Interval<T>
+{
+    /// Creates an interval between two bounds.
+    pub fn new(lower: T, upper: T) -> Self {
+        assert!(lower <= upper, "the lower bound of an interval must not exceed the upper bound");
+
+        Interval {
+            lower: lower,
+            upper: upper,
+        }
+    }
+
+    /// Creates an interval containing a single number.
+    pub fn point(value: T) -> Self {
+        Interval::new(value, value)
+    }
+
+    /// Creates the interval containing every number.
+    pub fn entire() -> Self {
+        Interval::new(T::zero() - T::infinity(), T::infinity())
+    }
+
+    pub fn lower(&self) -> T { self.lower }
+    pub fn upper(&self) -> T { self.upper }
+
+    pub fn width(&self) -> T { (self.upper - self.lower).next_up() }
+
+    /// Gets the middle of the interval.
+    pub fn midpoint(&self) -> T {
+        let two = T::one() + T::one();
+        self.lower / two + self.upper / two
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        self.lower <= value && value <= self.upper
+    }
+
+    /// Checks if the interval contains a single number.
+    pub fn is_point(&self) -> bool {
+        self.lower == self.upper
+    }
+
+    /// Gets the smallest interval containing both intervals.
+    pub fn hull(&self, other: &Self) -> Self {
+        Interval::new(min(self.lower, other.lower), max(self.upper, other.upper))
+    }
+
+    /// Creates an interval from two inexact bounds, rounding them outwards.
+    fn rounded(lower: T, upper: T) -> Self {
+        Interval::new(lower.next_down(), upper.next_up())
+    }
+
+    /// Applies a non-decreasing function to both bounds.
+    fn increasing<F>(self, f: F) -> Self
+        where F: Fn(T) -> T {
+        Interval::rounded(f(self.lower), f(self.upper))
+    }
+
+    /// Applies a non-increasing function to both bounds.
+    fn decreasing<F>(self, f: F) -> Self
+        where F: Fn(T) -> T {
+        Interval::rounded(f(self.upper), f(self.lower))
+    }
+
+    /// Restricts the interval to a function's domain or range.
+    fn clamp(self, lower: T, upper: T) -> Self {
+        Interval::new(min(max(self.lower, lower), upper), max(min(self.upper, upper), lower))
+    }
+
+    /// Checks if the interval contains `offset + k*period` for some integer `k`.
+    fn contains_periodic(&self, offset: T, period: T) -> bool {
+        let k = ((self.lower - offset) / period).ceil();
+        offset + k * period <= self.upper
+    }
+
+    /// Raises an exact number to a power by repeated multiplication.
+    fn powi_point(value: T, n: u32) -> Self {
+        let mut result = Interval::one();
+        let mut base = Interval::point(value);
+        let mut n = n;
+
+        while n > 0 {
+            if n & 1 == 1 { result = result * base; }
+            base = base * base;
+            n >>= 1;
+        }
+        result
+    }
+
+    fn ln(self) -> Self {
+        let lower = if self.lower > T::zero() { ln(self.lower).next_down() } else { T::zero() - T::infinity() };
+        let upper = if self.upper > T::zero() { ln(self.upper).next_up() } else { T::zero() - T::infinity() };
+
+        Interval::new(lower, upper)
+    }
+}
+
+impl<T: Float> Add for Interval<T>
+{
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        if self.is_zero() { return rhs; }
+        if rhs.is_zero() { return self; }
+
+        Interval::rounded(self.lower + rhs.lower, self.upper + rhs.upper)
+    }
+}
+
+impl<T: Float> Sub for Interval<T>
+{
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        if rhs.is_zero() { return self; }
+
+        Interval::rounded(self.lower - rhs.upper, self.upper - rhs.lower)
+    }
+}
+
+impl<T: Float> Mul for Interval<T>
+{
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        // Multiplying by an exact zero or one is exact.
+        if self.is_zero() || rhs.is_zero() { return Interval::zero(); }
+        if self == Interval::one() { return rhs; }
+        if rhs == Interval::one() { return self; }
+
+        let products = [product(self.lower, rhs.lower), product(self.lower, rhs.upper),
+                        product(self.upper, rhs.lower), product(self.upper, rhs.upper)];
+
+        Interval::rounded(products.iter().cloned().fold(products[0], min),
+                          products.iter().cloned().fold(products[0], max))
+    }
+}
+
+impl<T: Float> Div for Interval<T>
+{
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self {
+        if rhs.contains(T::zero()) {
+            return Interval::entire();
+        }
+
+        self * rhs.recip()
+    }
+}
+
+impl<T: Float> Rem for Interval<T>
+{
+    type Output = Self;
+
+    fn rem(self, rhs: Self) -> Self {
+        self - (self / rhs).trunc() * rhs
+    }
+}
+
+impl<T: Float> Neg for Interval<T>
+{
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Interval::new(T::zero() - self.upper, T::zero() - self.lower)
+    }
+}
+
+impl<T: Float> PartialEq for Interval<T>
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.lower == other.lower && self.upper == other.upper
+    }
+}
+
+impl<T: Float> PartialOrd for Interval<T>
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        if self.upper < other.lower {
+            Some(Ordering::Less)
+        } else if self.lower > other.upper {
+            Some(Ordering::Greater)
+        } else if self.is_point() && self == other {
+            Some(Ordering::Equal)
+        } else {
+            None
+        }
+    }
+}
+
+impl<T: Float> Zero for Interval<T>
+{
+    fn zero() -> Self { Interval::point(T::zero()) }
+    fn is_zero(self) -> bool { self.lower.is_zero() && self.upper.is_zero() }
+}
+
+impl<T: Float> One for Interval<T>
+{
+    fn one() -> Self { Interval::point(T::one()) }
+}
+
+impl<T: Float> Bounded for Interval<T>
+{
+    fn min() -> Self { Interval::point(T::min()) }
+    fn max() -> Self { Interval::point(T::max()) }
+}
+
+impl<T,V> Cast<V> for Interval<T>
+    where T: Float + Cast<V>
+{
+    fn cast(self) -> V {
+        self.midpoint().cast()
+    }
+}
+
+impl<T: Float> CastNum for Interval<T> { }
+
+impl<T: Float> NumCast for Interval<T>
+{
+    fn from<I>(val: I) -> Self
+        where I: CastNum {
+        Interval::point(T::from(val))
+    }
+}
+
+impl<T: Float> Num for Interval<T>
+{
+    fn abs(self) -> Self {
+        if self.lower >= T::zero() {
+            self
+        } else if self.upper <= T::zero() {
+            -self
+        } else {
+            Interval::new(T::zero(), max(T::zero() - self.lower, self.upper))
+        }
+    }
+}
+
+impl<T: Float> Signed for Interval<T> { }
+
+impl<T: Float> Decimal for Interval<T>
+{
+    /// Creates an interval containing a constant, which is assumed
+    /// to be exactly representable.
+    fn constant(val: f64) -> Self {
+        let value = T::constant(val);
+
+        if Cast::<f64>::cast(value) == val {
+            Interval::point(value)
+        } else {
+            Interval::rounded(value, value)
+        }
+    }
+
+    fn pi() -> Self { Interval::rounded(T::pi(), T::pi()) }
+    fn tau() -> Self { Interval::rounded(T::tau(), T::tau()) }
+    fn e() -> Self { Interval::rounded(T::e(), T::e()) }
+    fn epsilon() -> Self { Interval::point(T::epsilon()) }
+
+    // Rounding to an integer is exact.
+    fn floor(self) -> Self { Interval::new(self.lower.floor(), self.upper.floor()) }
+    fn ceil(self) -> Self { Interval::new(self.lower.ceil(), self.upper.ceil()) }
+    fn round(self) -> Self { Interval::new(self.lower.round(), self.upper.round()) }
+    fn trunc(self) -> Self { Interval::new(self.lower.trunc(), self.upper.trunc()) }
+
+    fn recip(self) -> Self {
+        if self.contains(T::zero()) {
+            return Interval::entire();
+        }
+        self.decreasing(|x| x.recip())
+    }
+
+    fn powi(self, n: i32) -> Self {
+        if n < 0 {
+            return self.powi(-n).recip();
+        }
+
+        let n = n as u32;
+        let (lower, upper) = (Interval::powi_point(self.lower, n), Interval::powi_point(self.upper, n));
+
+        if n % 2 == 1 || self.lower >= T::zero() {
+            Interval::new(lower.lower, upper.upper)
+        } else if self.upper <= T::zero() {
+            Interval::new(upper.lower, lower.upper)
+        } else {
+            Interval::new(T::zero(), max(lower.upper, upper.upper))
+        }
+    }
+
+    fn powf(self, n: Self) -> Self {
+        (self.ln() * n).exp()
+    }
+
+    fn sqrt(self) -> Self { self.clamp(T::zero(), T::infinity()).increasing(|x| x.sqrt()).clamp(T::zero(), T::infinity()) }
+    fn rsqrt(self) -> Self { self.sqrt().recip() }
+    fn cbrt(self) -> Self { self.increasing(|x| x.cbrt()) }
+
+    fn sin(self) -> Self {
+        let (one, half_pi) = (T::one(), T::pi() / (T::one() + T::one()));
+        let minus_one = T::zero() - one;
+
+        if self.upper - self.lower >= T::tau() {
+            return Interval::new(minus_one, one);
+        }
+
+        let (a, b) = (self.lower.sin(), self.upper.sin());
+        let upper = if self.contains_periodic(half_pi, T::tau()) { one } else { max(a, b).next_up() };
+        let lower = if self.contains_periodic(T::zero() - half_pi, T::tau()) { minus_one } else { min(a, b).next_down() };
+
+        Interval::new(lower, upper).clamp(minus_one, one)
+    }
+
+    fn cos(self) -> Self {
+        let one = T::one();
+        let minus_one = T::zero() - one;
+
+        if self.upper - self.lower >= T::tau() {
+            return Interval::new(minus_one, one);
+        }
+
+        let (a, b) = (self.lower.cos(), self.upper.cos());
+        let upper = if self.contains_periodic(T::zero(), T::tau()) { one } else { max(a, b).next_up() };
+        let lower = if self.contains_periodic(T::pi(), T::tau()) { minus_one } else { min(a, b).next_down() };
+
+        Interval::new(lower, upper).clamp(minus_one, one)
+    }
+
+    fn tan(self) -> Self {
+        let half_pi = T::pi() / (T::one() + T::one());
+
+        if self.upper - self.lower >= T::pi() || self.contains_periodic(half_pi, T::pi()) {
+            return Interval::entire();
+        }
+        self.increasing(|x| x.tan())
+    }
+
+    fn asin(self) -> Self { self.clamp(T::zero() - T::one(), T::one()).increasing(|x| x.asin()) }
+    fn acos(self) -> Self { self.clamp(T::zero() - T::one(), T::one()).decreasing(|x| x.acos()) }
+    fn atan(self) -> Self { self.increasing(|x| x.atan()) }
+
+    fn atan2(self, other: Self) -> Self {
+        let pi = T::pi().next_up();
+
+        // The angle is discontinuous across the negative X axis.
+        if self.contains(T::zero()) && other.lower <= T::zero() {
+            return Interval::new(T::zero() - pi, pi);
+        }
+
+        // Otherwise, the extreme angles are at the corners.
+        let corners = [self.lower.atan2(other.lower), self.lower.atan2(other.upper),
+                       self.upper.atan2(other.lower), self.upper.atan2(other.upper)];
+
+        Interval::rounded(corners.iter().cloned().fold(corners[0], min),
+                          corners.iter().cloned().fold(corners[0], max))
+    }
+
+    fn sinh(self) -> Self { self.increasing(|x| x.sinh()) }
+
+    fn cosh(self) -> Self {
+        let abs = self.abs();
+        abs.increasing(|x| x.cosh()).clamp(T::one(), T::infinity())
+    }
+
+    fn tanh(self) -> Self { self.increasing(|x| x.tanh()) }
+    fn asinh(self) -> Self { self.increasing(|x| x.asinh()) }
+    fn acosh(self) -> Self { self.clamp(T::one(), T::infinity()).increasing(|x| x.acosh()) }
+    fn atanh(self) -> Self { self.clamp(T::zero() - T::one(), T::one()).increasing(|x| x.atanh()) }
+
+    fn exp(self) -> Self { self.increasing(|x| x.exp()).clamp(T::zero(), T::infinity()) }
+
+    fn log(self, base: Self) -> Self {
+        self.ln() / base.ln()
+    }
+
+    fn to_degrees(self) -> Self { self * Interval::constant(180.0) / Interval::pi() }
+    fn to_radians(self) -> Self { self * Interval::pi() / Interval::constant(180.0) }
+}
+
+macro_rules! impl_float {
+    ($ty:ident) => {
+        impl Float for $ty
+        {
+            fn next_up(self) -> $ty {
+                if self.is_nan() || self == ::std::$ty::INFINITY {
+                    self
+                } else if self == 0.0 {
+                    $ty::from_bits(1)
+                } else if self > 0.0 {
+                    $ty::from_bits(self.to_bits() + 1)
+                } else {
+                    $ty::from_bits(self.to_bits() - 1)
+                }
+            }
+
+            fn next_down(self) -> $ty {
+                -(-self).next_up()
+            }
+
+            fn infinity() -> $ty { ::std::$ty::INFINITY }
+        }
+    }
+}
+
+impl_float!(f32);
+impl_float!(f64);
+
+fn min<T: PartialOrd>(a: T, b: T) -> T { if b < a { b } else { a } }
+fn max<T: PartialOrd>(a: T, b: T) -> T { if b > a { b } else { a } }
+
+/// Multiplies two bounds, taking zero times infinity to be zero.
+/// An infinite bound only means the interval is unbounded, so the
+/// product of it and a zero bound is zero.
+fn product<T: Float>(a: T, b: T) -> T {
+    if a.is_zero() || b.is_zero() { T::zero() } else { a * b }
+}
+
+fn ln<T: Decimal>(x: T) -> T {
+    x.log(T::e())
+}
+
+#[test]
+fn test_interval_arithmetic() {
+    let tenth = Interval::point(0.1f64);
+    let sum = tenth + tenth + tenth;
+
+    // 0.1 + 0.1 + 0.1 rounds to slightly more than 0.3.
+    assert!(sum.contains(0.1 + 0.1 + 0.1));
+    assert!(sum.lower() < sum.upper());
+
+    let a = Interval::new(-1.0f64, 2.0);
+    let b = Interval::new(3.0, 4.0);
+    assert!((a * b).contains(-4.0) && (a * b).contains(8.0));
+    assert!((a * b).lower() > -4.0 - 1.0e-12 && (a * b).upper() < 8.0 + 1.0e-12);
+
+    assert_eq!(a.powi(2).lower(), 0.0);
+    assert!(a.powi(2).contains(4.0));
+    assert!(a.abs() == Interval::new(0.0, 2.0));
+    assert!((b / a).upper().is_infinite());
+
+    // Exact operations stay exact.
+    assert!(Interval::point(3.0f64) * Interval::one() + Interval::zero() == Interval::point(3.0));
+
+    // Constants widen unless they convert exactly.
+    let tenth = Interval::<f32>::constant(0.1);
+    assert!(tenth.lower() < tenth.upper());
+    assert!(tenth.lower() as f64 <= 0.1 && 0.1 <= tenth.upper() as f64);
+    assert!(Interval::<f32>::constant(0.5).is_point());
+}
+
+#[test]
+fn test_interval_unbounded_product() {
+    let unbounded = Interval::new(3.0f64, 4.0) / Interval::new(-1.0, 2.0);
+    let product = unbounded * Interval::new(0.0, 1.0);
+
+    assert!(product.contains(0.0));
+    assert!(product.lower().is_infinite() && product.upper().is_infinite());
+
+    let half = Interval::new(0.0f64, ::std::f64::INFINITY) * Interval::new(0.0, 2.0);
+    assert!(half.contains(0.0) && half.lower() <= 0.0 && half.upper().is_infinite());
+}
+
+#[test]
+fn test_interval_functions() {
+    let range = Interval::new(1.0f64, 2.0);
+    let sin = range.sin();
+    assert_eq!(sin.upper(), 1.0);
+    assert!(sin.contains(1.0f64.sin()) && sin.lower() < 1.0f64.sin());
+
+    let cos = Interval::new(-0.5f64, 4.0).cos();
+    assert_eq!((cos.lower(), cos.upper()), (-1.0, 1.0));
+
+    assert!(Interval::new(1.0f64, 2.0).tan().upper().is_infinite());
+    assert!(Interval::point(2.0f64).sqrt().contains(2.0f64.sqrt()));
+    assert!(Interval::<f64>::pi().contains(::std::f64::consts::PI));
+    assert!(Interval::new(-1.0f64, 1.0).atan2(Interval::new(-2.0, -1.0)).contains(::std::f64::consts::PI));
+}
+
+#[test]
+fn test_interval_certifies_predicates() {
+    // The orientation of three points, which is uncertain when they
+    // are nearly collinear.
+    let orientation = |a: (f64,f64), b: (f64,f64), c: (f64,f64)| {
+        let p = |x: f64| Interval::point(x);
+        (p(b.0) - p(a.0)) * (p(c.1) - p(a.1)) - (p(b.1) - p(a.1)) * (p(c.0) - p(a.0))
+    };
+
+    assert!(orientation((0.0, 0.0), (1.0, 0.0), (0.0, 1.0)) > Interval::zero());
+    assert!(orientation((0.0, 0.0), (0.0, 1.0), (1.0, 0.0)) < Interval::zero());
+
+    let nearly = orientation((0.1, 0.1), (0.2, 0.2), (0.3, 0.3 + 1.0e-17));
+    assert!(!(nearly > Interval::zero()) && !(nearly < Interval::zero()));
+}
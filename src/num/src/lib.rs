@@ -3,6 +3,20 @@
 
 use std::ops::{Add,Sub,Mul,Div,Neg,Rem};
 
+pub use self::dual::Dual;
+pub use self::interval::{Interval,Float};
+pub use self::half::f16;
+pub use self::norm::{Unorm8,Unorm16,Snorm8,Snorm16,Snorm10x3};
+pub use self::checked::{TryCast,SaturatingCast};
+pub use self::fixed::{Fixed,I8F8,I16F16,I32F32};
+
+pub mod dual;
+pub mod interval;
+pub mod half;
+pub mod norm;
+pub mod checked;
+pub mod fixed;
+
 pub trait Cast<T>
 {
     fn cast(self) -> T;
@@ -53,6 +67,17 @@ pub trait Decimal : Num
     fn tau() -> Self;
     fn two_pi() -> Self { Decimal::tau() }
     fn e() -> Self;
+
+    /// Gets the difference between one and the next larger number,
+    /// which bounds the relative rounding error of an operation.
+    fn epsilon() -> Self {
+        let (one, two) = (Self::one(), Self::one() + Self::one());
+        let mut epsilon = one;
+        while one + epsilon / two != one {
+            epsilon = epsilon / two;
+        }
+        epsilon
+    }
     
     // functions
     fn floor(self) -> Self;
@@ -195,6 +220,7 @@ macro_rules! impl_decimal {
             fn pi() -> $ty { ::std::$ty::consts::PI }
             fn tau() -> $ty { ::std::$ty::consts::PI * 2.0}
             fn e() -> $ty { ::std::$ty::consts::E }
+            fn epsilon() -> $ty { ::std::$ty::EPSILON }
             
             fn floor(self) -> $ty { $ty::floor(self) }
             fn ceil(self) -> $ty { $ty::ceil(self) }
@@ -366,6 +392,18 @@ pub fn cast<T,V>(from: T) -> V
     V::from(from)
 }
 
+/// Casts a number to another type, or gives `None` if it does not fit.
+pub fn checked_cast<T,V>(from: T) -> Option<V>
+    where T: TryCast<V> {
+    from.try_cast()
+}
+
+/// Casts a number to another type, clamping it to the range of the type.
+pub fn saturating_cast<T,V>(from: T) -> V
+    where T: SaturatingCast<V> {
+    from.saturating_cast()
+}
+
 /// Gets the zero value for a type.
 pub fn zero<T: Zero>() -> T
 {
@@ -0,0 +1,173 @@
+//! Normalised integers, which store fractions in a fixed range.
+
+use {Num,Signed,Zero,One,Bounded,Cast,CastNum,NumCast};
+use std::ops::{Add,Sub,Mul,Div,Neg,Rem};
+use std;
+
+macro_rules! impl_norm {
+    ($name:ident, $ty:ident, $lowest:expr, $doc:expr) => {
+        #[doc = $doc]
+        ///
+        /// Arithmetic is performed in single precision, and the result is
+        /// clamped to the range and rounded to the nearest step.
+        #[derive(Copy,Clone,Debug,Default,PartialEq,Eq,PartialOrd,Ord)]
+        pub struct $name(pub $ty);
+
+        impl $name
+        {
+            /// Rounds a fraction to the nearest step, clamping it to the range.
+            pub fn from_f32(value: f32) -> Self {
+                let value = if value < $lowest { $lowest } else if value > 1.0 { 1.0 } else { value };
+                $name((value * std::$ty::MAX as f32).round() as $ty)
+            }
+
+            pub fn to_f32(self) -> f32 {
+                let value = self.0 as f32 / std::$ty::MAX as f32;
+
+                // Signed types have one more negative step, which also means -1.
+                if value < $lowest { $lowest } else { value }
+            }
+        }
+
+        impl From<$name> for f32
+        {
+            fn from(value: $name) -> f32 { value.to_f32() }
+        }
+
+        impl_norm!(op $name, Add, add);
+        impl_norm!(op $name, Sub, sub);
+        impl_norm!(op $name, Mul, mul);
+        impl_norm!(op $name, Div, div);
+        impl_norm!(op $name, Rem, rem);
+
+        impl Zero for $name
+        {
+            fn zero() -> $name { $name(0) }
+            fn is_zero(self) -> bool { self.0 == 0 }
+        }
+
+        impl One for $name
+        {
+            fn one() -> $name { $name(std::$ty::MAX) }
+        }
+
+        impl Bounded for $name
+        {
+            fn min() -> $name { $name::from_f32($lowest) }
+            fn max() -> $name { $name(std::$ty::MAX) }
+        }
+
+        impl<V> Cast<V> for $name
+            where f32: Cast<V>
+        {
+            fn cast(self) -> V {
+                self.to_f32().cast()
+            }
+        }
+
+        impl CastNum for $name { }
+
+        impl NumCast for $name
+        {
+            fn from<I>(val: I) -> $name
+                where I: CastNum {
+                $name::from_f32(val.cast())
+            }
+        }
+
+        impl Num for $name
+        {
+            fn abs(self) -> $name { $name::from_f32(self.to_f32().abs()) }
+        }
+    };
+    (op $name:ident, $tr:ident, $method:ident) => {
+        impl $tr for $name
+        {
+            type Output = $name;
+
+            fn $method(self, rhs: $name) -> $name {
+                $name::from_f32(self.to_f32().$method(rhs.to_f32()))
+            }
+        }
+    };
+    (signed $name:ident) => {
+        impl Neg for $name
+        {
+            type Output = $name;
+
+            fn neg(self) -> $name { $name::from_f32(-self.to_f32()) }
+        }
+
+        impl Signed for $name { }
+    };
+}
+
+impl_norm!(Unorm8, u8, 0.0, "An unsigned byte representing a fraction from 0 to 1.");
+impl_norm!(Unorm16, u16, 0.0, "An unsigned short representing a fraction from 0 to 1.");
+impl_norm!(Snorm8, i8, -1.0, "A signed byte representing a fraction from -1 to 1.");
+impl_norm!(Snorm16, i16, -1.0, "A signed short representing a fraction from -1 to 1.");
+
+impl_norm!(signed Snorm8);
+impl_norm!(signed Snorm16);
+
+/// Four signed normalised components packed into 32 bits, with 10 bits
+/// for each of X, Y and Z, and 2 bits for W.
+///
+/// The layout matches OpenGL's `INT_2_10_10_10_REV`, with X in the
+/// lowest bits. This is a compact format for normals and tangents.
+#[derive(Copy,Clone,Debug,Default,PartialEq,Eq)]
+pub struct Snorm10x3(pub u32);
+
+impl Snorm10x3
+{
+    /// Packs four fractions, clamping them to -1..1.
+    pub fn new(x: f32, y: f32, z: f32, w: f32) -> Self {
+        let pack = |value: f32, bits: u32, shift: u32| {
+            let value = if value < -1.0 { -1.0 } else if value > 1.0 { 1.0 } else { value };
+            let steps = ((1 << (bits - 1)) - 1) as f32;
+            let mask = (1u32 << bits) - 1;
+            (((value * steps).round() as i32) as u32 & mask) << shift
+        };
+
+        Snorm10x3(pack(x, 10, 0) | pack(y, 10, 10) | pack(z, 10, 20) | pack(w, 2, 30))
+    }
+
+    /// Unpacks the four fractions.
+    pub fn unpack(self) -> (f32,f32,f32,f32) {
+        let unpack = |bits: u32, shift: u32| {
+            // Shift the sign bit up to the top, and back to sign extend.
+            let value = ((self.0 << (32 - bits - shift)) as i32) >> (32 - bits);
+            let value = value as f32 / ((1 << (bits - 1)) - 1) as f32;
+            if value < -1.0 { -1.0 } else { value }
+        };
+
+        (unpack(10, 0), unpack(10, 10), unpack(10, 20), unpack(2, 30))
+    }
+}
+
+#[test]
+fn test_norm_conversion() {
+    assert_eq!(Unorm8::from_f32(1.0), Unorm8(255));
+    assert_eq!(Unorm8::from_f32(0.5), Unorm8(128));
+    assert_eq!(Unorm8::from_f32(-3.0), Unorm8(0));
+    assert_eq!(Snorm16::from_f32(-1.0), Snorm16(-32767));
+    assert_eq!(Snorm8(-128).to_f32(), -1.0);
+    assert_eq!(Snorm8(127).to_f32(), 1.0);
+
+    // Arithmetic saturates.
+    assert_eq!(Unorm8(200) + Unorm8(200), Unorm8(255));
+    assert_eq!(Unorm16::one() * Unorm16(1000), Unorm16(1000));
+    assert_eq!(-Snorm8(127), Snorm8(-127));
+    assert_eq!(::cast::<f64,Snorm8>(-0.5), Snorm8(-64));
+}
+
+#[test]
+fn test_packed_snorm() {
+    let packed = Snorm10x3::new(1.0, -1.0, 0.0, -1.0);
+    assert_eq!(packed.unpack(), (1.0, -1.0, 0.0, -1.0));
+
+    let (x, y, z, w) = Snorm10x3::new(0.25, -0.5, 0.75, 1.0).unpack();
+    assert!((x - 0.25).abs() < 1.0 / 511.0 && (y + 0.5).abs() < 1.0 / 511.0);
+    assert!((z - 0.75).abs() < 1.0 / 511.0 && w == 1.0);
+    assert_eq!(Snorm10x3::new(1.0, 0.0, 0.0, 0.0).0, 511);
+}
@@ -0,0 +1,75 @@
+//! Seedable, deterministic random number generation.
+//!
+//! The same seed always gives the same sequence, on every platform, which
+//! makes the generators suitable for procedural generation and for tests.
+
+extern crate num;
+extern crate math;
+extern crate geom;
+
+pub mod pcg;
+pub mod uniform;
+pub mod sample;
+
+pub use self::pcg::Pcg32;
+pub use self::uniform::Uniform;
+
+/// A source of random bits.
+pub trait Rng
+{
+    /// Generates 32 random bits.
+    fn next_u32(&mut self) -> u32;
+
+    /// Generates 64 random bits.
+    fn next_u64(&mut self) -> u64 {
+        let high = self.next_u32() as u64;
+        let low = self.next_u32() as u64;
+
+        (high << 32) | low
+    }
+
+    /// Generates a number in the half-open range `low..high`.
+    fn range<T: Uniform>(&mut self, low: T, high: T) -> T
+        where Self: Sized {
+        T::sample(self, low, high)
+    }
+
+    /// Generates a decimal in the range `0..1`.
+    fn unit<T: Uniform + num::Decimal>(&mut self) -> T
+        where Self: Sized {
+        self.range(T::zero(), T::one())
+    }
+
+    /// Generates `true` with the given probability.
+    fn chance(&mut self, probability: f64) -> bool
+        where Self: Sized {
+        self.unit::<f64>() < probability
+    }
+
+    /// Shuffles a slice in place.
+    fn shuffle<E>(&mut self, elements: &mut [E])
+        where Self: Sized {
+        for i in (1..elements.len()).rev() {
+            let j = self.range(0, i + 1);
+            elements.swap(i, j);
+        }
+    }
+}
+
+impl<'a, R: Rng + ?Sized> Rng for &'a mut R
+{
+    fn next_u32(&mut self) -> u32 { (**self).next_u32() }
+    fn next_u64(&mut self) -> u64 { (**self).next_u64() }
+}
+
+#[test]
+fn test_shuffle_is_permutation() {
+    let mut rng = Pcg32::new(11);
+    let mut values: Vec<u32> = (0..50).collect();
+
+    rng.shuffle(&mut values);
+    assert!(values != (0..50).collect::<Vec<_>>());
+
+    values.sort();
+    assert_eq!(values, (0..50).collect::<Vec<_>>());
+}
@@ -0,0 +1,72 @@
+//! The PCG family of generators.
+
+use Rng;
+
+const MULTIPLIER: u64 = 6364136223846793005;
+const DEFAULT_STREAM: u64 = 0xda3e39cb94b95bdb;
+
+/// The `PCG-XSH-RR` generator, with 64 bits of state and 32 bits of output.
+///
+/// It is small, fast, and has good statistical quality, but is not
+/// cryptographically secure.
+#[derive(Copy,Clone,Debug,PartialEq,Eq)]
+pub struct Pcg32
+{
+    state: u64,
+    /// The stream selector. This is always odd.
+    increment: u64,
+}
+
+impl Pcg32
+{
+    /// Creates a generator from a seed.
+    pub fn new(seed: u64) -> Self {
+        Pcg32::with_stream(seed, DEFAULT_STREAM)
+    }
+
+    /// Creates a generator from a seed and a stream number.
+    ///
+    /// Generators with the same seed but different streams give
+    /// independent sequences.
+    pub fn with_stream(seed: u64, stream: u64) -> Self {
+        let mut rng = Pcg32 {
+            state: 0,
+            increment: (stream << 1) | 1,
+        };
+
+        rng.step();
+        rng.state = rng.state.wrapping_add(seed);
+        rng.step();
+        rng
+    }
+
+    fn step(&mut self) {
+        self.state = self.state.wrapping_mul(MULTIPLIER).wrapping_add(self.increment);
+    }
+}
+
+impl Rng for Pcg32
+{
+    fn next_u32(&mut self) -> u32 {
+        let old = self.state;
+        self.step();
+
+        let xorshifted = (((old >> 18) ^ old) >> 27) as u32;
+        let rotation = (old >> 59) as u32;
+
+        xorshifted.rotate_right(rotation)
+    }
+}
+
+#[test]
+fn test_pcg32_reference() {
+    // The first outputs of the reference implementation's demo,
+    // which seeds with 42 on stream 54.
+    let mut rng = Pcg32::with_stream(42, 54);
+    let expected = [0xa15c02b7, 0x7b47f409, 0xba1d3330,
+                    0x83d2f293, 0xbfa4784b, 0xcbed606e];
+
+    for &value in expected.iter() {
+        assert_eq!(rng.next_u32(), value);
+    }
+}
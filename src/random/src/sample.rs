@@ -0,0 +1,236 @@
+//! Random geometric samples.
+
+use {Rng,Uniform};
+use math::{Vector2,Vector3,Quaternion};
+use geom::{Vertex,Triangle,Aabb};
+use num::{self,Decimal,Signed};
+use std;
+
+/// The number of candidates tried around each point in Poisson-disc sampling.
+const POISSON_ATTEMPTS: usize = 30;
+
+/// Generates a point inside a sphere.
+pub fn in_sphere<T,R>(rng: &mut R, center: Vector3<T>, radius: T) -> Vector3<T>
+    where T: Uniform + Decimal + Signed, R: Rng {
+    let one = T::one();
+
+    loop {
+        let point = Vector3(rng.range(-one, one),
+                            rng.range(-one, one),
+                            rng.range(-one, one));
+
+        if point.length_squared() <= one {
+            return center + point * radius;
+        }
+    }
+}
+
+/// Generates a point on the surface of a sphere.
+pub fn on_sphere<T,R>(rng: &mut R, center: Vector3<T>, radius: T) -> Vector3<T>
+    where T: Uniform + Decimal + Signed, R: Rng {
+    let z = rng.range(-T::one(), T::one());
+    let (sin,cos) = rng.range(T::zero(), T::tau()).sincos();
+    let r = (T::one() - z*z).sqrt();
+
+    center + Vector3(r * cos, r * sin, z) * radius
+}
+
+/// Generates a point inside a disc.
+pub fn in_disc<T,R>(rng: &mut R, center: Vector2<T>, radius: T) -> Vector2<T>
+    where T: Uniform + Decimal, R: Rng {
+    // The square root compensates for the area growing with the radius.
+    let r = radius * rng.unit::<T>().sqrt();
+    let (sin,cos) = rng.range(T::zero(), T::tau()).sincos();
+
+    center + Vector2(r * cos, r * sin)
+}
+
+/// Generates a point inside a triangle.
+pub fn in_triangle<V,R>(rng: &mut R, triangle: &Triangle<V>) -> Vector3<V::T>
+    where V: Vertex, V::T: Uniform + Decimal, R: Rng {
+    let points: Vec<_> = triangle.points().map(|p| p.coords()).collect();
+    let one: V::T = num::one();
+    let (mut u, mut v) = (rng.unit::<V::T>(), rng.unit::<V::T>());
+
+    // Fold points from the far half of the parallelogram back in.
+    if u + v > one {
+        u = one - u;
+        v = one - v;
+    }
+
+    points[0] + (points[1] - points[0]) * u + (points[2] - points[0]) * v
+}
+
+/// Generates a point inside a bounding box.
+pub fn in_aabb<T,R>(rng: &mut R, aabb: &Aabb<T>) -> Vector3<T>
+    where T: Uniform, R: Rng {
+    let (min,max) = (aabb.min(), aabb.max());
+
+    Vector3(between(rng, min.0, max.0),
+            between(rng, min.1, max.1),
+            between(rng, min.2, max.2))
+}
+
+/// Generates a rotation, with every orientation equally likely.
+pub fn quaternion<T,R>(rng: &mut R) -> Quaternion<T>
+    where T: Uniform + Decimal, R: Rng {
+    // Shoemake's method.
+    let u = rng.unit::<T>();
+    let (sin1,cos1) = (rng.unit::<T>() * T::tau()).sincos();
+    let (sin2,cos2) = (rng.unit::<T>() * T::tau()).sincos();
+
+    let (a,b) = ((T::one() - u).sqrt(), u.sqrt());
+
+    Quaternion(a * sin1, a * cos1, b * sin2, b * cos2)
+}
+
+/// Generates points inside a rectangle, where no two points are closer
+/// than `radius`, and no more points can be added.
+///
+/// This uses Bridson's algorithm.
+pub fn poisson_disc<T,R>(rng: &mut R,
+                         min: Vector2<T>,
+                         max: Vector2<T>,
+                         radius: T) -> Vec<Vector2<T>>
+    where T: Uniform + Decimal + Signed, R: Rng {
+    assert!(radius > T::zero(), "the radius must be positive");
+
+    // Each grid cell is small enough to hold at most one point.
+    let cell_size = radius / T::constant(2.0).sqrt();
+    let size = max - min;
+    let columns: usize = num::cast((size.0 / cell_size).ceil());
+    let rows: usize = num::cast((size.1 / cell_size).ceil());
+
+    let cell_of = |point: Vector2<T>| -> (usize, usize) {
+        let column: usize = num::cast(((point.0 - min.0) / cell_size).floor());
+        let row: usize = num::cast(((point.1 - min.1) / cell_size).floor());
+
+        (std::cmp::min(column, columns - 1), std::cmp::min(row, rows - 1))
+    };
+
+    if columns == 0 || rows == 0 {
+        return Vec::new();
+    }
+
+    let mut grid: Vec<Option<usize>> = vec![None; columns * rows];
+    let mut points = Vec::new();
+    let mut active = Vec::new();
+
+    let first = Vector2(between(rng, min.0, max.0), between(rng, min.1, max.1));
+    let (column, row) = cell_of(first);
+    grid[row * columns + column] = Some(0);
+    points.push(first);
+    active.push(0);
+
+    while !active.is_empty() {
+        let index = rng.range(0, active.len());
+        let origin = points[active[index]];
+        let mut found = false;
+
+        for _ in 0..POISSON_ATTEMPTS {
+            // Try a point in the annulus between one and two radii away.
+            let distance = rng.range(radius, radius + radius);
+            let (sin,cos) = rng.range(T::zero(), T::tau()).sincos();
+            let candidate = origin + Vector2(cos * distance, sin * distance);
+
+            if candidate.0 < min.0 || candidate.0 >= max.0 ||
+               candidate.1 < min.1 || candidate.1 >= max.1 {
+                continue;
+            }
+
+            let (column, row) = cell_of(candidate);
+            let neighbours = (column.saturating_sub(2)..std::cmp::min(column + 3, columns))
+                .flat_map(|c| (row.saturating_sub(2)..std::cmp::min(row + 3, rows)).map(move |r| (c, r)));
+
+            let clear = neighbours.filter_map(|(c,r)| grid[r * columns + c]).all(|other| {
+                (points[other] - candidate).length_squared() >= radius * radius
+            });
+
+            if clear {
+                grid[row * columns + column] = Some(points.len());
+                active.push(points.len());
+                points.push(candidate);
+                found = true;
+                break;
+            }
+        }
+
+        if !found {
+            active.swap_remove(index);
+        }
+    }
+
+    points
+}
+
+/// Samples from `low..high`, or gives `low` if the range is empty.
+fn between<T: Uniform, R: Rng>(rng: &mut R, low: T, high: T) -> T {
+    if low < high { rng.range(low, high) } else { low }
+}
+
+#[cfg(test)]
+fn rng() -> ::Pcg32 { ::Pcg32::new(1234) }
+
+#[test]
+fn test_sphere_samples() {
+    let mut rng = rng();
+    let center = Vector3(1.0f64, -2.0, 3.0);
+
+    for _ in 0..500 {
+        let inside = in_sphere(&mut rng, center, 2.0);
+        assert!((inside - center).length_squared() <= 4.0);
+
+        let surface = on_sphere(&mut rng, center, 2.0);
+        assert!(((surface - center).length_squared() - 4.0).abs() < 1.0e-9);
+    }
+}
+
+#[test]
+fn test_area_samples() {
+    let mut rng = rng();
+    let triangle = Triangle::new(Vector3(0.0f64, 0.0, 0.0),
+                                 Vector3(1.0, 0.0, 0.0),
+                                 Vector3(0.0, 1.0, 0.0));
+    let aabb = Aabb::new(Vector3(0.0f32, 5.0, 0.0), Vector3(1.0, 2.0, 0.0));
+
+    for _ in 0..500 {
+        let point = in_disc(&mut rng, Vector2(0.0f64, 0.0), 3.0);
+        assert!(point.length_squared() <= 9.0);
+
+        let point = in_triangle(&mut rng, &triangle);
+        assert!(point.0 >= 0.0 && point.1 >= 0.0 && point.0 + point.1 <= 1.0);
+        assert_eq!(point.2, 0.0);
+
+        let point = in_aabb(&mut rng, &aabb);
+        assert!(aabb.contains(point));
+    }
+}
+
+#[test]
+fn test_quaternion_is_unit() {
+    let mut rng = rng();
+
+    for _ in 0..100 {
+        let q: Quaternion<f64> = quaternion(&mut rng);
+        let length_squared = q.fold(0.0, |acc, c| acc + c*c);
+
+        assert!((length_squared - 1.0).abs() < 1.0e-9);
+    }
+}
+
+#[test]
+fn test_poisson_disc_spacing() {
+    let points = poisson_disc(&mut rng(), Vector2(0.0f64, 0.0), Vector2(10.0, 5.0), 0.5);
+    assert!(points.len() > 50);
+
+    for (i, &a) in points.iter().enumerate() {
+        assert!(a.0 >= 0.0 && a.0 < 10.0 && a.1 >= 0.0 && a.1 < 5.0);
+
+        for &b in points[i+1..].iter() {
+            assert!((a - b).length_squared() >= 0.25);
+        }
+    }
+
+    let again = poisson_disc(&mut rng(), Vector2(0.0f64, 0.0), Vector2(10.0, 5.0), 0.5);
+    assert_eq!(points.len(), again.len());
+}
@@ -0,0 +1,119 @@
+//! Uniformly distributed numbers.
+
+use Rng;
+use num::Num;
+
+/// A number which can be sampled uniformly from a range.
+pub trait Uniform : Num
+{
+    /// Samples a number in the half-open range `low..high`.
+    ///
+    /// Panics if the range is empty.
+    fn sample<R: Rng + ?Sized>(rng: &mut R, low: Self, high: Self) -> Self;
+}
+
+/// Generates an unbiased number in the range `0..span`.
+fn below<R: Rng + ?Sized>(rng: &mut R, span: u64) -> u64 {
+    // Reject the values which would make some results more likely.
+    let threshold = span.wrapping_neg() % span;
+
+    loop {
+        let value = rng.next_u64();
+
+        if value >= threshold {
+            return value % span;
+        }
+    }
+}
+
+macro_rules! impl_uniform_integer {
+    ($ty:ident, $unsigned:ident) => {
+        impl Uniform for $ty
+        {
+            fn sample<R: Rng + ?Sized>(rng: &mut R, low: $ty, high: $ty) -> $ty {
+                assert!(low < high, "cannot sample from an empty range");
+
+                let span = high.wrapping_sub(low) as $unsigned as u64;
+                low.wrapping_add(below(rng, span) as $ty)
+            }
+        }
+    }
+}
+
+macro_rules! impl_uniform_decimal {
+    ($ty:ident, $next:ident, $shift:expr, $bits:expr) => {
+        impl Uniform for $ty
+        {
+            fn sample<R: Rng + ?Sized>(rng: &mut R, low: $ty, high: $ty) -> $ty {
+                assert!(low < high, "cannot sample from an empty range");
+
+                loop {
+                    // Use as many bits as fit in the mantissa.
+                    let unit = (rng.$next() >> $shift) as $ty / (1u64 << $bits) as $ty;
+                    let value = low + (high - low) * unit;
+
+                    // Rounding can occasionally land on the upper bound.
+                    if value < high {
+                        return value;
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl_uniform_integer!(u8, u8);
+impl_uniform_integer!(u16, u16);
+impl_uniform_integer!(u32, u32);
+impl_uniform_integer!(u64, u64);
+impl_uniform_integer!(usize, usize);
+
+impl_uniform_integer!(i8, u8);
+impl_uniform_integer!(i16, u16);
+impl_uniform_integer!(i32, u32);
+impl_uniform_integer!(i64, u64);
+impl_uniform_integer!(isize, usize);
+
+impl_uniform_decimal!(f32, next_u32, 8, 24);
+impl_uniform_decimal!(f64, next_u64, 11, 53);
+
+#[test]
+fn test_uniform_integer_ranges() {
+    use Pcg32;
+
+    let mut rng = Pcg32::new(3);
+    let mut seen = [false; 7];
+
+    for _ in 0..1000 {
+        let value: i8 = rng.range(-3, 4);
+        assert!(value >= -3 && value < 4);
+        seen[(value + 3) as usize] = true;
+
+        let value: u8 = rng.range(250, 255);
+        assert!(value >= 250 && value < 255);
+
+        let value: i64 = rng.range(i64::min_value(), i64::max_value());
+        assert!(value < i64::max_value());
+    }
+
+    assert!(seen.iter().all(|&s| s));
+}
+
+#[test]
+fn test_uniform_decimal_ranges() {
+    use Pcg32;
+
+    let mut rng = Pcg32::new(8);
+    let mut sum = 0.0;
+
+    for _ in 0..10000 {
+        let value: f64 = rng.unit();
+        assert!(value >= 0.0 && value < 1.0);
+        sum += value;
+
+        let value: f32 = rng.range(-2.5, -2.0);
+        assert!(value >= -2.5 && value < -2.0);
+    }
+
+    assert!((sum / 10000.0 - 0.5).abs() < 0.02);
+}